@@ -0,0 +1,39 @@
+use bwenv::bitwarden::provider::{fetch_ordered, DEFAULT_CONCURRENCY};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Secrets fetched per iteration, one request each like `SdkProvider`
+const SECRET_COUNT: usize = 100;
+
+/// Stand-in for the round trip of one `secrets.get` request
+const REQUEST_LATENCY: Duration = Duration::from_millis(1);
+
+async fn fetch_secret(index: usize) -> (String, String) {
+    tokio::time::sleep(REQUEST_LATENCY).await;
+    (format!("VAR_{:03}", index), format!("value_{}", index))
+}
+
+fn bench_fetch_100_secrets(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("fetch_100_secrets");
+    group.sample_size(10);
+
+    for concurrency in [1, DEFAULT_CONCURRENCY] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(concurrency),
+            &concurrency,
+            |b, &concurrency| {
+                b.iter(|| {
+                    let secrets =
+                        runtime.block_on(fetch_ordered(0..SECRET_COUNT, concurrency, fetch_secret));
+                    black_box(secrets);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fetch_100_secrets);
+criterion_main!(benches);
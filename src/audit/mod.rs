@@ -0,0 +1,238 @@
+//! Audit module - Append-only log of changes made to Bitwarden
+//!
+//! Each mutation is appended to the log as one JSON object per line, naming
+//! the affected key but never its value. Unlike the debug log, the audit log
+//! is meant to be kept.
+
+use crate::bitwarden::provider::SyncResult;
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the audit log
+pub const AUDIT_LOG_ENV: &str = "BWENV_AUDIT_LOG";
+
+/// What a mutation did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    CreateProject,
+    Create,
+    Update,
+    Delete,
+    /// A create or update that failed before its kind was known
+    Write,
+}
+
+/// Whether a mutation took effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    pub project_id: String,
+    /// The affected key, absent for project-level actions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEntry {
+    pub fn new(
+        command: &str,
+        project_id: &str,
+        key: Option<&str>,
+        action: AuditAction,
+        outcome: AuditOutcome,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            project_id: project_id.to_string(),
+            key: key.map(str::to_string),
+            action,
+            outcome,
+        }
+    }
+}
+
+/// An append-only JSON lines audit log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a single entry
+    pub fn record(
+        &self,
+        command: &str,
+        project_id: &str,
+        key: Option<&str>,
+        action: AuditAction,
+        outcome: AuditOutcome,
+    ) -> Result<()> {
+        self.append(&[AuditEntry::new(command, project_id, key, action, outcome)])
+    }
+
+    /// Append an entry for each write of a sync
+    ///
+    /// Skipped and unchanged keys were not written and are not logged. Errors
+    /// of failed writes can echo request data, so only their keys are kept.
+    pub fn record_sync(&self, command: &str, project_id: &str, result: &SyncResult) -> Result<()> {
+        let succeeded = [
+            (AuditAction::Create, &result.created),
+            (AuditAction::Update, &result.updated),
+            (AuditAction::Delete, &result.pruned),
+        ];
+        let mut entries: Vec<AuditEntry> = succeeded
+            .into_iter()
+            .flat_map(|(action, keys)| {
+                keys.iter().map(move |key| {
+                    AuditEntry::new(
+                        command,
+                        project_id,
+                        Some(key),
+                        action,
+                        AuditOutcome::Success,
+                    )
+                })
+            })
+            .collect();
+        entries.extend(result.failed.iter().map(|failure| {
+            AuditEntry::new(
+                command,
+                project_id,
+                Some(&failure.key),
+                AuditAction::Write,
+                AuditOutcome::Failure,
+            )
+        }));
+        self.append(&entries)
+    }
+
+    /// Append entries in one write
+    pub fn append(&self, entries: &[AuditEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+        let mut file = open_append_private(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        Ok(())
+    }
+
+    /// Read every entry, oldest first
+    pub fn read(&self) -> Result<Vec<AuditEntry>> {
+        let content = fs::read_to_string(&self.path)?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+}
+
+/// Open a file for appending with owner-only permissions
+fn open_append_private(path: &Path) -> Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        // Tighten permissions of a log that already existed
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(options.open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_appends_json_lines() {
+        let dir = tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+
+        log.record(
+            "push",
+            "proj_1",
+            Some("A"),
+            AuditAction::Create,
+            AuditOutcome::Success,
+        )
+        .unwrap();
+        log.record(
+            "prune",
+            "proj_1",
+            Some("B"),
+            AuditAction::Delete,
+            AuditOutcome::Success,
+        )
+        .unwrap();
+
+        let entries = log.read().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "push");
+        assert_eq!(entries[1].key.as_deref(), Some("B"));
+        assert_eq!(entries[1].action, AuditAction::Delete);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_audit_log_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        AuditLog::new(&path)
+            .record(
+                "push",
+                "proj_1",
+                Some("A"),
+                AuditAction::Update,
+                AuditOutcome::Success,
+            )
+            .unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -3,10 +3,14 @@
 //! In-memory mock implementation for deterministic testing
 
 use async_trait::async_trait;
-use std::collections::HashMap;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
-use super::provider::{Project, Secret, SecretsProvider};
+use super::provider::{
+    unique_by_name, Project, RevisionEvent, Secret, SecretRevision, SecretsProvider,
+    DEFAULT_CONCURRENCY,
+};
 use crate::{AppError, Result};
 
 /// Mock implementation of SecretsProvider for testing
@@ -21,6 +25,25 @@ struct MockState {
     secrets: HashMap<String, Secret>,
     next_secret_id: usize,
     next_project_id: usize,
+    failing_keys: HashSet<String>,
+    history: HashMap<String, Vec<SecretRevision>>,
+    concurrency: Option<usize>,
+    writes_in_flight: usize,
+    max_writes_in_flight: usize,
+}
+
+impl MockState {
+    /// Record a synthetic revision attributed to the mock user
+    fn record(&mut self, secret_id: &str, event: RevisionEvent) {
+        self.history
+            .entry(secret_id.to_string())
+            .or_default()
+            .push(SecretRevision {
+                event,
+                changed_at: Utc::now(),
+                changed_by: Some("mock-user".to_string()),
+            });
+    }
 }
 
 impl MockProvider {
@@ -57,12 +80,57 @@ impl MockProvider {
         state.projects.insert(project.id.clone(), project);
     }
 
+    /// A provider holding only the project `id` named `name`
+    pub fn with_project(id: &str, name: &str) -> Self {
+        let provider = Self::new();
+        provider.add_project(Self::project(id, name));
+        provider
+    }
+
+    /// A writable project `id` named `name` in organization `org_1`
+    pub fn project(id: &str, name: &str) -> Project {
+        Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            organization_id: "org_1".to_string(),
+            access: None,
+        }
+    }
+
     /// Add a secret to the mock provider
     pub fn add_secret(&self, secret: Secret) {
         let mut state = self.state.lock().unwrap();
         state.secrets.insert(secret.id.clone(), secret);
     }
 
+    /// Make every create, update or delete of `key` fail (for testing error paths)
+    pub fn fail_writes_for(&self, key: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.failing_keys.insert(key.to_string());
+    }
+
+    /// Report `concurrency` from [`SecretsProvider::concurrency`]
+    pub fn set_concurrency(&self, concurrency: usize) {
+        self.state.lock().unwrap().concurrency = Some(concurrency);
+    }
+
+    /// Most creates and updates that were ever running at the same time
+    pub fn max_concurrent_writes(&self) -> usize {
+        self.state.lock().unwrap().max_writes_in_flight
+    }
+
+    /// Count a write as running until the returned guard is dropped,
+    /// yielding once so that concurrent writes overlap
+    async fn begin_write(&self) -> WriteGuard {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.writes_in_flight += 1;
+            state.max_writes_in_flight = state.max_writes_in_flight.max(state.writes_in_flight);
+        }
+        tokio::task::yield_now().await;
+        WriteGuard(self.state.clone())
+    }
+
     /// Get all secrets (for testing purposes)
     pub fn get_all_secrets(&self) -> Vec<Secret> {
         let state = self.state.lock().unwrap();
@@ -74,11 +142,21 @@ impl MockProvider {
         let mut state = self.state.lock().unwrap();
         state.projects.clear();
         state.secrets.clear();
+        state.failing_keys.clear();
         state.next_secret_id = 1;
         state.next_project_id = 1;
     }
 }
 
+/// A running write of [`MockProvider::begin_write`]
+struct WriteGuard(Arc<Mutex<MockState>>);
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().writes_in_flight -= 1;
+    }
+}
+
 impl Default for MockProvider {
     fn default() -> Self {
         Self::new()
@@ -92,6 +170,11 @@ impl SecretsProvider for MockProvider {
         Ok(state.projects.values().cloned().collect())
     }
 
+    fn concurrency(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.concurrency.unwrap_or(DEFAULT_CONCURRENCY)
+    }
+
     async fn get_project(&self, project_id: &str) -> Result<Option<Project>> {
         let state = self.state.lock().unwrap();
         Ok(state.projects.get(project_id).cloned())
@@ -99,7 +182,23 @@ impl SecretsProvider for MockProvider {
 
     async fn get_project_by_name(&self, name: &str) -> Result<Option<Project>> {
         let state = self.state.lock().unwrap();
-        Ok(state.projects.values().find(|p| p.name == name).cloned())
+        unique_by_name(state.projects.values().cloned().collect(), name)
+    }
+
+    async fn create_project(&self, name: &str) -> Result<Project> {
+        let mut state = self.state.lock().unwrap();
+
+        let project_id = format!("mock_project_{}", state.next_project_id);
+        state.next_project_id += 1;
+
+        let project = Project {
+            id: project_id.clone(),
+            name: name.to_string(),
+            organization_id: "mock_org".to_string(),
+            access: None,
+        };
+        state.projects.insert(project_id, project.clone());
+        Ok(project)
     }
 
     async fn list_secrets(&self, project_id: &str) -> Result<Vec<Secret>> {
@@ -124,8 +223,13 @@ impl SecretsProvider for MockProvider {
         value: &str,
         note: Option<&str>,
     ) -> Result<Secret> {
+        let _write = self.begin_write().await;
         let mut state = self.state.lock().unwrap();
 
+        if state.failing_keys.contains(key) {
+            return Err(AppError::Unknown(format!("Simulated failure for {}", key)));
+        }
+
         // Verify project exists
         if !state.projects.contains_key(project_id) {
             return Err(AppError::ItemNotFound(format!(
@@ -158,6 +262,7 @@ impl SecretsProvider for MockProvider {
             project_id: project_id.to_string(),
         };
 
+        state.record(&secret_id, RevisionEvent::Created);
         state.secrets.insert(secret_id, secret.clone());
         Ok(secret)
     }
@@ -169,8 +274,13 @@ impl SecretsProvider for MockProvider {
         value: &str,
         note: Option<&str>,
     ) -> Result<Secret> {
+        let _write = self.begin_write().await;
         let mut state = self.state.lock().unwrap();
 
+        if state.failing_keys.contains(key) {
+            return Err(AppError::Unknown(format!("Simulated failure for {}", key)));
+        }
+
         let existing = state
             .secrets
             .get(secret_id)
@@ -200,18 +310,68 @@ impl SecretsProvider for MockProvider {
             project_id: existing.project_id,
         };
 
+        state.record(secret_id, RevisionEvent::Updated);
         state.secrets.insert(secret_id.to_string(), updated.clone());
         Ok(updated)
     }
 
+    async fn list_secret_history(&self, secret_id: &str) -> Result<Vec<SecretRevision>> {
+        let state = self.state.lock().unwrap();
+        if !state.secrets.contains_key(secret_id) {
+            return Err(AppError::ItemNotFound(format!(
+                "Secret not found: {}",
+                secret_id
+            )));
+        }
+        Ok(state.history.get(secret_id).cloned().unwrap_or_default())
+    }
+
     async fn delete_secret(&self, secret_id: &str) -> Result<()> {
         let mut state = self.state.lock().unwrap();
 
-        if state.secrets.remove(secret_id).is_none() {
+        let Some(secret) = state.secrets.get(secret_id) else {
             return Err(AppError::ItemNotFound(format!(
                 "Secret not found: {}",
                 secret_id
             )));
+        };
+        if state.failing_keys.contains(&secret.key) {
+            return Err(AppError::Unknown(format!(
+                "Simulated failure for {}",
+                secret.key
+            )));
+        }
+
+        state.secrets.remove(secret_id);
+        Ok(())
+    }
+
+    async fn delete_secrets(&self, secret_ids: &[&str]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        // Fail without deleting anything if any ID is unknown
+        if let Some(missing) = secret_ids
+            .iter()
+            .find(|id| !state.secrets.contains_key(**id))
+        {
+            return Err(AppError::ItemNotFound(format!(
+                "Secret not found: {}",
+                missing
+            )));
+        }
+        if let Some(failing) = secret_ids
+            .iter()
+            .map(|id| &state.secrets[*id].key)
+            .find(|key| state.failing_keys.contains(*key))
+        {
+            return Err(AppError::Unknown(format!(
+                "Simulated failure for {}",
+                failing
+            )));
+        }
+
+        for secret_id in secret_ids {
+            state.secrets.remove(*secret_id);
         }
 
         Ok(())
@@ -221,12 +381,14 @@ impl SecretsProvider for MockProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bitwarden::provider::SyncOptions;
 
     fn create_test_project() -> Project {
         Project {
             id: "proj_1".to_string(),
             name: "Test Project".to_string(),
             organization_id: "org_1".to_string(),
+            access: None,
         }
     }
 
@@ -248,6 +410,19 @@ mod tests {
         assert_eq!(projects[0].name, "Test Project");
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_lists_and_finds_in_large_project_list() {
+        // The SDK returns all projects in one response; lookups must see all
+        let projects = (0..2500)
+            .map(|i| MockProvider::project(&format!("proj_{}", i), &format!("Service {}", i)))
+            .collect();
+        let provider = MockProvider::with_data(projects, Vec::new());
+
+        assert_eq!(provider.list_projects().await.unwrap().len(), 2500);
+        let last = provider.get_project_by_name("Service 2499").await.unwrap();
+        assert_eq!(last.unwrap().id, "proj_2499");
+    }
+
     #[tokio::test]
     async fn test_mock_provider_get_project() {
         let provider = MockProvider::new();
@@ -259,6 +434,23 @@ mod tests {
         assert_eq!(found.unwrap().name, "Test Project");
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_get_project_misses_are_not_errors() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        // A name is not an ID
+        assert_eq!(provider.get_project("Test Project").await.unwrap(), None);
+        // A well-formed ID that matches nothing
+        assert_eq!(
+            provider
+                .get_project("48b4774c-68ca-4539-a3d7-ac00018b4377")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
     #[tokio::test]
     async fn test_mock_provider_get_project_by_name() {
         let provider = MockProvider::new();
@@ -270,6 +462,35 @@ mod tests {
         assert_eq!(found.unwrap().id, "proj_1");
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_create_project() {
+        let provider = MockProvider::new();
+
+        let created = provider.create_project("New Service").await.unwrap();
+
+        let found = provider.get_project_by_name("New Service").await.unwrap();
+        assert_eq!(found, Some(created));
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_get_project_by_ambiguous_name() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider.add_project(MockProvider::project("proj_2", "Test Project"));
+
+        let result = provider.get_project_by_name("Test Project").await;
+
+        match result {
+            Err(AppError::InvalidArguments(msg)) => {
+                assert!(msg.contains("proj_1"));
+                assert!(msg.contains("proj_2"));
+            }
+            other => panic!("Expected InvalidArguments, got {:?}", other),
+        }
+        // The IDs still resolve unambiguously
+        assert!(provider.get_project("proj_2").await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_mock_provider_create_secret() {
         let provider = MockProvider::new();
@@ -439,6 +660,89 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_create_or_update_secret_creates() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let (secret, created) = provider
+            .create_or_update_secret("proj_1", "API_KEY", "secret123", Some("note"))
+            .await
+            .unwrap();
+
+        assert!(created);
+        assert_eq!(secret.value, "secret123");
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_create_or_update_secret_updates() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let original = provider
+            .create_secret("proj_1", "API_KEY", "old_value", Some("keep me"))
+            .await
+            .unwrap();
+
+        let (secret, created) = provider
+            .create_or_update_secret("proj_1", "API_KEY", "new_value", None)
+            .await
+            .unwrap();
+
+        assert!(!created);
+        assert_eq!(secret.id, original.id);
+        assert_eq!(secret.value, "new_value");
+        assert_eq!(secret.note, Some("keep me".to_string()));
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_delete_secrets_batch() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let first = provider
+            .create_secret("proj_1", "KEY1", "value1", None)
+            .await
+            .unwrap();
+        let second = provider
+            .create_secret("proj_1", "KEY2", "value2", None)
+            .await
+            .unwrap();
+        provider
+            .create_secret("proj_1", "KEY3", "value3", None)
+            .await
+            .unwrap();
+
+        provider
+            .delete_secrets(&[first.id.as_str(), second.id.as_str()])
+            .await
+            .unwrap();
+
+        let secrets = provider.list_secrets("proj_1").await.unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].key, "KEY3");
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_delete_secrets_unknown_id_deletes_nothing() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let created = provider
+            .create_secret("proj_1", "KEY1", "value1", None)
+            .await
+            .unwrap();
+
+        let result = provider
+            .delete_secrets(&[created.id.as_str(), "nonexistent"])
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_mock_provider_get_secrets_map() {
         let provider = MockProvider::new();
@@ -460,6 +764,43 @@ mod tests {
         assert_eq!(map.get("KEY2"), Some(&"value2".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_project_exists() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        assert!(provider.project_exists("proj_1").await.unwrap());
+        assert!(provider.project_exists("Test Project").await.unwrap());
+        assert!(!provider.project_exists("proj_missing").await.unwrap());
+        assert!(!provider.project_exists("Missing Project").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_get_secrets_by_keys() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        for (key, value) in [("KEY1", "value1"), ("KEY2", "value2"), ("KEY3", "value3")] {
+            provider
+                .create_secret("proj_1", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let map = provider
+            .get_secrets_by_keys("proj_1", &["KEY1", "KEY3", "MISSING"])
+            .await
+            .unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("KEY1"), Some(&"value1".to_string()));
+        assert_eq!(map.get("KEY3"), Some(&"value3".to_string()));
+
+        let none = provider
+            .get_secrets_by_keys("proj_1", &["MISSING"])
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
     #[tokio::test]
     async fn test_mock_provider_sync_secrets_create() {
         let provider = MockProvider::new();
@@ -470,11 +811,13 @@ mod tests {
         secrets.insert("KEY1".to_string(), "value1".to_string());
         secrets.insert("KEY2".to_string(), "value2".to_string());
 
-        let results = provider
+        let result = provider
             .sync_secrets("proj_1", &secrets, false)
             .await
             .unwrap();
-        assert_eq!(results.len(), 2);
+        assert_eq!(result.created, vec!["KEY1".to_string(), "KEY2".to_string()]);
+        assert!(result.updated.is_empty());
+        assert!(result.skipped.is_empty());
 
         let all_secrets = provider.list_secrets("proj_1").await.unwrap();
         assert_eq!(all_secrets.len(), 2);
@@ -496,15 +839,48 @@ mod tests {
         let mut secrets = HashMap::new();
         secrets.insert("KEY1".to_string(), "new_value".to_string());
 
-        provider
+        let result = provider
             .sync_secrets("proj_1", &secrets, true)
             .await
             .unwrap();
+        assert_eq!(result.updated, vec!["KEY1".to_string()]);
 
         let map = provider.get_secrets_map("proj_1").await.unwrap();
         assert_eq!(map.get("KEY1"), Some(&"new_value".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_skips_identical_updates() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let mut secrets = HashMap::new();
+        secrets.insert("KEY1".to_string(), "value1".to_string());
+        secrets.insert("KEY2".to_string(), "value2".to_string());
+
+        let first = provider
+            .sync_secrets("proj_1", &secrets, true)
+            .await
+            .unwrap();
+        assert_eq!(first.created.len(), 2);
+
+        let second = provider
+            .sync_secrets("proj_1", &secrets, true)
+            .await
+            .unwrap();
+        assert!(second.updated.is_empty());
+        assert_eq!(
+            second.unchanged,
+            vec!["KEY1".to_string(), "KEY2".to_string()]
+        );
+
+        // No update reached the provider
+        for secret in provider.list_secrets("proj_1").await.unwrap() {
+            let history = provider.list_secret_history(&secret.id).await.unwrap();
+            assert!(history.iter().all(|r| r.event != RevisionEvent::Updated));
+        }
+    }
+
     #[tokio::test]
     async fn test_mock_provider_sync_secrets_no_overwrite() {
         let provider = MockProvider::new();
@@ -521,15 +897,203 @@ mod tests {
         let mut secrets = HashMap::new();
         secrets.insert("KEY1".to_string(), "new_value".to_string());
 
-        provider
+        let result = provider
             .sync_secrets("proj_1", &secrets, false)
             .await
             .unwrap();
+        assert_eq!(result.skipped, vec!["KEY1".to_string()]);
 
         let map = provider.get_secrets_map("proj_1").await.unwrap();
         assert_eq!(map.get("KEY1"), Some(&"old_value".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_collects_failures() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider.fail_writes_for("BAD_KEY");
+
+        let mut secrets = HashMap::new();
+        secrets.insert("KEY1".to_string(), "value1".to_string());
+        secrets.insert("BAD_KEY".to_string(), "value".to_string());
+        secrets.insert("KEY2".to_string(), "value2".to_string());
+
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &SyncOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.created, vec!["KEY1".to_string(), "KEY2".to_string()]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].key, "BAD_KEY");
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_fail_writes_for_delete_secret() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        let secret = provider
+            .create_secret("proj_1", "BAD_KEY", "value", None)
+            .await
+            .unwrap();
+        provider.fail_writes_for("BAD_KEY");
+
+        assert!(provider.delete_secret(&secret.id).await.is_err());
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_fail_fast() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider.fail_writes_for("BAD_KEY");
+
+        let mut secrets: HashMap<String, String> = (0..3)
+            .map(|i| (format!("KEY{}", i), format!("value{}", i)))
+            .collect();
+        secrets.insert("BAD_KEY".to_string(), "value".to_string());
+
+        let options = SyncOptions {
+            fail_fast: true,
+            chunk_size: 2,
+            ..Default::default()
+        };
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &options)
+            .await
+            .unwrap();
+
+        // The failing key's chunk completes; later chunks are not started
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].key, "BAD_KEY");
+        assert!(!result.created.is_empty());
+        assert_eq!(result.created.len() + result.not_attempted.len(), 3);
+        assert_eq!(
+            provider.list_secrets("proj_1").await.unwrap().len(),
+            result.created.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_small_chunks() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider
+            .create_secret("proj_1", "KEY0", "old", None)
+            .await
+            .unwrap();
+
+        let secrets: HashMap<String, String> = (0..5)
+            .map(|i| (format!("KEY{}", i), format!("value{}", i)))
+            .collect();
+
+        let options = SyncOptions {
+            overwrite: true,
+            chunk_size: 2,
+            ..Default::default()
+        };
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(result.created.len(), 4);
+        assert_eq!(result.updated, vec!["KEY0".to_string()]);
+        assert_eq!(provider.get_secrets_map("proj_1").await.unwrap(), secrets);
+    }
+
+    #[tokio::test]
+    async fn test_sync_honors_provider_concurrency() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider.set_concurrency(3);
+
+        let secrets: HashMap<String, String> = (0..7)
+            .map(|i| (format!("KEY{}", i), format!("value{}", i)))
+            .collect();
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &SyncOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.created.len(), 7);
+        assert_eq!(provider.max_concurrent_writes(), 3);
+
+        // An explicit chunk size takes precedence
+        let options = SyncOptions {
+            overwrite: true,
+            chunk_size: 5,
+            ..Default::default()
+        };
+        let changed: HashMap<String, String> = secrets
+            .keys()
+            .map(|key| (key.clone(), "new".to_string()))
+            .collect();
+        provider
+            .sync_secrets_with("proj_1", &changed, &options)
+            .await
+            .unwrap();
+        assert_eq!(provider.max_concurrent_writes(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_interrupted() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+
+        let secrets: HashMap<String, String> = (0..3)
+            .map(|i| (format!("KEY{}", i), format!("value{}", i)))
+            .collect();
+
+        let options = SyncOptions::default();
+        options.interrupt.trigger();
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &options)
+            .await
+            .unwrap();
+
+        assert!(result.was_interrupted());
+        assert_eq!(result.not_attempted.len(), 3);
+        assert!(provider.list_secrets("proj_1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_sync_secrets_add_only_missing() {
+        let provider = MockProvider::new();
+        provider.add_project(create_test_project());
+        provider
+            .create_secret("proj_1", "PRESENT", "remote", None)
+            .await
+            .unwrap();
+
+        let mut secrets = HashMap::new();
+        secrets.insert("PRESENT".to_string(), "local".to_string());
+        secrets.insert("ABSENT_A".to_string(), "a".to_string());
+        secrets.insert("ABSENT_B".to_string(), "b".to_string());
+
+        let options = SyncOptions {
+            overwrite: true,
+            add_only_missing: true,
+            ..Default::default()
+        };
+        let result = provider
+            .sync_secrets_with("proj_1", &secrets, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.created,
+            vec!["ABSENT_A".to_string(), "ABSENT_B".to_string()]
+        );
+        assert!(result.updated.is_empty());
+        assert!(result.skipped.is_empty());
+
+        let remote = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(remote["PRESENT"], "remote");
+        assert_eq!(remote["ABSENT_A"], "a");
+    }
+
     #[tokio::test]
     async fn test_mock_provider_clear() {
         let provider = MockProvider::new();
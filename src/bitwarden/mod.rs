@@ -3,13 +3,18 @@
 //! Provides high-level API for interacting with Bitwarden Secrets Manager.
 
 pub mod provider;
+pub mod retry;
 pub mod sdk_provider;
+pub mod token;
 
 #[cfg(test)]
 pub mod mock_provider;
 
 // Re-export commonly used types
-pub use provider::{Project, Secret, SecretsProvider};
+pub use provider::{
+    Project, ProjectAccess, Secret, SecretsProvider, SyncFailure, SyncOptions, SyncResult,
+};
+pub use retry::RetryPolicy;
 pub use sdk_provider::SdkProvider;
 
 #[cfg(test)]
@@ -3,10 +3,18 @@
 //! Defines the interface for interacting with secrets providers (SDK, mock, etc.)
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+use log::debug;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
 
-use crate::Result;
+use crate::interrupt::Interrupt;
+use crate::{AppError, Result};
 
 /// Represents a Bitwarden project containing secrets
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -14,6 +22,24 @@ pub struct Project {
     pub id: String,
     pub name: String,
     pub organization_id: String,
+    /// What the token may do in the project, when the provider reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<ProjectAccess>,
+}
+
+impl Project {
+    /// Whether the token is known to be unable to write secrets here
+    pub fn is_read_only(&self) -> bool {
+        self.access == Some(ProjectAccess::Read)
+    }
+}
+
+/// A token's permission on a project
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectAccess {
+    Read,
+    ReadWrite,
 }
 
 /// Represents a secret in Bitwarden Secrets Manager
@@ -26,28 +52,265 @@ pub struct Secret {
     pub project_id: String,
 }
 
+impl Secret {
+    /// Whether two secrets hold the same key, value and note, regardless of
+    /// their IDs and project
+    pub fn same_content(&self, other: &Secret) -> bool {
+        self.key == other.key && self.value == other.value && self.note == other.note
+    }
+}
+
+/// What happened to a secret at a point in its history
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RevisionEvent {
+    Created,
+    Updated,
+}
+
+impl fmt::Display for RevisionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevisionEvent::Created => write!(f, "created"),
+            RevisionEvent::Updated => write!(f, "updated"),
+        }
+    }
+}
+
+/// One change to a secret; never carries the value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SecretRevision {
+    pub event: RevisionEvent,
+    pub changed_at: DateTime<Utc>,
+    /// Who made the change, when the provider knows
+    pub changed_by: Option<String>,
+}
+
+/// Outcome of a bulk sync, listing the affected keys by action taken
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncResult {
+    /// Keys that did not exist remotely and were created
+    pub created: Vec<String>,
+    /// Keys that existed remotely and were overwritten
+    pub updated: Vec<String>,
+    /// Keys that existed remotely and were left untouched
+    pub skipped: Vec<String>,
+    /// Keys that already had the pushed value and note, so were not written
+    #[serde(default)]
+    pub unchanged: Vec<String>,
+    /// Keys that were removed remotely
+    pub pruned: Vec<String>,
+    /// Keys whose create or update failed
+    pub failed: Vec<SyncFailure>,
+    /// Keys never sent because the sync was interrupted, or stopped by
+    /// `fail_fast` after a failure
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_attempted: Vec<String>,
+}
+
+/// A key that could not be synced, with the provider error
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncFailure {
+    pub key: String,
+    pub error: String,
+}
+
+/// Requests run concurrently by default, both when fetching secrets and
+/// when syncing them; see `--concurrency`
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Run `fetch` for each of `items`, at most `concurrency` at a time, and
+/// return the results in the order of `items`
+///
+/// Providers that need one request per secret fetch them through this.
+pub async fn fetch_ordered<I, F, Fut>(items: I, concurrency: usize, fetch: F) -> Vec<Fut::Output>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future,
+{
+    stream::iter(items)
+        .map(fetch)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Pause between two chunks of a bulk sync
+const CHUNK_PAUSE: Duration = Duration::from_millis(100);
+
+/// Options for a bulk sync
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Overwrite values of keys that already exist remotely
+    pub overwrite: bool,
+    /// Start no further chunk after a failed key instead of syncing the rest
+    pub fail_fast: bool,
+    /// Only create absent keys; existing keys are left alone and not reported
+    pub add_only_missing: bool,
+    /// Creates and updates run concurrently in sequential chunks of this
+    /// size; 0 uses the provider's [`SecretsProvider::concurrency`]
+    pub chunk_size: usize,
+    /// Once triggered, no further chunk is started
+    pub interrupt: Interrupt,
+    /// Notes to store, by key; written keys without one keep their note
+    pub notes: HashMap<String, String>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            overwrite: false,
+            fail_fast: false,
+            add_only_missing: false,
+            chunk_size: 0,
+            interrupt: Interrupt::new(),
+            notes: HashMap::new(),
+        }
+    }
+}
+
+/// A remote write planned by a bulk sync
+enum SyncOp<'a> {
+    Create,
+    Update(&'a Secret),
+}
+
+impl SyncResult {
+    /// Total number of keys processed
+    pub fn total(&self) -> usize {
+        self.created.len()
+            + self.updated.len()
+            + self.skipped.len()
+            + self.unchanged.len()
+            + self.pruned.len()
+            + self.failed.len()
+            + self.not_attempted.len()
+    }
+
+    /// Whether the sync stopped early, on an interrupt or after a failure
+    /// with `fail_fast`
+    pub fn was_interrupted(&self) -> bool {
+        !self.not_attempted.is_empty()
+    }
+
+    /// Whether any key failed to sync
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+
+    /// Sort each key list for stable output
+    fn sort(&mut self) {
+        self.created.sort();
+        self.updated.sort();
+        self.skipped.sort();
+        self.unchanged.sort();
+        self.pruned.sort();
+        self.failed.sort_by(|a, b| a.key.cmp(&b.key));
+        self.not_attempted.sort();
+    }
+}
+
+/// Pick the single project called `name`
+///
+/// Bitwarden allows duplicate project names, so several matches are an
+/// [`AppError::InvalidArguments`] listing their IDs rather than a guess.
+pub fn unique_by_name(projects: Vec<Project>, name: &str) -> Result<Option<Project>> {
+    let mut matches: Vec<Project> = projects.into_iter().filter(|p| p.name == name).collect();
+    if matches.len() > 1 {
+        let mut ids: Vec<&str> = matches.iter().map(|p| p.id.as_str()).collect();
+        ids.sort();
+        return Err(AppError::InvalidArguments(format!(
+            "{} projects are named '{}' ({}); pass the project ID with --project",
+            matches.len(),
+            name,
+            ids.join(", ")
+        )));
+    }
+    Ok(matches.pop())
+}
+
 /// Trait for secrets provider implementations
 ///
 /// This trait abstracts the interaction with Bitwarden Secrets Manager,
 /// enabling testing with mock implementations and production use with the SDK.
 #[async_trait]
 pub trait SecretsProvider: Send + Sync {
+    /// Most requests to run at once when fetching or syncing many secrets
+    fn concurrency(&self) -> usize {
+        DEFAULT_CONCURRENCY
+    }
+
     /// List all accessible projects
     async fn list_projects(&self) -> Result<Vec<Project>>;
 
     /// Get a specific project by ID
+    ///
+    /// Returns `Ok(None)` both for an ID that matches no project and for input
+    /// that is not an ID at all, such as a project name, so callers can fall
+    /// back to [`Self::get_project_by_name`].
     async fn get_project(&self, project_id: &str) -> Result<Option<Project>>;
 
     /// Get a project by name
+    ///
+    /// Fails with [`AppError::InvalidArguments`] when the name is ambiguous.
     async fn get_project_by_name(&self, name: &str) -> Result<Option<Project>>;
 
+    /// Whether a project with this ID or name exists
+    ///
+    /// Resolves by ID first, then by name, without fetching any secrets.
+    async fn project_exists(&self, project_id_or_name: &str) -> Result<bool> {
+        if self.get_project(project_id_or_name).await?.is_some() {
+            return Ok(true);
+        }
+        Ok(self
+            .get_project_by_name(project_id_or_name)
+            .await?
+            .is_some())
+    }
+
+    /// Create a project in the organization
+    ///
+    /// The default implementation reports that creating projects is unsupported.
+    async fn create_project(&self, name: &str) -> Result<Project> {
+        Err(AppError::Unknown(format!(
+            "Creating projects is not supported by this provider (project {})",
+            name
+        )))
+    }
+
     /// List all secrets in a project
     async fn list_secrets(&self, project_id: &str) -> Result<Vec<Secret>>;
 
     /// Get secrets as a HashMap for easy .env conversion
     async fn get_secrets_map(&self, project_id: &str) -> Result<HashMap<String, String>> {
+        let started = Instant::now();
         let secrets = self.list_secrets(project_id).await?;
-        Ok(secrets.into_iter().map(|s| (s.key, s.value)).collect())
+        let map: HashMap<String, String> = secrets.into_iter().map(|s| (s.key, s.value)).collect();
+        debug!(
+            "Mapped {} secrets of project {} in {:.2?}",
+            map.len(),
+            project_id,
+            started.elapsed()
+        );
+        Ok(map)
+    }
+
+    /// Get the values of `keys` in a project, omitting keys that don't exist
+    ///
+    /// The default implementation filters [`Self::list_secrets`]; providers
+    /// able to fetch a subset directly can override it.
+    async fn get_secrets_by_keys(
+        &self,
+        project_id: &str,
+        keys: &[&str],
+    ) -> Result<HashMap<String, String>> {
+        Ok(self
+            .list_secrets(project_id)
+            .await?
+            .into_iter()
+            .filter(|s| keys.contains(&s.key.as_str()))
+            .map(|s| (s.key, s.value))
+            .collect())
     }
 
     /// Get a specific secret by ID
@@ -74,44 +337,180 @@ pub trait SecretsProvider: Send + Sync {
     /// Delete a secret
     async fn delete_secret(&self, secret_id: &str) -> Result<()>;
 
+    /// List the changes made to a secret, oldest first
+    ///
+    /// The default implementation reports that history is unsupported.
+    async fn list_secret_history(&self, secret_id: &str) -> Result<Vec<SecretRevision>> {
+        Err(AppError::Unknown(format!(
+            "Secret history is not supported by this provider (secret {})",
+            secret_id
+        )))
+    }
+
+    /// Delete several secrets in one operation
+    ///
+    /// The default implementation deletes one at a time; providers with a
+    /// batch API should override it.
+    async fn delete_secrets(&self, secret_ids: &[&str]) -> Result<()> {
+        for secret_id in secret_ids {
+            self.delete_secret(secret_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Create a secret, or update it if the key already exists in the project
+    ///
+    /// Returns the resulting secret and `true` if it was newly created.
+    /// When updating with `note` set to `None`, the existing note is kept.
+    async fn create_or_update_secret(
+        &self,
+        project_id: &str,
+        key: &str,
+        value: &str,
+        note: Option<&str>,
+    ) -> Result<(Secret, bool)> {
+        let existing = self
+            .list_secrets(project_id)
+            .await?
+            .into_iter()
+            .find(|s| s.key == key);
+
+        match existing {
+            Some(secret) => {
+                let note = note.or(secret.note.as_deref());
+                let updated = self.update_secret(&secret.id, key, value, note).await?;
+                Ok((updated, false))
+            }
+            None => {
+                let created = self.create_secret(project_id, key, value, note).await?;
+                Ok((created, true))
+            }
+        }
+    }
+
     /// Bulk update or create secrets (used for push operations)
+    ///
+    /// Stops after the first chunk with a failure; see
+    /// [`SecretsProvider::sync_secrets_with`] to sync every key instead.
     async fn sync_secrets(
         &self,
         project_id: &str,
         secrets: &HashMap<String, String>,
         overwrite: bool,
-    ) -> Result<Vec<Secret>> {
+    ) -> Result<SyncResult> {
+        let options = SyncOptions {
+            overwrite,
+            fail_fast: true,
+            ..Default::default()
+        };
+        self.sync_secrets_with(project_id, secrets, &options).await
+    }
+
+    /// Bulk update or create secrets with explicit sync options
+    ///
+    /// Creates and updates run concurrently, `chunk_size` (or
+    /// [`Self::concurrency`]) at a time, with a
+    /// short pause between chunks. Unless `fail_fast` is set, keys that fail
+    /// are recorded in [`SyncResult::failed`] and the remaining keys are still
+    /// synced; with it, no further chunk is started after a failure. After a
+    /// failure with `fail_fast`, or once `interrupt` is triggered, the running
+    /// chunk completes and the remaining keys are reported in
+    /// [`SyncResult::not_attempted`].
+    async fn sync_secrets_with(
+        &self,
+        project_id: &str,
+        secrets: &HashMap<String, String>,
+        options: &SyncOptions,
+    ) -> Result<SyncResult> {
+        let started = Instant::now();
         let existing = self.list_secrets(project_id).await?;
-        let mut existing_map: HashMap<String, Secret> =
-            existing.into_iter().map(|s| (s.key.clone(), s)).collect();
+        let existing_map: HashMap<&str, &Secret> =
+            existing.iter().map(|s| (s.key.as_str(), s)).collect();
 
-        let mut results = Vec::new();
+        let mut result = SyncResult::default();
+        let mut ops = Vec::new();
 
         for (key, value) in secrets {
-            if let Some(existing_secret) = existing_map.remove(key) {
-                // Update existing secret
-                if overwrite {
-                    let updated = self
-                        .update_secret(
-                            &existing_secret.id,
-                            key,
-                            value,
-                            existing_secret.note.as_deref(),
-                        )
-                        .await?;
-                    results.push(updated);
-                } else {
-                    // Skip if not overwriting
-                    results.push(existing_secret);
+            match existing_map.get(key.as_str()) {
+                Some(_) if options.add_only_missing => {}
+                // Update existing secret, unless that would change nothing
+                Some(secret) if options.overwrite => {
+                    let desired = Secret {
+                        key: key.clone(),
+                        value: value.clone(),
+                        note: options.notes.get(key).cloned().or(secret.note.clone()),
+                        ..(*secret).clone()
+                    };
+                    if desired.same_content(secret) {
+                        result.unchanged.push(key.clone());
+                    } else {
+                        ops.push((key, value, SyncOp::Update(secret)));
+                    }
                 }
-            } else {
+                // Skip if not overwriting
+                Some(_) => result.skipped.push(key.clone()),
                 // Create new secret
-                let created = self.create_secret(project_id, key, value, None).await?;
-                results.push(created);
+                None => ops.push((key, value, SyncOp::Create)),
+            }
+        }
+
+        let chunk_size = match options.chunk_size {
+            0 => self.concurrency(),
+            size => size,
+        }
+        .max(1);
+        for (index, chunk) in ops.chunks(chunk_size).enumerate() {
+            let failed = options.fail_fast && result.has_failures();
+            if index > 0 && !failed {
+                tokio::time::sleep(CHUNK_PAUSE).await;
+            }
+            if failed || options.interrupt.is_triggered() {
+                let rest = &ops[index * chunk_size..];
+                result
+                    .not_attempted
+                    .extend(rest.iter().map(|(key, _, _)| (*key).clone()));
+                break;
+            }
+
+            let outcomes = join_all(chunk.iter().map(|(key, value, op)| async move {
+                let note = options.notes.get(*key).map(String::as_str);
+                match op {
+                    SyncOp::Create => self
+                        .create_secret(project_id, key, value, note)
+                        .await
+                        .map(|_| true),
+                    SyncOp::Update(secret) => self
+                        .update_secret(&secret.id, key, value, note.or(secret.note.as_deref()))
+                        .await
+                        .map(|_| false),
+                }
+            }))
+            .await;
+
+            for ((key, _, _), outcome) in chunk.iter().zip(outcomes) {
+                match outcome {
+                    Ok(true) => result.created.push((*key).clone()),
+                    Ok(false) => result.updated.push((*key).clone()),
+                    Err(e) => result.failed.push(SyncFailure {
+                        key: (*key).clone(),
+                        error: e.to_string(),
+                    }),
+                }
             }
         }
 
-        Ok(results)
+        result.sort();
+        debug!(
+            "Synced {} secrets to project {} in {:.2?}: {} created, {} updated, {} skipped, {} failed",
+            secrets.len(),
+            project_id,
+            started.elapsed(),
+            result.created.len(),
+            result.updated.len(),
+            result.skipped.len(),
+            result.failed.len()
+        );
+        Ok(result)
     }
 }
 
@@ -125,12 +524,42 @@ mod tests {
             id: "proj123".to_string(),
             name: "Test Project".to_string(),
             organization_id: "org456".to_string(),
+            access: None,
         };
 
         assert_eq!(project.id, "proj123");
         assert_eq!(project.name, "Test Project");
     }
 
+    #[test]
+    fn test_same_content_ignores_ids() {
+        let secret = Secret {
+            id: "sec123".to_string(),
+            key: "API_KEY".to_string(),
+            value: "secret_value".to_string(),
+            note: Some("note".to_string()),
+            project_id: "proj123".to_string(),
+        };
+        let copy = Secret {
+            id: "other".to_string(),
+            project_id: "proj456".to_string(),
+            ..secret.clone()
+        };
+        assert!(secret.same_content(&copy));
+        assert_ne!(secret, copy);
+
+        let renoted = Secret {
+            note: None,
+            ..secret.clone()
+        };
+        assert!(!secret.same_content(&renoted));
+        let revalued = Secret {
+            value: "new".to_string(),
+            ..secret.clone()
+        };
+        assert!(!secret.same_content(&revalued));
+    }
+
     #[test]
     fn test_secret_creation() {
         let secret = Secret {
@@ -152,6 +581,7 @@ mod tests {
             id: "proj123".to_string(),
             name: "Test Project".to_string(),
             organization_id: "org456".to_string(),
+            access: None,
         };
 
         let json = serde_json::to_string(&project).unwrap();
@@ -175,4 +605,47 @@ mod tests {
 
         assert_eq!(secret, deserialized);
     }
+
+    #[test]
+    fn test_sync_result_total() {
+        let result = SyncResult {
+            created: vec!["A".to_string(), "B".to_string()],
+            updated: vec!["C".to_string()],
+            skipped: vec!["D".to_string()],
+            unchanged: vec!["F".to_string()],
+            pruned: vec![],
+            failed: vec![SyncFailure {
+                key: "E".to_string(),
+                error: "boom".to_string(),
+            }],
+            not_attempted: vec![],
+        };
+
+        assert_eq!(result.total(), 6);
+        assert!(result.has_failures());
+        assert_eq!(SyncResult::default().total(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_ordered_keeps_order_and_limits_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = fetch_ordered(0..20u64, 4, |i| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later items finish first
+                tokio::time::sleep(Duration::from_millis(20 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .await;
+
+        assert_eq!(results, (0..20).collect::<Vec<_>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,246 @@
+//! Retry policy for transient Bitwarden API failures
+//!
+//! Read-only and authentication calls in [`super::SdkProvider`] are retried;
+//! writes are not, to avoid creating a secret twice. Only transient failures
+//! are retried - network errors, timeouts, rate limiting (429) and server
+//! errors (5xx); anything else, like a rejected token, fails at once.
+//!
+//! The delay doubles after each failure and, by default, a random delay up
+//! to that backoff is used instead ("full jitter"), so that many processes
+//! rate limited at the same moment don't all retry in lockstep.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use log::debug;
+use regex::Regex;
+use std::fmt;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Retries after the first attempt when not configured
+pub const DEFAULT_RETRY_COUNT: u32 = 3;
+
+/// Delay before the first retry in milliseconds when not configured
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 500;
+
+/// Upper bound of the backoff between two attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How often and how long to wait before retrying a failed call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub count: u32,
+    /// Delay before the first retry, doubled for each later one
+    pub delay: Duration,
+    /// Wait a random time up to the backoff instead of the full backoff
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_RETRY_COUNT,
+            delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy from a retry count and a delay in milliseconds
+    pub fn new(count: u32, delay_ms: u64) -> Self {
+        Self {
+            count,
+            delay: Duration::from_millis(delay_ms),
+            jitter: true,
+        }
+    }
+
+    /// The same policy waiting the exact backoff, e.g. for deterministic tests
+    pub fn without_jitter(self) -> Self {
+        Self {
+            jitter: false,
+            ..self
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based), capped at 30 seconds
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+
+    /// Time to wait before retry number `retry`
+    fn wait(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+        let random = OsRng.next_u64();
+        let millis = backoff.as_millis() as u64;
+        Duration::from_millis(random % (millis + 1))
+    }
+
+    /// Run `op`, retrying transient failures according to the policy
+    ///
+    /// Returns the first error that [`is_transient`] rejects, or the last
+    /// one once all retries are used up.
+    pub async fn run<T, E, F, Fut>(&self, mut op: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: fmt::Display,
+    {
+        let mut attempt = 0;
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.count && is_transient(&e.to_string()) => {
+                    attempt += 1;
+                    let wait = self.wait(attempt);
+                    debug!(
+                        "Bitwarden call failed ({}), retry {} of {} in {:?}",
+                        e, attempt, self.count, wait
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Whether an error message describes a failure worth retrying
+///
+/// The SDK reports HTTP and transport errors only as text, so this looks
+/// for a 429 or 5xx status, a timeout or a failed connection in it.
+pub fn is_transient(message: &str) -> bool {
+    static STATUS: OnceLock<Regex> = OnceLock::new();
+    let status = STATUS.get_or_init(|| Regex::new(r"\b(429|5\d\d)\b").unwrap());
+
+    let message = message.to_lowercase();
+    status.is_match(&message)
+        || [
+            "timed out",
+            "timeout",
+            "too many requests",
+            "connection",
+            "error sending request",
+            "dns error",
+            "network",
+        ]
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_failures() {
+        let calls = Cell::new(0);
+        let policy = RetryPolicy::new(3, 0);
+
+        let result: Result<u32, String> = policy
+            .run(|| async {
+                calls.set(calls.get() + 1);
+                if calls.get() < 3 {
+                    Err("[503 Service Unavailable]".to_string())
+                } else {
+                    Ok(calls.get())
+                }
+            })
+            .await;
+
+        assert_eq!(result, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_count() {
+        let calls = Cell::new(0);
+        let policy = RetryPolicy::new(2, 0);
+
+        let result: Result<(), String> = policy
+            .run(|| async {
+                calls.set(calls.get() + 1);
+                Err("error sending request: connection refused".to_string())
+            })
+            .await;
+
+        assert_eq!(
+            result,
+            Err("error sending request: connection refused".to_string())
+        );
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_error_is_attempted_once() {
+        let calls = Cell::new(0);
+        let policy = RetryPolicy::new(3, 0);
+
+        let result: Result<(), String> = policy
+            .run(|| async {
+                calls.set(calls.get() + 1);
+                Err("[401 Unauthorized] invalid access token".to_string())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_is_transient() {
+        for message in [
+            "[429 Too Many Requests]",
+            "[500 Internal Server Error]",
+            "[502 Bad Gateway]",
+            "operation timed out",
+            "error sending request for url (https://api.bitwarden.com/)",
+        ] {
+            assert!(is_transient(message), "{}", message);
+        }
+        for message in [
+            "[400 Bad Request]",
+            "[401 Unauthorized]",
+            "[404 Not Found]",
+            "Invalid access token",
+        ] {
+            assert!(!is_transient(message), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_default_policy_uses_constants() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.count, DEFAULT_RETRY_COUNT);
+        assert_eq!(policy.delay, Duration::from_millis(DEFAULT_RETRY_DELAY_MS));
+        assert!(policy.jitter);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy::new(10, 500).without_jitter();
+
+        assert_eq!(policy.wait(1), Duration::from_millis(500));
+        assert_eq!(policy.wait(2), Duration::from_millis(1000));
+        assert_eq!(policy.wait(3), Duration::from_millis(2000));
+        assert_eq!(policy.wait(10), MAX_RETRY_DELAY);
+        assert_eq!(policy.wait(u32::MAX), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_backoff() {
+        let policy = RetryPolicy::new(3, 500);
+
+        let waits: Vec<Duration> = (0..50).map(|_| policy.wait(3)).collect();
+        assert!(waits.iter().all(|wait| *wait <= policy.backoff(3)));
+        // 50 draws from 0..=2000ms all being equal would mean no jitter
+        assert!(waits.iter().any(|wait| *wait != waits[0]));
+    }
+}
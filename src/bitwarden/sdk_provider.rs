@@ -3,12 +3,16 @@
 //! Production implementation using the official Bitwarden Rust SDK
 
 use async_trait::async_trait;
+use log::{debug, trace};
+use std::fmt;
+use std::future::Future;
+use std::time::Instant;
 use uuid::Uuid;
 
 use bitwarden::{
     auth::login::AccessTokenLoginRequest,
     secrets_manager::{
-        projects::{ProjectGetRequest, ProjectsListRequest},
+        projects::{ProjectCreateRequest, ProjectGetRequest, ProjectsListRequest},
         secrets::{
             SecretCreateRequest, SecretGetRequest, SecretIdentifiersByProjectRequest,
             SecretPutRequest, SecretsDeleteRequest,
@@ -18,15 +22,76 @@ use bitwarden::{
     Client, ClientSettings, DeviceType,
 };
 
-use super::provider::{Project, Secret, SecretsProvider};
+use super::provider::{
+    fetch_ordered, unique_by_name, Project, RevisionEvent, Secret, SecretRevision, SecretsProvider,
+    DEFAULT_CONCURRENCY,
+};
+use super::retry::RetryPolicy;
+use crate::cache::projects::ProjectCache;
+use crate::logging;
 use crate::{AppError, Result};
 
+/// Run one SDK request, logging it at trace level before and after
+///
+/// Only the request and the IDs or key it targets are logged, never values,
+/// notes or the access token. Errors are redacted, as the SDK's can echo the
+/// request.
+async fn traced<T, E: fmt::Display>(
+    request: &str,
+    target: &str,
+    call: impl Future<Output = std::result::Result<T, E>>,
+) -> std::result::Result<T, E> {
+    trace!("{}", request_line(request, target));
+    let started = Instant::now();
+    let result = call.await;
+    let error = result.as_ref().err().map(|e| e.to_string());
+    trace!(
+        "{}",
+        outcome_line(request, target, error.as_deref(), started.elapsed())
+    );
+    result
+}
+
+fn request_line(request: &str, target: &str) -> String {
+    format!("-> {} {}", request, target)
+}
+
+fn outcome_line(
+    request: &str,
+    target: &str,
+    error: Option<&str>,
+    elapsed: std::time::Duration,
+) -> String {
+    match error {
+        None => format!("<- {} {} ok in {:.2?}", request, target, elapsed),
+        Some(error) => format!(
+            "<- {} {} failed in {:.2?}: {}",
+            request,
+            target,
+            elapsed,
+            logging::redact(error)
+        ),
+    }
+}
+
 /// SDK-based implementation using real Bitwarden SDK
+///
+/// The SDK's `Client` builds one `reqwest::Client` and shares it between
+/// the identity and API configurations, so every request of a provider,
+/// including the per-secret fetches of `list_secrets`, reuses its pooled
+/// keep-alive connections. Create one provider per run rather than one per
+/// call.
 #[derive(Debug)]
 pub struct SdkProvider {
     client: Client,
     /// Organization ID extracted from access token
     organization_id: Uuid,
+    /// Retry policy for authentication and read calls
+    retry: RetryPolicy,
+    /// Cache of project names resolved by `get_project_by_name`
+    project_cache: Option<ProjectCache>,
+    /// Most requests sent at once when fetching or syncing secrets
+    concurrency: usize,
 }
 
 impl SdkProvider {
@@ -34,6 +99,11 @@ impl SdkProvider {
     ///
     /// This will initialize the Bitwarden client and authenticate with the access token.
     pub async fn new(access_token: String) -> Result<Self> {
+        Self::with_retry_policy(access_token, RetryPolicy::default()).await
+    }
+
+    /// Create a new SDK provider that retries transient failures per `retry`
+    pub async fn with_retry_policy(access_token: String, retry: RetryPolicy) -> Result<Self> {
         // Parse the access token to extract organization ID
         let organization_id = Self::parse_organization_id(&access_token)?;
 
@@ -52,22 +122,43 @@ impl SdkProvider {
             state_file: None,
         };
 
-        client
-            .auth()
-            .login_access_token(&token_request)
+        let target = format!("organization {}", organization_id);
+        retry
+            .run(|| {
+                traced("auth.login_access_token", &target, async {
+                    client.auth().login_access_token(&token_request).await
+                })
+            })
             .await
             .map_err(|_| AppError::BitwardenAuthFailed)?;
 
         Ok(Self {
             client,
             organization_id,
+            retry,
+            project_cache: None,
+            concurrency: DEFAULT_CONCURRENCY,
         })
     }
 
+    /// Cache resolved project names on disk for `ttl`; `None` disables it
+    pub fn with_project_cache_ttl(mut self, ttl: Option<chrono::Duration>) -> Self {
+        self.project_cache =
+            ttl.map(|ttl| ProjectCache::new(&self.organization_id.to_string(), ttl));
+        self
+    }
+
+    /// Send at most `concurrency` requests at once when fetching or syncing
+    /// many secrets
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
     /// Parse organization ID from access token
     ///
     /// Bitwarden access tokens have the format: {version}.{org_id}.{data}
-    fn parse_organization_id(access_token: &str) -> Result<Uuid> {
+    pub(crate) fn parse_organization_id(access_token: &str) -> Result<Uuid> {
         let parts: Vec<&str> = access_token.split('.').collect();
         if parts.len() < 2 {
             return Err(AppError::BitwardenAuthFailed);
@@ -76,6 +167,29 @@ impl SdkProvider {
         Uuid::parse_str(parts[1]).map_err(|_| AppError::BitwardenAuthFailed)
     }
 
+    /// Parse a project ID, or `None` if `project_id` is not a UUID
+    fn parse_project_id(project_id: &str) -> Option<Uuid> {
+        Uuid::parse_str(project_id).ok()
+    }
+
+    /// Fail naming every ID a bulk delete reported an error for
+    ///
+    /// A bulk delete succeeds as a request even when some of its secrets
+    /// could not be deleted, e.g. for lack of access.
+    fn check_deleted<'a>(results: impl IntoIterator<Item = (Uuid, Option<&'a str>)>) -> Result<()> {
+        let failed: Vec<String> = results
+            .into_iter()
+            .filter_map(|(id, error)| error.map(|error| format!("{} ({})", id, error)))
+            .collect();
+        if failed.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::Unknown(format!(
+            "Failed to delete secrets: {}",
+            failed.join(", ")
+        )))
+    }
+
     /// Convert SDK Project to our Project type
     fn convert_project(
         sdk_project: bitwarden::secrets_manager::projects::ProjectResponse,
@@ -84,6 +198,8 @@ impl SdkProvider {
             id: sdk_project.id.to_string(),
             name: sdk_project.name,
             organization_id: sdk_project.organization_id.to_string(),
+            // The SDK's project responses don't say what the token may do
+            access: None,
         }
     }
 
@@ -108,15 +224,26 @@ impl SdkProvider {
 
 #[async_trait]
 impl SecretsProvider for SdkProvider {
+    fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// `ProjectsListRequest` has no paging parameters and `ProjectsResponse`
+    /// no continuation token: the API returns every project the access token
+    /// can read in one response, so `data` is the complete list.
     async fn list_projects(&self) -> Result<Vec<Project>> {
         let request = ProjectsListRequest {
             organization_id: self.organization_id,
         };
 
+        let target = format!("organization {}", self.organization_id);
         let response = self
-            .client
-            .projects()
-            .list(&request)
+            .retry
+            .run(|| {
+                traced("projects.list", &target, async {
+                    self.client.projects().list(&request).await
+                })
+            })
             .await
             .map_err(|e| AppError::Unknown(format!("Failed to list projects: {}", e)))?;
 
@@ -128,21 +255,60 @@ impl SecretsProvider for SdkProvider {
     }
 
     async fn get_project(&self, project_id: &str) -> Result<Option<Project>> {
-        let uuid = Uuid::parse_str(project_id).map_err(|_| {
-            AppError::InvalidArguments(format!("Invalid project ID: {}", project_id))
-        })?;
+        // Not an ID, most likely a name; no project can match it
+        let Some(uuid) = Self::parse_project_id(project_id) else {
+            return Ok(None);
+        };
 
         let request = ProjectGetRequest { id: uuid };
 
-        match self.client.projects().get(&request).await {
+        let target = format!("project {}", uuid);
+        match traced(
+            "projects.get",
+            &target,
+            self.client.projects().get(&request),
+        )
+        .await
+        {
             Ok(project) => Ok(Some(Self::convert_project(project))),
             Err(_) => Ok(None),
         }
     }
 
     async fn get_project_by_name(&self, name: &str) -> Result<Option<Project>> {
+        if let Some(project) = self.project_cache.as_ref().and_then(|c| c.lookup(name)) {
+            debug!("Resolved project {} from the project cache", name);
+            return Ok(Some(project));
+        }
+
         let projects = self.list_projects().await?;
-        Ok(projects.into_iter().find(|p| p.name == name))
+        if let Some(cache) = &self.project_cache {
+            if let Err(e) = cache.store(&projects) {
+                debug!("Failed to write the project cache: {}", e);
+            }
+        }
+        unique_by_name(projects, name)
+    }
+
+    async fn create_project(&self, name: &str) -> Result<Project> {
+        let request = ProjectCreateRequest {
+            organization_id: self.organization_id,
+            name: name.to_string(),
+        };
+
+        let target = format!("project named {}", name);
+        let project = traced(
+            "projects.create",
+            &target,
+            self.client.projects().create(&request),
+        )
+        .await
+        .map_err(|e| AppError::Unknown(format!("Failed to create project: {}", e)))?;
+        if let Some(cache) = &self.project_cache {
+            cache.invalidate();
+        }
+
+        Ok(Self::convert_project(project))
     }
 
     async fn list_secrets(&self, project_id: &str) -> Result<Vec<Secret>> {
@@ -151,26 +317,62 @@ impl SecretsProvider for SdkProvider {
         })?;
 
         let request = SecretIdentifiersByProjectRequest { project_id: uuid };
+        let started = Instant::now();
 
+        let target = format!("project {}", uuid);
         let identifiers = self
-            .client
-            .secrets()
-            .list_by_project(&request)
+            .retry
+            .run(|| {
+                traced("secrets.list_by_project", &target, async {
+                    self.client.secrets().list_by_project(&request).await
+                })
+            })
             .await
             .map_err(|e| AppError::Unknown(format!("Failed to list secrets: {}", e)))?;
 
-        // For each identifier, fetch the full secret
+        debug!(
+            "Listed {} secret identifiers of project {} in {:.2?}",
+            identifiers.data.len(),
+            project_id,
+            started.elapsed()
+        );
+
+        // Fetch the full secrets, `concurrency` at a time, keeping their order
+        let fetch_started = Instant::now();
+        let fetched = fetch_ordered(
+            identifiers.data,
+            self.concurrency,
+            |identifier| async move {
+                let secret_request = SecretGetRequest { id: identifier.id };
+                let target = format!("secret {}", identifier.id);
+                let result = self
+                    .retry
+                    .run(|| {
+                        traced("secrets.get", &target, async {
+                            self.client.secrets().get(&secret_request).await
+                        })
+                    })
+                    .await;
+                (identifier.id, result)
+            },
+        )
+        .await;
         let mut secrets = Vec::new();
-        for identifier in identifiers.data {
-            let secret_request = SecretGetRequest { id: identifier.id };
-            match self.client.secrets().get(&secret_request).await {
+        for (id, result) in fetched {
+            match result {
                 Ok(secret) => secrets.push(Self::convert_secret(secret)),
                 Err(e) => {
                     // Log error but continue
-                    eprintln!("Warning: Failed to fetch secret {}: {}", identifier.id, e);
+                    eprintln!("Warning: Failed to fetch secret {}: {}", id, e);
                 }
             }
         }
+        debug!(
+            "Fetched {} secrets in {:.2?} ({:.2?} total)",
+            secrets.len(),
+            fetch_started.elapsed(),
+            started.elapsed()
+        );
 
         Ok(secrets)
     }
@@ -180,13 +382,47 @@ impl SecretsProvider for SdkProvider {
             .map_err(|_| AppError::InvalidArguments(format!("Invalid secret ID: {}", secret_id)))?;
 
         let request = SecretGetRequest { id: uuid };
+        let target = format!("secret {}", uuid);
 
-        match self.client.secrets().get(&request).await {
+        match traced("secrets.get", &target, self.client.secrets().get(&request)).await {
             Ok(secret) => Ok(Some(Self::convert_secret(secret))),
             Err(_) => Ok(None),
         }
     }
 
+    /// Secrets Manager keeps no revision log, so the history is the creation
+    /// and, if different, the latest revision; the author is not exposed
+    async fn list_secret_history(&self, secret_id: &str) -> Result<Vec<SecretRevision>> {
+        let uuid = Uuid::parse_str(secret_id)
+            .map_err(|_| AppError::InvalidArguments(format!("Invalid secret ID: {}", secret_id)))?;
+
+        let request = SecretGetRequest { id: uuid };
+        let target = format!("secret {}", uuid);
+        let secret = self
+            .retry
+            .run(|| {
+                traced("secrets.get", &target, async {
+                    self.client.secrets().get(&request).await
+                })
+            })
+            .await
+            .map_err(|e| AppError::Unknown(format!("Failed to get secret: {}", e)))?;
+
+        let mut history = vec![SecretRevision {
+            event: RevisionEvent::Created,
+            changed_at: secret.creation_date,
+            changed_by: None,
+        }];
+        if secret.revision_date != secret.creation_date {
+            history.push(SecretRevision {
+                event: RevisionEvent::Updated,
+                changed_at: secret.revision_date,
+                changed_by: None,
+            });
+        }
+        Ok(history)
+    }
+
     async fn create_secret(
         &self,
         project_id: &str,
@@ -206,12 +442,14 @@ impl SecretsProvider for SdkProvider {
             project_ids: Some(vec![project_uuid]),
         };
 
-        let secret = self
-            .client
-            .secrets()
-            .create(&request)
-            .await
-            .map_err(|e| AppError::Unknown(format!("Failed to create secret: {}", e)))?;
+        let target = format!("key {} in project {}", key, project_uuid);
+        let secret = traced(
+            "secrets.create",
+            &target,
+            self.client.secrets().create(&request),
+        )
+        .await
+        .map_err(|e| AppError::Unknown(format!("Failed to create secret: {}", e)))?;
 
         Ok(Self::convert_secret(secret))
     }
@@ -247,12 +485,14 @@ impl SecretsProvider for SdkProvider {
             project_ids,
         };
 
-        let secret = self
-            .client
-            .secrets()
-            .update(&request)
-            .await
-            .map_err(|e| AppError::Unknown(format!("Failed to update secret: {}", e)))?;
+        let target = format!("secret {} ({})", uuid, key);
+        let secret = traced(
+            "secrets.update",
+            &target,
+            self.client.secrets().update(&request),
+        )
+        .await
+        .map_err(|e| AppError::Unknown(format!("Failed to update secret: {}", e)))?;
 
         Ok(Self::convert_secret(secret))
     }
@@ -262,20 +502,56 @@ impl SecretsProvider for SdkProvider {
             .map_err(|_| AppError::InvalidArguments(format!("Invalid secret ID: {}", secret_id)))?;
 
         let request = SecretsDeleteRequest { ids: vec![uuid] };
+        let target = format!("secret {}", uuid);
 
-        self.client
-            .secrets()
-            .delete(request)
-            .await
-            .map_err(|e| AppError::Unknown(format!("Failed to delete secret: {}", e)))?;
+        traced(
+            "secrets.delete",
+            &target,
+            self.client.secrets().delete(request),
+        )
+        .await
+        .map_err(|e| AppError::Unknown(format!("Failed to delete secret: {}", e)))?;
 
         Ok(())
     }
+
+    async fn delete_secrets(&self, secret_ids: &[&str]) -> Result<()> {
+        if secret_ids.is_empty() {
+            return Ok(());
+        }
+
+        let ids = secret_ids
+            .iter()
+            .map(|id| {
+                Uuid::parse_str(id)
+                    .map_err(|_| AppError::InvalidArguments(format!("Invalid secret ID: {}", id)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let target = format!("secrets {}", secret_ids.join(", "));
+        let request = SecretsDeleteRequest { ids };
+
+        let response = traced(
+            "secrets.delete",
+            &target,
+            self.client.secrets().delete(request),
+        )
+        .await
+        .map_err(|e| AppError::Unknown(format!("Failed to delete secrets: {}", e)))?;
+
+        Self::check_deleted(
+            response
+                .data
+                .iter()
+                .map(|entry| (entry.id, entry.error.as_deref())),
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_parse_organization_id() {
@@ -284,10 +560,63 @@ mod tests {
         assert_eq!(org_id.to_string(), "48b4774c-68ca-4539-a3d7-ac00018b4377");
     }
 
+    #[test]
+    fn test_parse_project_id_accepts_uuid() {
+        let id = "48b4774c-68ca-4539-a3d7-ac00018b4377";
+        assert_eq!(
+            SdkProvider::parse_project_id(id).map(|u| u.to_string()),
+            Some(id.to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_project_id_rejects_names() {
+        assert_eq!(SdkProvider::parse_project_id("My App"), None);
+        assert_eq!(SdkProvider::parse_project_id(""), None);
+    }
+
     #[test]
     fn test_parse_organization_id_invalid() {
         let token = "invalid_token";
         let result = SdkProvider::parse_organization_id(token);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_check_deleted_names_failed_ids() {
+        let ok = Uuid::parse_str("48b4774c-68ca-4539-a3d7-ac00018b4377").unwrap();
+        let denied = Uuid::parse_str("9c1f6a2e-3b7d-4e0a-8f5c-2d6b1a7e9f30").unwrap();
+        assert!(SdkProvider::check_deleted([(ok, None)]).is_ok());
+
+        let message = SdkProvider::check_deleted([(ok, None), (denied, Some("access denied"))])
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains(&denied.to_string()), "{}", message);
+        assert!(!message.contains(&ok.to_string()), "{}", message);
+    }
+
+    #[test]
+    fn test_trace_lines_never_include_values() {
+        let target = format!("key {} in project {}", "API_KEY", "proj_1");
+        let request = request_line("secrets.create", &target);
+        assert_eq!(request, "-> secrets.create key API_KEY in project proj_1");
+
+        let ok = outcome_line("secrets.create", &target, None, Duration::from_millis(5));
+        assert!(ok.starts_with("<- secrets.create key API_KEY in project proj_1 ok in"));
+
+        // An SDK error echoing the request must not leak the value or token
+        let token = "0.48b4774c-68ca-4539-a3d7-ac00018b4377.c2VjcmV0:a2V5";
+        let error = format!("400 Bad Request: API_KEY=hunter2 (token {})", token);
+        let failed = outcome_line(
+            "secrets.create",
+            &target,
+            Some(&error),
+            Duration::from_millis(5),
+        );
+        assert!(failed.contains("failed in"));
+        for line in [&request, &ok, &failed] {
+            assert!(!line.contains("hunter2"), "{}", line);
+            assert!(!line.contains("c2VjcmV0"), "{}", line);
+        }
+    }
 }
@@ -0,0 +1,98 @@
+//! Access token resolution
+//!
+//! The token is read from `BITWARDEN_ACCESS_TOKEN`, or produced by a
+//! user-supplied command so it can live in another secret store (`pass`,
+//! `gopass`, a cloud KMS CLI, ...). The token itself is never logged.
+
+use crate::{AppError, Result};
+use log::debug;
+use std::process::Command;
+
+/// Environment variable holding the Bitwarden access token
+pub const ACCESS_TOKEN_ENV: &str = "BITWARDEN_ACCESS_TOKEN";
+
+/// Environment variable holding a command that prints the access token
+pub const TOKEN_COMMAND_ENV: &str = "BWENV_TOKEN_COMMAND";
+
+/// Resolve the access token, preferring `token_command` when given
+pub fn access_token(token_command: Option<&str>) -> Result<String> {
+    match token_command {
+        Some(command) => run_token_command(command),
+        None => std::env::var(ACCESS_TOKEN_ENV).map_err(|_| AppError::BitwardenAuthFailed),
+    }
+}
+
+/// Run `command` through the shell and return its trimmed stdout
+fn run_token_command(command: &str) -> Result<String> {
+    debug!("Running access token command: {}", command);
+
+    let output = shell(command)
+        .output()
+        .map_err(|e| AppError::TokenCommandFailed(format!("could not run '{}': {}", command, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut message = format!("'{}' exited with {}", command, output.status);
+        if !stderr.trim().is_empty() {
+            message = format!("{}: {}", message, stderr.trim());
+        }
+        return Err(AppError::TokenCommandFailed(message));
+    }
+
+    let token = String::from_utf8(output.stdout).map_err(|_| {
+        AppError::TokenCommandFailed(format!("'{}' printed invalid UTF-8", command))
+    })?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(AppError::TokenCommandFailed(format!(
+            "'{}' printed nothing",
+            command
+        )));
+    }
+
+    debug!("Access token command succeeded");
+    Ok(token.to_string())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_command_output_is_trimmed() {
+        let token = access_token(Some("printf '  0.abc.def\\n'")).unwrap();
+        assert_eq!(token, "0.abc.def");
+    }
+
+    #[test]
+    fn test_token_command_failure() {
+        let result = access_token(Some("echo denied >&2; exit 3"));
+
+        match result {
+            Err(AppError::TokenCommandFailed(msg)) => assert!(msg.contains("denied")),
+            other => panic!("Expected TokenCommandFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_token_command_empty_output() {
+        assert!(matches!(
+            access_token(Some("true")),
+            Err(AppError::TokenCommandFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,356 @@
+//! Encrypted local cache of pulled secrets
+//!
+//! `pull --cached` and `pull --offline` read project secrets from here instead
+//! of the network. Each project is stored in its own AES-256-GCM encrypted file
+//! under the XDG cache directory, readable only by the current user. The key of
+//! each file is derived with PBKDF2-HMAC-SHA256 from a random salt stored in
+//! its header, so guessing a passphrase costs the same for every file.
+
+use crate::bitwarden::provider::Project;
+use crate::{AppError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use chrono::{DateTime, Duration, Utc};
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+pub mod projects;
+
+/// Environment variable holding a passphrase used instead of the access token
+pub const PASSPHRASE_ENV: &str = "BWENV_CACHE_PASSPHRASE";
+
+/// Size of the random KDF salt that starts each cache file
+const SALT_LEN: usize = 16;
+
+/// Size of the AES-GCM nonce following the salt
+const NONCE_LEN: usize = 12;
+
+/// Prefixed to each salt; bump it when the file format changes
+const KDF_DOMAIN: &[u8] = b"bwenv-cache-v2\0";
+
+/// PBKDF2 rounds deriving the key of a cache file
+#[cfg(not(test))]
+const KDF_ROUNDS: u32 = 600_000;
+/// Unit tests derive many keys in unoptimized builds
+#[cfg(test)]
+const KDF_ROUNDS: u32 = 1_000;
+
+/// Secrets of one project as read from the cache
+pub struct CachedProject {
+    pub project: Project,
+    pub secrets: Vec<(String, Zeroizing<String>)>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// On-disk representation, only ever held briefly before encryption
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    project: Project,
+    secrets: Vec<(String, String)>,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Encrypted per-project secret cache
+#[derive(Clone)]
+pub struct SecretCache {
+    dir: PathBuf,
+    key_material: Zeroizing<String>,
+    ttl: Duration,
+}
+
+impl fmt::Debug for SecretCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretCache")
+            .field("dir", &self.dir)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretCache {
+    /// Open the cache in the default directory
+    ///
+    /// The encryption key is derived from `BWENV_CACHE_PASSPHRASE` when set,
+    /// otherwise from `access_token`.
+    pub fn new(access_token: &str, ttl: Duration) -> Self {
+        let key_material =
+            Zeroizing::new(env::var(PASSPHRASE_ENV).unwrap_or_else(|_| access_token.to_string()));
+        Self::with_dir(get_cache_directory(), &key_material, ttl)
+    }
+
+    /// Open the cache in `dir` with keys derived from `key_material`
+    pub fn with_dir(dir: impl Into<PathBuf>, key_material: &str, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            key_material: Zeroizing::new(key_material.to_string()),
+            ttl,
+        }
+    }
+
+    /// Whether a cached entry is younger than the TTL
+    pub fn is_fresh(&self, entry: &CachedProject) -> bool {
+        Utc::now() - entry.fetched_at < self.ttl
+    }
+
+    /// Read the cached secrets for `project`, regardless of age
+    ///
+    /// Returns `None` when nothing is cached. A file that cannot be decrypted,
+    /// e.g. after rotating the access token, is an error.
+    pub fn load(&self, project: &str) -> Result<Option<CachedProject>> {
+        let path = self.path_for(project);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let data = fs::read(&path)?;
+        if data.len() < SALT_LEN + NONCE_LEN {
+            return Err(AppError::Unknown(format!(
+                "Cache file {} is truncated",
+                path.display()
+            )));
+        }
+        let (salt, rest) = data.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let plaintext = Zeroizing::new(
+            self.cipher(salt)
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| {
+                    AppError::Unknown(format!("Failed to decrypt cache file {}", path.display()))
+                })?,
+        );
+        let file: CacheFile = serde_json::from_slice(&plaintext)?;
+
+        Ok(Some(CachedProject {
+            project: file.project,
+            secrets: file
+                .secrets
+                .into_iter()
+                .map(|(key, value)| (key, Zeroizing::new(value)))
+                .collect(),
+            fetched_at: file.fetched_at,
+        }))
+    }
+
+    /// Encrypt and write the secrets for `project`
+    pub fn store(
+        &self,
+        project: &str,
+        resolved: &Project,
+        secrets: &[(String, String)],
+    ) -> Result<()> {
+        let file = CacheFile {
+            project: resolved.clone(),
+            secrets: secrets.to_vec(),
+            fetched_at: Utc::now(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&file)?);
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(&salt)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| AppError::Unknown("Failed to encrypt cache".to_string()))?;
+
+        fs::create_dir_all(&self.dir)?;
+        let mut out = open_private(&self.path_for(project))?;
+        out.write_all(&salt)?;
+        out.write_all(&nonce)?;
+        out.write_all(&ciphertext)?;
+
+        Ok(())
+    }
+
+    /// Cipher keyed from the key material and a cache file's salt
+    fn cipher(&self, salt: &[u8]) -> Aes256Gcm {
+        let mut key = Zeroizing::new([0u8; 32]);
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(
+            self.key_material.as_bytes(),
+            &[KDF_DOMAIN, salt].concat(),
+            KDF_ROUNDS,
+            key.as_mut(),
+        )
+        .expect("HMAC accepts keys of any length");
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice()))
+    }
+
+    /// Cache file for a project name or ID, hashed so names never hit the disk
+    fn path_for(&self, project: &str) -> PathBuf {
+        let digest = Sha256::digest(project.as_bytes());
+        let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(format!("{}.cache", name))
+    }
+}
+
+/// Returns the cache directory, following the XDG Base Directory Specification
+pub fn get_cache_directory() -> PathBuf {
+    let home_dir = directories::BaseDirs::new()
+        .map(|base_dirs| base_dirs.home_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    if cfg!(target_os = "linux") || cfg!(target_os = "macos") {
+        let xdg_cache_home = env::var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home_dir.join(".cache"));
+        xdg_cache_home.join("bwenv")
+    } else {
+        home_dir.join(".bwenv/cache")
+    }
+}
+
+/// Create or truncate a file with owner-only permissions
+pub(crate) fn open_private(path: &Path) -> Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        // Tighten permissions of a file that already existed
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(options.open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use tempfile::tempdir;
+
+    fn secrets() -> Vec<(String, String)> {
+        vec![("API_KEY".to_string(), "secret123".to_string())]
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+
+        cache
+            .store(
+                "My App",
+                &MockProvider::project("proj_1", "My App"),
+                &secrets(),
+            )
+            .unwrap();
+        let entry = cache.load("My App").unwrap().unwrap();
+
+        assert_eq!(entry.project, MockProvider::project("proj_1", "My App"));
+        assert_eq!(entry.secrets[0].0, "API_KEY");
+        assert_eq!(entry.secrets[0].1.as_str(), "secret123");
+        assert!(cache.is_fresh(&entry));
+    }
+
+    #[test]
+    fn test_cache_file_is_encrypted() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+        cache
+            .store(
+                "My App",
+                &MockProvider::project("proj_1", "My App"),
+                &secrets(),
+            )
+            .unwrap();
+
+        let data = fs::read(cache.path_for("My App")).unwrap();
+        let text = String::from_utf8_lossy(&data);
+        assert!(!text.contains("secret123"));
+        assert!(!text.contains("API_KEY"));
+    }
+
+    #[test]
+    fn test_each_file_has_its_own_salt() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+        let project = MockProvider::project("proj_1", "My App");
+
+        cache.store("My App", &project, &secrets()).unwrap();
+        let first = fs::read(cache.path_for("My App")).unwrap();
+        cache.store("My App", &project, &secrets()).unwrap();
+        let second = fs::read(cache.path_for("My App")).unwrap();
+
+        assert_ne!(first[..SALT_LEN], second[..SALT_LEN]);
+        assert!(cache.load("My App").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_load_missing_project() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+
+        assert!(cache.load("Unknown").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_load_with_wrong_key_fails() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+        cache
+            .store(
+                "My App",
+                &MockProvider::project("proj_1", "My App"),
+                &secrets(),
+            )
+            .unwrap();
+
+        let other = SecretCache::with_dir(dir.path(), "other-token", Duration::hours(1));
+        assert!(other.load("My App").is_err());
+    }
+
+    #[test]
+    fn test_expired_entry_is_not_fresh() {
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::zero());
+        cache
+            .store(
+                "My App",
+                &MockProvider::project("proj_1", "My App"),
+                &secrets(),
+            )
+            .unwrap();
+
+        let entry = cache.load("My App").unwrap().unwrap();
+        assert!(!cache.is_fresh(&entry));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let cache = SecretCache::with_dir(dir.path(), "token", Duration::hours(1));
+        cache
+            .store(
+                "My App",
+                &MockProvider::project("proj_1", "My App"),
+                &secrets(),
+            )
+            .unwrap();
+
+        let mode = fs::metadata(cache.path_for("My App"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
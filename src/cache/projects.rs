@@ -0,0 +1,153 @@
+//! Cache of resolved project names
+//!
+//! Resolving a project by name lists every project. With a TTL configured,
+//! the listed projects are kept in a JSON file per organization so repeated
+//! runs skip that call. Only project names and IDs are stored, never secrets.
+
+use super::{get_cache_directory, open_private};
+use crate::bitwarden::provider::Project;
+use crate::Result;
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize)]
+struct ProjectCacheFile {
+    cached_at: DateTime<Utc>,
+    projects: Vec<Project>,
+}
+
+/// Name to ID cache of one organization's projects
+#[derive(Debug, Clone)]
+pub struct ProjectCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl ProjectCache {
+    /// Open the cache of `organization_id` in the default cache directory
+    pub fn new(organization_id: &str, ttl: Duration) -> Self {
+        let path = get_cache_directory().join(format!("projects-{}.json", organization_id));
+        Self::with_path(path, ttl)
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            path: path.into(),
+            ttl,
+        }
+    }
+
+    /// The project named `name`, if the cache is fresh and names exactly one
+    ///
+    /// The cache is best-effort: an unreadable file counts as a miss.
+    pub fn lookup(&self, name: &str) -> Option<Project> {
+        self.lookup_at(name, Utc::now())
+    }
+
+    fn lookup_at(&self, name: &str, now: DateTime<Utc>) -> Option<Project> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let file: ProjectCacheFile = serde_json::from_str(&content).ok()?;
+        if now - file.cached_at >= self.ttl {
+            debug!("Project cache expired");
+            return None;
+        }
+
+        let mut matches = file.projects.into_iter().filter(|p| p.name == name);
+        match (matches.next(), matches.next()) {
+            (Some(project), None) => Some(project),
+            // Ambiguous names go to the provider so it can report them
+            _ => None,
+        }
+    }
+
+    /// Replace the cache with `projects`
+    pub fn store(&self, projects: &[Project]) -> Result<()> {
+        self.store_at(projects, Utc::now())
+    }
+
+    fn store_at(&self, projects: &[Project], cached_at: DateTime<Utc>) -> Result<()> {
+        let file = ProjectCacheFile {
+            cached_at,
+            projects: projects.to_vec(),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut out = open_private(&self.path)?;
+        out.write_all(&serde_json::to_vec(&file)?)?;
+        Ok(())
+    }
+
+    /// Drop the cache, e.g. after a project was created
+    pub fn invalidate(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_project_cache_hit_miss_and_expiry() {
+        let dir = tempdir().unwrap();
+        let cache = ProjectCache::with_path(dir.path().join("projects.json"), Duration::minutes(5));
+
+        // Nothing cached yet
+        assert_eq!(cache.lookup("App"), None);
+
+        cache
+            .store(&[
+                MockProvider::project("proj_1", "App"),
+                MockProvider::project("proj_2", "Api"),
+            ])
+            .unwrap();
+        assert_eq!(
+            cache.lookup("App"),
+            Some(MockProvider::project("proj_1", "App"))
+        );
+        assert_eq!(cache.lookup("Missing"), None);
+
+        let later = Utc::now() + Duration::minutes(6);
+        assert_eq!(cache.lookup_at("App", later), None);
+
+        cache.invalidate();
+        assert_eq!(cache.lookup("App"), None);
+    }
+
+    #[test]
+    fn test_project_cache_skips_ambiguous_names() {
+        let dir = tempdir().unwrap();
+        let cache = ProjectCache::with_path(dir.path().join("projects.json"), Duration::minutes(5));
+
+        cache
+            .store(&[
+                MockProvider::project("proj_1", "App"),
+                MockProvider::project("proj_2", "App"),
+            ])
+            .unwrap();
+
+        assert_eq!(cache.lookup("App"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_project_cache_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        ProjectCache::with_path(&path, Duration::minutes(5))
+            .store(&[MockProvider::project("proj_1", "App")])
+            .unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -2,10 +2,40 @@
 //!
 //! This module handles argument parsing and command dispatch.
 
+use crate::audit::{AuditLog, AUDIT_LOG_ENV};
+use crate::bitwarden::provider::DEFAULT_CONCURRENCY;
+use crate::bitwarden::retry::{RetryPolicy, DEFAULT_RETRY_COUNT, DEFAULT_RETRY_DELAY_MS};
 use crate::bitwarden::sdk_provider::SdkProvider;
+use crate::bitwarden::token::{self, TOKEN_COMMAND_ENV};
+use crate::cache::SecretCache;
 use crate::commands;
+use crate::commands::completions::CompletionSource;
+use crate::commands::config::ShowOptions;
+use crate::commands::env::Shell;
+use crate::commands::prune::DEFAULT_TOMBSTONE;
+use crate::commands::pull::{CollisionPolicy, KeyTransform, PullOptions};
+use crate::commands::push::{ConflictAction, EmptyAs, PushOptions, SummaryFormat};
+use crate::commands::rotate::{Charset, RotateOptions, DEFAULT_LENGTH as DEFAULT_ROTATE_LENGTH};
+use crate::commands::status::{ListOptions, ProjectSort, StatusOptions};
+use crate::commands::validate::ValidateOptions;
+use crate::config::Config;
+use crate::env::encoding::TextEncoding;
+use crate::env::fix::LineEnding;
+use crate::env::input::InputFormat;
+use crate::env::lock::DEFAULT_LOCK_TIMEOUT_SECS;
+use crate::env::parser::{MergeStrategy, SortMode};
+use crate::env::paths;
+use crate::env::template::PlaceholderStyle;
+use crate::error::ErrorFormat;
+use crate::git;
+use crate::logging::{self, Verbosity};
+use crate::output::{self, ColorChoice, OutputFormat};
 use crate::{AppError, Result};
-use clap::{Parser, Subcommand};
+use clap::builder::{FalseyValueParser, RangedU64ValueParser};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell as CompletionShell;
+use std::io::IsTerminal;
+use std::time::Duration;
 
 /// bwenv - Bitwarden Secrets Manager .env CLI
 ///
@@ -15,40 +45,383 @@ use clap::{Parser, Subcommand};
 #[command(about = "Manage .env files with Bitwarden Secrets Manager", long_about = None)]
 #[command(version)]
 pub struct Cli {
+    /// When to use colors and emoji in output (honors NO_COLOR); without
+    /// them, status symbols are written as [OK], [WARN] and the like
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Same as --color never
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Increase output verbosity
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Derive the project name from the git repository's origin remote
+    /// (the default when neither --project nor default_project is set)
+    #[arg(long, global = true)]
+    pub project_from_git: bool,
+
+    /// Profile from .bwenv.toml whose settings apply to this run
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// How to print a fatal error on stderr
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+
+    /// Command whose output is used as the access token instead of
+    /// BITWARDEN_ACCESS_TOKEN, e.g. "pass show bitwarden/token"
+    #[arg(long, global = true, env = TOKEN_COMMAND_ENV, value_name = "COMMAND")]
+    pub token_command: Option<String>,
+
+    /// Times to retry a Bitwarden call that failed transiently (network error,
+    /// timeout, 429 or 5xx)
+    #[arg(long, global = true, env = "BWENV_RETRY_COUNT", default_value_t = DEFAULT_RETRY_COUNT)]
+    pub retry_count: u32,
+
+    /// Milliseconds to wait before the first retry; later retries back off
+    /// exponentially with random jitter
+    #[arg(long, global = true, env = "BWENV_RETRY_DELAY", default_value_t = DEFAULT_RETRY_DELAY_MS)]
+    pub retry_delay: u64,
+
+    /// Refuse every command that would modify Bitwarden
+    #[arg(
+        long,
+        global = true,
+        env = "BWENV_READ_ONLY",
+        value_parser = FalseyValueParser::new()
+    )]
+    pub read_only: bool,
+
+    /// Scrub KEY=VALUE pairs and known secret formats from log lines
+    /// before they are written
+    #[arg(
+        long,
+        global = true,
+        env = "BWENV_REDACT_LOGS",
+        value_name = "BOOL",
+        default_value_t = true,
+        action = ArgAction::Set
+    )]
+    pub redact_logs: bool,
+
+    /// Append a JSON line for every change made to Bitwarden to this file
+    /// (default: audit_log in .bwenv.toml)
+    #[arg(long, global = true, env = AUDIT_LOG_ENV, value_name = "PATH")]
+    pub audit_log: Option<String>,
+
+    /// Remember resolved project names for SECS seconds, skipping the
+    /// project listing on later runs (default: off)
+    #[arg(
+        long,
+        global = true,
+        env = "BWENV_PROJECT_CACHE_TTL",
+        value_name = "SECS"
+    )]
+    pub project_cache_ttl: Option<u64>,
+
+    /// Most requests sent to Bitwarden at once when fetching or pushing
+    /// many secrets; lower it to be gentler on the API
+    #[arg(
+        long,
+        global = true,
+        env = "BWENV_CONCURRENCY",
+        value_name = "N",
+        default_value_t = DEFAULT_CONCURRENCY,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub concurrency: usize,
+
+    /// Don't lock .env files against concurrent bwenv processes
+    #[arg(long, global = true)]
+    pub no_lock: bool,
+
+    /// Seconds to wait for another bwenv process to release a .env file
+    #[arg(long, global = true, value_name = "SECS", default_value_t = DEFAULT_LOCK_TIMEOUT_SECS)]
+    pub lock_timeout: u64,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// The color choice, with --no-color taking precedence over --color
+    pub fn color_choice(&self) -> ColorChoice {
+        if self.no_color {
+            ColorChoice::Never
+        } else {
+            self.color
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Pull secrets from Bitwarden to .env file
     Pull {
-        /// Project name or ID in Bitwarden
+        /// Project name or ID in Bitwarden (repeat to merge several projects)
         #[arg(short, long)]
-        project: String,
+        project: Vec<String>,
 
-        /// Output file path (default: .env)
-        #[arg(short, long, default_value = ".env")]
-        output: String,
+        /// Output file path (default: config env_file, then profile env_file, then .env)
+        #[arg(short, long)]
+        output: Option<String>,
 
         /// Overwrite existing file
         #[arg(long)]
         force: bool,
+
+        /// How to handle a key present in more than one project
+        #[arg(long, value_enum, default_value_t = CollisionPolicy::Error)]
+        on_collision: CollisionPolicy,
+
+        /// Merge into an existing output file, keeping keys only it has
+        /// and adding keys only Bitwarden has
+        #[arg(long, conflicts_with_all = ["force", "template"])]
+        merge: bool,
+
+        /// Only refresh values of keys the existing output file already
+        /// has: no keys are added or removed, unlike --merge or --force
+        #[arg(long, conflicts_with_all = ["force", "merge", "template", "example"])]
+        merge_from_remote: bool,
+
+        /// With --merge, which value wins for a key the output file already has
+        #[arg(long, value_enum, default_value_t = MergeStrategy::RemoteWins, requires = "merge")]
+        on_conflict: MergeStrategy,
+
+        /// Render a template file, substituting ${KEY} placeholders, instead
+        /// of writing a .env file
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Leave unresolved template placeholders instead of failing
+        #[arg(long, requires = "template")]
+        allow_missing: bool,
+
+        /// Placeholder syntax of the template: shell `${KEY}` (escape as
+        /// `$${KEY}`) or mustache `{{KEY}}` (escape as `\{{KEY}}`)
+        #[arg(
+            long,
+            value_enum,
+            alias = "template-engine",
+            default_value_t = PlaceholderStyle::Shell,
+            requires = "template"
+        )]
+        placeholder_style: PlaceholderStyle,
+
+        /// Key ordering in the written file (none keeps Bitwarden's order)
+        #[arg(long, value_enum, default_value_t = SortMode::Alphabetical)]
+        sort: SortMode,
+
+        /// Use the encrypted local cache when fresh, refreshing it otherwise
+        #[arg(long)]
+        cached: bool,
+
+        /// Read only from the encrypted local cache, never the network
+        #[arg(long, conflicts_with = "cached")]
+        offline: bool,
+
+        /// Seconds before cached secrets are considered stale
+        #[arg(long, default_value_t = 3600)]
+        cache_ttl: u64,
+
+        /// Write keys with blank values (or a <note> placeholder) instead of
+        /// real values, e.g. for a committable .env.example; an existing file
+        /// is replaced
+        #[arg(long, conflicts_with_all = ["template", "merge", "force"])]
+        example: bool,
+
+        /// Write each secret's Bitwarden note as a comment above its key
+        #[arg(long, conflicts_with_all = ["offline", "template"])]
+        notes_as_comments: bool,
+
+        /// Write every key as `export KEY=VALUE` so the file can be sourced
+        #[arg(long, conflicts_with = "template")]
+        export: bool,
+
+        /// Format of the written file: env (default), json or yaml
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Only refresh keys under the `# === NAME ===` header of the
+        /// existing output file, leaving the rest of it untouched
+        #[arg(
+            long,
+            value_name = "NAME",
+            alias = "dotenv-section",
+            conflicts_with_all = ["force", "merge", "template", "example", "format"]
+        )]
+        section: Option<String>,
+
+        /// Change the case of every key before writing; keys that become
+        /// equal are an error
+        #[arg(long, value_enum, default_value_t = KeyTransform::None)]
+        key_transform: KeyTransform,
+
+        /// Refuse to pull a project with more than N secrets (default: no
+        /// limit), e.g. a shared project pulled into one service
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        max_secrets: Option<usize>,
     },
 
     /// Push .env file secrets to Bitwarden
     Push {
         /// Project name or ID in Bitwarden
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
 
-        /// Input .env file path (default: .env)
-        #[arg(short, long, default_value = ".env")]
-        input: String,
+        /// Input .env file path (default: config env_file, then profile env_file, then .env)
+        #[arg(short, long)]
+        input: Option<String>,
 
-        /// Overwrite existing secrets
+        /// Overwrite existing secrets with local values
         #[arg(long)]
         overwrite: bool,
+
+        /// Only add new secrets; existing keys are never modified and are
+        /// reported as an error (default) or a warning
+        #[arg(
+            long,
+            value_enum,
+            num_args = 0..=1,
+            default_missing_value = "error",
+            conflicts_with = "overwrite"
+        )]
+        create_only: Option<ConflictAction>,
+
+        /// Only create keys missing in Bitwarden; existing keys are left
+        /// untouched and not reported
+        #[arg(long, conflicts_with_all = ["overwrite", "create_only"])]
+        add_only_missing: bool,
+
+        /// Warn about empty, placeholder or short values (keys and reasons only)
+        #[arg(long)]
+        warn_weak: bool,
+
+        /// Like --warn-weak, but abort the push if any weak value is found
+        #[arg(long)]
+        fail_weak: bool,
+
+        /// Minimum value length for *_SECRET, *_PASSWORD, *_KEY and *_TOKEN keys
+        #[arg(long, default_value_t = 12)]
+        min_secret_length: usize,
+
+        /// Stop at the first secret that fails instead of pushing the rest
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Summary output format
+        #[arg(long, value_enum, default_value_t = SummaryFormat::Text)]
+        summary: SummaryFormat,
+
+        /// Secrets created or updated concurrently, in chunks sent one after
+        /// another (default: --concurrency)
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        chunk_size: Option<usize>,
+
+        /// Layer FILE over the input before pushing; repeatable, later files win
+        #[arg(long, value_name = "FILE")]
+        overlay: Vec<String>,
+
+        /// Treat KEY+=value as appending to the value from earlier layers
+        #[arg(long)]
+        allow_append: bool,
+
+        /// Push the contents of PATH for KEY=@PATH values, relative to the
+        /// .env file's directory
+        #[arg(long)]
+        allow_file_refs: bool,
+
+        /// Store the comment lines directly above each key as its note
+        #[arg(long)]
+        comments_as_notes: bool,
+
+        /// Inline the .env file named by each `#!include PATH` line, relative
+        /// to the including file
+        #[arg(long)]
+        allow_includes: bool,
+
+        /// Push a bare KEY line (no `=`) as an empty value
+        #[arg(long, alias = "dotenv-allow-no-value")]
+        allow_bare_keys: bool,
+
+        /// With --allow-bare-keys, accept bare keys but don't push them
+        #[arg(long, requires = "allow_bare_keys")]
+        skip_bare: bool,
+
+        /// Warn about each line that isn't KEY=VALUE and is skipped, by line
+        /// number only
+        #[arg(long)]
+        warn_skipped: bool,
+
+        /// Refuse to push if any line isn't KEY=VALUE instead of skipping it
+        #[arg(long, conflicts_with = "warn_skipped")]
+        strict_lines: bool,
+
+        /// What KEY= means: clear sets the secret to an empty value, skip
+        /// leaves the remote value untouched, error refuses to push
+        #[arg(long, value_enum, default_value_t = EmptyAs::Clear)]
+        empty_as: EmptyAs,
+
+        /// Separator placed between an existing value and an appended one
+        #[arg(long, default_value = "", requires = "allow_append")]
+        append_separator: String,
+
+        /// Create the project if it does not exist, asking first on a terminal
+        #[arg(long)]
+        create_project: bool,
+
+        /// Only push keys changed since the last push recorded in PATH, then
+        /// update it (stores hashes, never values)
+        #[arg(long, value_name = "PATH")]
+        since_file: Option<String>,
+
+        /// Format of the input file: auto (by extension, then content), env,
+        /// or a flat json or yaml object
+        #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+        input_format: InputFormat,
+
+        /// Push values exactly as written after `=`, keeping leading and
+        /// trailing whitespace that is trimmed by default
+        #[arg(long)]
+        no_trim: bool,
+
+        /// After pushing, write a JSON report of the created, updated,
+        /// skipped and pruned keys to PATH (never values)
+        #[arg(long, value_name = "PATH")]
+        report_file: Option<String>,
+
+        /// Encoding of the input and overlay files, e.g. windows-1252 or
+        /// latin1; values are always stored as UTF-8
+        #[arg(long, alias = "env-file-encoding", default_value_t = TextEncoding::default())]
+        encoding: TextEncoding,
+
+        /// Succeed when the input has no keys instead of refusing to push it
+        #[arg(long)]
+        allow_empty: bool,
+
+        /// Only push keys under the `# === NAME ===` header of the input
+        #[arg(long, value_name = "NAME", alias = "dotenv-section")]
+        section: Option<String>,
+
+        /// After pushing, delete remote secrets missing from the input; the
+        /// keys are listed and confirmed before anything is changed
+        #[arg(long, conflicts_with_all = ["since_file", "section"])]
+        prune: bool,
+
+        /// With --prune, delete without asking (required when not interactive)
+        #[arg(short = 'y', long, requires = "prune")]
+        yes: bool,
     },
 
     /// List projects and secrets
@@ -56,40 +429,338 @@ pub enum Commands {
         /// List secrets in a specific project
         #[arg(short, long)]
         project: Option<String>,
+
+        /// Only show projects (or, with --project, secret keys) containing
+        /// PATTERN, ignoring case
+        #[arg(long, value_name = "PATTERN")]
+        search: Option<String>,
+
+        /// Project ordering (secrets are always sorted by key)
+        #[arg(long, value_enum, default_value_t = ProjectSort::Name)]
+        sort: ProjectSort,
+
+        /// Output format: text (default), json or yaml; never includes values
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Group projects under their organization
+        #[arg(long, conflicts_with = "project")]
+        tree: bool,
+
+        /// Hide projects the token can only read, when that is known
+        #[arg(long, conflicts_with = "project")]
+        writable: bool,
     },
 
     /// Initialize configuration
-    Init,
+    Init {
+        /// Add .env and its variants to .gitignore, creating it if needed
+        #[arg(long)]
+        gitignore: bool,
+    },
 
     /// Show status of current project
     Status {
         /// Project name or ID
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
 
-        /// Path to .env file to compare
+        /// Path to .env file to compare (repeatable, `-` reads stdin; default:
+        /// config env_file, then profile env_file, then .env)
+        #[arg(short, long)]
+        env_file: Vec<String>,
+
+        /// Glob matching several .env files to compare, e.g. 'services/*/.env'
+        #[arg(long)]
+        file_glob: Option<String>,
+
+        /// Show the Bitwarden note of remote-only and changed keys
+        #[arg(long)]
+        with_notes: bool,
+
+        /// Porcelain output: one line per drifted key, '+' local only,
+        /// '-' remote only, '~' different value
+        #[arg(long, conflicts_with = "with_notes")]
+        short: bool,
+
+        /// Never report KEY as drift; repeatable, added to status.ignore in .bwenv.toml
+        #[arg(long, value_name = "KEY")]
+        ignore: Vec<String>,
+
+        /// Output format: text (default), json or yaml
+        #[arg(long, value_enum, conflicts_with_all = ["short", "with_notes"])]
+        format: Option<OutputFormat>,
+
+        /// Show the Bitwarden and local values of changed keys, revealing
+        /// only the first N characters of each (0 shows only their lengths)
+        #[arg(long, value_name = "N", conflicts_with_all = ["short", "format"])]
+        reveal_prefix: Option<usize>,
+
+        /// Only compare keys under the `# === NAME ===` header of each file
+        #[arg(long, value_name = "NAME", alias = "dotenv-section")]
+        section: Option<String>,
+
+        /// Exit with an error if a local file has keys missing from
+        /// Bitwarden (after --ignore), even if nothing else drifted
+        #[arg(long)]
+        fail_on_extra: bool,
+
+        /// Compare the project with another project instead of local files,
+        /// e.g. `--project staging --against prod`; values stay masked
+        #[arg(
+            long,
+            value_name = "PROJECT",
+            conflicts_with_all = ["env_file", "file_glob", "with_notes", "section"]
+        )]
+        against: Option<String>,
+    },
+
+    /// Fail unless a local .env has exactly the keys and values of the
+    /// project, e.g. as a CI check; values are never printed
+    Verify {
+        /// Project name or ID
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// .env file or .env.d directory to verify (default: config
+        /// env_file, then profile env_file, then .env)
         #[arg(short, long)]
         env_file: Option<String>,
     },
 
+    /// Print secrets as shell export statements, for `eval "$(bwenv env)"`
+    Env {
+        /// Project name or ID in Bitwarden
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Shell syntax to print
+        #[arg(long, value_enum, default_value_t = Shell::Posix)]
+        shell: Shell,
+    },
+
+    /// Print a shell completion script, e.g. `bwenv completions bash`
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+
+    /// Print completion candidates; used by the completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        source: CompletionSource,
+    },
+
+    /// Show when a secret changed (timestamps and authors, never values)
+    History {
+        /// Project name or ID in Bitwarden
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Secret key
+        #[arg(short, long)]
+        key: String,
+    },
+
+    /// Replace a secret with a new random value, e.g. for routine rotation
+    Rotate {
+        /// Project name or ID in Bitwarden
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Secret key
+        #[arg(short, long)]
+        key: String,
+
+        /// Number of characters to generate
+        #[arg(
+            long,
+            default_value_t = DEFAULT_ROTATE_LENGTH,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        length: usize,
+
+        /// Characters to draw from
+        #[arg(long, value_enum, default_value_t = Charset::Alnum)]
+        charset: Charset,
+
+        /// Print the new value (by default only success is reported)
+        #[arg(long)]
+        show: bool,
+
+        /// Also set the key in the local .env file
+        #[arg(long)]
+        write_env: bool,
+
+        /// .env file for --write-env (default: config env_file, then profile env_file, then .env)
+        #[arg(short, long, requires = "write_env")]
+        env_file: Option<String>,
+
+        /// Rotate without asking for confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Show the project, key and planned change without changing anything
+        #[arg(long, conflicts_with = "show")]
+        dry_run: bool,
+    },
+
     /// Validate .env file format
     Validate {
-        /// Input .env file path (default: .env)
-        #[arg(short, long, default_value = ".env")]
-        input: String,
+        /// Input .env file path (default: config env_file, then profile env_file, then .env)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Warn about values with leading/trailing whitespace
+        #[arg(long)]
+        warn_whitespace: bool,
+
+        /// Rewrite the file fixing trailing whitespace, `export` prefixes,
+        /// a missing final newline, a byte order mark and CRLF line endings
+        #[arg(long)]
+        fix: bool,
+
+        /// With --fix, also remove earlier definitions of duplicate keys
+        #[arg(long, requires = "fix")]
+        fix_duplicates: bool,
+
+        /// With --fix, write CRLF line endings instead of LF
+        #[arg(long, requires = "fix")]
+        crlf: bool,
+
+        /// Also reject keys a shell can't export, i.e. not matching
+        /// [A-Za-z_][A-Za-z0-9_]* (e.g. APP.NAME)
+        #[arg(long)]
+        strict_keys: bool,
+
+        /// Accept a bare KEY line (no `=`) as KEY= instead of rejecting it
+        #[arg(long, alias = "dotenv-allow-no-value")]
+        allow_bare_keys: bool,
+
+        /// Encoding of the file, e.g. windows-1252 or latin1
+        #[arg(long, alias = "env-file-encoding", default_value_t = TextEncoding::default())]
+        encoding: TextEncoding,
+
+        /// Output format: text (default), json or yaml; json and yaml list
+        /// every error with its line number
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Delete Bitwarden secrets missing from the local .env, in two phases
+    Prune {
+        /// Project name or ID in Bitwarden
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Input .env file path (default: config env_file, then profile env_file, then .env)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Write the secrets that would be deleted to the tombstone file
+        #[arg(long, conflicts_with = "apply", required_unless_present = "apply")]
+        plan: bool,
+
+        /// Delete the secrets listed in the tombstone file
+        #[arg(long)]
+        apply: bool,
+
+        /// Tombstone file shared by --plan and --apply
+        #[arg(long, default_value = DEFAULT_TOMBSTONE)]
+        tombstone: String,
+
+        /// Plan even when the input has no keys, i.e. delete every secret
+        #[arg(long, requires = "plan")]
+        allow_empty: bool,
+
+        /// Inline the .env file named by each `#!include PATH` line, as push
+        /// does
+        #[arg(long, requires = "plan")]
+        allow_includes: bool,
+
+        /// Encoding of the input files, e.g. windows-1252 or latin1
+        #[arg(long, alias = "env-file-encoding", default_value_t = TextEncoding::default())]
+        encoding: TextEncoding,
+    },
+
+    /// Check the config file, access token, authentication and project
+    /// access, with a hint for each problem found
+    Doctor,
+
+    /// Inspect bwenv configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+impl Commands {
+    /// Name of the command if it can create, change or delete anything in
+    /// Bitwarden, for `--read-only`
+    fn mutation(&self) -> Option<&'static str> {
+        match self {
+            Commands::Push { .. } => Some("push"),
+            Commands::Rotate { dry_run: false, .. } => Some("rotate"),
+            Commands::Prune { apply: true, .. } => Some("prune --apply"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the effective configuration and where each value came from,
+    /// without contacting Bitwarden
+    Show {
+        /// Project name or ID, as it would be passed to other commands
+        #[arg(short, long)]
+        project: Option<String>,
     },
 }
 
 /// Run the CLI application
-pub async fn run() -> Result<()> {
-    let cli = Cli::parse();
+pub async fn run(cli: Cli) -> Result<()> {
+    if cli.read_only {
+        if let Some(command) = cli.command.mutation() {
+            return Err(AppError::ReadOnly(format!(
+                "{} modifies Bitwarden and is disabled by --read-only (BWENV_READ_ONLY)",
+                command
+            )));
+        }
+    }
 
-    // Get access token from environment
-    let access_token =
-        std::env::var("BITWARDEN_ACCESS_TOKEN").map_err(|_| AppError::BitwardenAuthFailed)?;
+    let color = cli.color_choice();
+    output::init(color);
+    if cli.verbose > 0 {
+        // Logging is opt-in so plain runs don't create log files
+        if let Err(e) =
+            logging::initialize(Verbosity::from_count(cli.verbose), false, cli.redact_logs)
+        {
+            eprintln!("Failed to initialize logging: {}", e);
+        }
+    }
+    let retry = RetryPolicy::new(cli.retry_count, cli.retry_delay);
+    if let Commands::Doctor = cli.command {
+        // Runs before the config is loaded so a broken config is diagnosed
+        return commands::doctor::execute(cli.token_command.as_deref(), retry).await;
+    }
+    let config = Config::load()?;
+    let profile = cli.profile.as_deref();
+    let lock_timeout = (!cli.no_lock).then(|| Duration::from_secs(cli.lock_timeout));
+    let project_cache_ttl = cli
+        .project_cache_ttl
+        .filter(|&secs| secs > 0)
+        .map(|secs| chrono::Duration::seconds(secs.try_into().unwrap_or(i64::MAX)));
+    let audit = cli
+        .audit_log
+        .clone()
+        .or_else(|| config.audit_log.clone())
+        .map(AuditLog::new);
 
-    // Create SDK provider
-    let provider = SdkProvider::new(access_token).await?;
+    let token_command = cli.token_command.as_deref();
+    let access_token = || token::access_token(token_command);
 
     // Dispatch to command handlers
     match cli.command {
@@ -97,17 +768,504 @@ pub async fn run() -> Result<()> {
             project,
             output,
             force,
-        } => commands::pull::execute(provider, &project, &output, force).await,
+            on_collision,
+            merge,
+            on_conflict,
+            merge_from_remote,
+            template,
+            allow_missing,
+            placeholder_style,
+            sort,
+            cached,
+            offline,
+            cache_ttl,
+            example,
+            notes_as_comments,
+            export,
+            format,
+            section,
+            key_transform,
+            max_secrets,
+        } => {
+            let access_token = access_token()?;
+            let projects = resolve_projects(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let output = config.resolve_env_file(output.as_deref(), profile)?;
+            let cache = (cached || offline).then(|| {
+                let ttl = chrono::Duration::seconds(cache_ttl.try_into().unwrap_or(i64::MAX));
+                SecretCache::new(&access_token, ttl)
+            });
+            let options = PullOptions {
+                force,
+                merge,
+                on_conflict,
+                merge_from_remote,
+                on_collision,
+                verbose: cli.verbose > 0,
+                template,
+                allow_missing,
+                placeholder_style,
+                sort,
+                cache,
+                example,
+                notes_as_comments,
+                export,
+                format,
+                lock_timeout,
+                section,
+                key_transform,
+                max_secrets,
+            };
+            if offline {
+                commands::pull::execute_offline(&projects, &output, &options)?
+                    .render(options.verbose);
+                return Ok(());
+            }
+            let provider = SdkProvider::with_retry_policy(access_token, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::pull::execute(provider, &projects, &output, &options)
+                .await?
+                .render(options.verbose);
+            Ok(())
+        }
         Commands::Push {
             project,
             input,
             overwrite,
-        } => commands::push::execute(provider, &project, &input, overwrite).await,
-        Commands::List { project } => commands::status::list(provider, project.as_deref()).await,
-        Commands::Init => commands::init::execute().await,
-        Commands::Status { project, env_file } => {
-            commands::status::execute(provider, &project, env_file.as_deref()).await
+            create_only,
+            add_only_missing,
+            warn_weak,
+            fail_weak,
+            min_secret_length,
+            fail_fast,
+            summary,
+            chunk_size,
+            overlay,
+            allow_append,
+            append_separator,
+            allow_file_refs,
+            allow_includes,
+            allow_bare_keys,
+            skip_bare,
+            warn_skipped,
+            strict_lines,
+            empty_as,
+            comments_as_notes,
+            create_project,
+            since_file,
+            input_format,
+            no_trim,
+            report_file,
+            encoding,
+            allow_empty,
+            section,
+            prune,
+            yes,
+        } => {
+            let options = PushOptions {
+                overwrite,
+                create_only,
+                summary,
+                warn_weak,
+                fail_weak,
+                min_secret_length,
+                fail_fast,
+                add_only_missing,
+                chunk_size: chunk_size.unwrap_or(0),
+                overlays: overlay,
+                allow_append,
+                append_separator,
+                allow_file_refs,
+                allow_includes,
+                comments_as_notes,
+                policy: config.validation.clone(),
+                create_project,
+                confirm_create: std::io::stdin().is_terminal(),
+                lock_timeout,
+                since_file,
+                audit,
+                input_format,
+                no_trim,
+                report_file,
+                encoding,
+                allow_empty,
+                section,
+                prune,
+                yes,
+                confirm_prune: std::io::stdin().is_terminal(),
+                allow_bare_keys,
+                skip_bare,
+                warn_skipped,
+                strict_lines,
+                empty_as,
+            };
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let input = config.resolve_env_file(input.as_deref(), profile)?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            let plan = commands::push::execute(provider, &project, &input, &options).await?;
+            plan.render(options.summary)?;
+            plan.check()
+        }
+        Commands::List {
+            project,
+            search,
+            sort,
+            format,
+            tree,
+            writable,
+        } => {
+            let options = ListOptions {
+                search,
+                sort,
+                format,
+                tree,
+                writable,
+            };
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::status::list(provider, project.as_deref(), &options).await
+        }
+        Commands::Init { gitignore } => commands::init::execute(gitignore).await,
+        Commands::Status {
+            project,
+            env_file,
+            file_glob,
+            with_notes,
+            short,
+            mut ignore,
+            format,
+            reveal_prefix,
+            section,
+            fail_on_extra,
+            against,
+        } => {
+            ignore.extend(config.status.ignore.iter().cloned());
+            let mut env_file = env_file
+                .iter()
+                .map(|path| paths::expand_path(path))
+                .collect::<Result<Vec<_>>>()?;
+            if env_file.is_empty() && file_glob.is_none() {
+                env_file.push(config.resolve_env_file(None, profile)?);
+            }
+            let options = StatusOptions {
+                env_files: env_file,
+                file_glob,
+                with_notes,
+                short,
+                ignore,
+                format,
+                reveal_prefix,
+                section,
+                fail_on_extra,
+                against,
+            };
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::status::execute(provider, &project, &options).await
+        }
+        Commands::Verify { project, env_file } => {
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let env_file = config.resolve_env_file(env_file.as_deref(), profile)?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::verify::execute(provider, &project, &env_file).await
+        }
+        Commands::Env { project, shell } => {
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::env::execute(provider, &project, shell).await
+        }
+        Commands::Completions { shell } => {
+            print!(
+                "{}",
+                commands::completions::script(shell, &mut Cli::command())
+            );
+            Ok(())
+        }
+        Commands::Complete { source } => match source {
+            CompletionSource::Projects => {
+                // Fail fast and silently: a completion must not hang or print errors
+                let Ok(token) = access_token() else {
+                    return Ok(());
+                };
+                let Ok(provider) =
+                    SdkProvider::with_retry_policy(token, RetryPolicy::new(0, 0)).await
+                else {
+                    return Ok(());
+                };
+                print!("{}", commands::completions::project_names(provider).await);
+                Ok(())
+            }
+        },
+        Commands::History { project, key } => {
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::history::execute(provider, &project, &key).await
+        }
+        Commands::Rotate {
+            project,
+            key,
+            length,
+            charset,
+            show,
+            write_env,
+            env_file,
+            yes,
+            dry_run,
+        } => {
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let env_file = if write_env {
+                Some(config.resolve_env_file(env_file.as_deref(), profile)?)
+            } else {
+                None
+            };
+            let options = RotateOptions {
+                length,
+                charset,
+                show,
+                env_file,
+                lock_timeout,
+                yes,
+                confirm: std::io::stdin().is_terminal(),
+                audit,
+                dry_run,
+            };
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            commands::rotate::execute(provider, &project, &key, &options).await
+        }
+        Commands::Validate {
+            input,
+            warn_whitespace,
+            fix,
+            fix_duplicates,
+            crlf,
+            strict_keys,
+            allow_bare_keys,
+            encoding,
+            format,
+        } => {
+            let input = config.resolve_env_file(input.as_deref(), profile)?;
+            let options = ValidateOptions {
+                warn_whitespace,
+                fix,
+                fix_duplicates,
+                line_ending: if crlf {
+                    LineEnding::Crlf
+                } else {
+                    LineEnding::Lf
+                },
+                format,
+                policy: config.validation.clone(),
+                strict_keys,
+                lock_timeout,
+                encoding,
+                allow_bare_keys,
+            };
+            commands::validate::execute(&input, &options).await
+        }
+        Commands::Prune {
+            project,
+            input,
+            plan,
+            apply: _,
+            tombstone,
+            allow_empty,
+            allow_includes,
+            encoding,
+        } => {
+            let project = resolve_project(
+                project,
+                cli.project_from_git,
+                config.default_project.as_deref(),
+            )?;
+            let provider = SdkProvider::with_retry_policy(access_token()?, retry)
+                .await?
+                .with_project_cache_ttl(project_cache_ttl)
+                .with_concurrency(cli.concurrency);
+            if plan {
+                let input = config.resolve_env_file(input.as_deref(), profile)?;
+                let input_options = PushOptions {
+                    allow_includes,
+                    encoding,
+                    lock_timeout,
+                    ..Default::default()
+                };
+                commands::prune::plan(
+                    provider,
+                    &project,
+                    &input,
+                    &tombstone,
+                    allow_empty,
+                    &input_options,
+                )
+                .await
+            } else {
+                commands::prune::apply(provider, &project, &tombstone, audit.as_ref()).await
+            }
+        }
+        Commands::Config {
+            action: ConfigAction::Show { project },
+        } => {
+            let options = ShowOptions {
+                profile: cli.profile,
+                color,
+                token_command: cli.token_command.clone(),
+                project,
+                project_from_git: cli.project_from_git,
+            };
+            commands::config::show(&config, &options).await
+        }
+        Commands::Doctor => unreachable!("doctor is dispatched before the config is loaded"),
+    }
+}
+
+/// Resolve target projects from `--project`, falling back to `default_project`
+/// of the config and then to the git repository name
+///
+/// `--project-from-git` skips the config and asks git directly.
+pub(crate) fn resolve_projects(
+    projects: Vec<String>,
+    from_git: bool,
+    default_project: Option<&str>,
+) -> Result<Vec<String>> {
+    if projects.is_empty() {
+        return match default_project {
+            Some(project) if !from_git => Ok(vec![project.to_string()]),
+            _ => Ok(vec![git::project_name()?]),
+        };
+    }
+    if from_git {
+        return Err(AppError::InvalidArguments(
+            "--project and --project-from-git cannot be used together".to_string(),
+        ));
+    }
+    Ok(projects)
+}
+
+/// Resolve a single target project; see [`resolve_projects`]
+fn resolve_project(
+    project: Option<String>,
+    from_git: bool,
+    default_project: Option<&str>,
+) -> Result<String> {
+    let mut projects = resolve_projects(project.into_iter().collect(), from_git, default_project)?;
+    Ok(projects.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mutation(args: &[&str]) -> Option<&'static str> {
+        Cli::try_parse_from(args).unwrap().command.mutation()
+    }
+
+    #[test]
+    fn test_mutating_commands() {
+        assert_eq!(mutation(&["bwenv", "push", "-p", "App"]), Some("push"));
+        assert_eq!(
+            mutation(&["bwenv", "rotate", "-p", "App", "-k", "API_TOKEN"]),
+            Some("rotate")
+        );
+        assert_eq!(
+            mutation(&["bwenv", "prune", "-p", "App", "--apply"]),
+            Some("prune --apply")
+        );
+        assert_eq!(mutation(&["bwenv", "prune", "-p", "App", "--plan"]), None);
+        assert_eq!(
+            mutation(&[
+                "bwenv",
+                "rotate",
+                "-p",
+                "App",
+                "-k",
+                "API_TOKEN",
+                "--dry-run"
+            ]),
+            None
+        );
+        assert_eq!(mutation(&["bwenv", "pull", "-p", "App"]), None);
+        assert_eq!(mutation(&["bwenv", "status", "-p", "App"]), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_refuses_before_any_api_call() {
+        let cli = Cli::try_parse_from(["bwenv", "--read-only", "push", "-p", "App"]).unwrap();
+
+        // No access token is needed: the refusal comes first
+        let result = run(cli).await;
+
+        assert!(matches!(result, Err(AppError::ReadOnly(_))));
+    }
+
+    #[test]
+    fn test_resolve_projects_prefers_flag_then_config() {
+        let explicit = resolve_projects(vec!["Flag".to_string()], false, Some("Config")).unwrap();
+        assert_eq!(explicit, vec!["Flag"]);
+
+        let configured = resolve_project(None, false, Some("Config")).unwrap();
+        assert_eq!(configured, "Config");
+
+        // --project-from-git asks git even when the config names a project
+        if let Ok(from_git) = resolve_project(None, true, Some("Config")) {
+            assert_ne!(from_git, "Config");
+        }
+    }
+
+    #[test]
+    fn test_pull_example_conflicts() {
+        for flag in ["--template=.env.tpl", "--merge", "--force"] {
+            let args = ["bwenv", "pull", "-p", "App", "--example", flag];
+            assert!(Cli::try_parse_from(args).is_err(), "{}", flag);
         }
-        Commands::Validate { input } => commands::validate::execute(&input).await,
+        assert!(Cli::try_parse_from(["bwenv", "pull", "-p", "App", "--example"]).is_ok());
     }
 }
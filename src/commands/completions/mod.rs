@@ -0,0 +1,114 @@
+//! Completions command - Shell completion scripts
+//!
+//! The generated scripts complete `--project` with real project names by
+//! calling the hidden `bwenv __complete projects` subcommand.
+
+use crate::bitwarden::provider::SecretsProvider;
+use clap::{Command, ValueEnum};
+use clap_complete::Shell;
+
+/// Dynamic values the completion scripts can ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionSource {
+    /// Names of accessible projects
+    Projects,
+}
+
+/// bash: complete `--project` values from bwenv, everything else as generated
+const BASH_PROJECTS: &str = r#"
+_bwenv_with_projects() {
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ "$prev" == "--project" || "$prev" == "-p" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(bwenv __complete projects 2>/dev/null)" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return 0
+    fi
+    _bwenv "$@"
+}
+complete -F _bwenv_with_projects -o bashdefault -o default bwenv
+"#;
+
+/// zsh: inserted before the generated `_bwenv` so the action is defined
+const ZSH_PROJECTS: &str = r#"
+_bwenv_projects() {
+    local -a projects
+    projects=(${(f)"$(bwenv __complete projects 2>/dev/null)"})
+    compadd -a projects
+}
+"#;
+
+/// fish: an extra rule merged with the generated ones
+const FISH_PROJECTS: &str =
+    "complete -c bwenv -s p -l project -f -a '(bwenv __complete projects 2>/dev/null)'\n";
+
+/// Generate the completion script for `shell`
+///
+/// bash, zsh and fish scripts also complete project names; other shells get
+/// clap's static completions only.
+pub fn script(shell: Shell, command: &mut Command) -> String {
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, command, "bwenv", &mut buffer);
+    let generated = String::from_utf8_lossy(&buffer).into_owned();
+
+    match shell {
+        Shell::Bash => generated + BASH_PROJECTS,
+        Shell::Zsh => {
+            let with_action = generated.replace(":PROJECT:_default", ":PROJECT:_bwenv_projects");
+            match with_action.split_once('\n') {
+                Some((compdef, rest)) => format!("{}\n{}{}", compdef, ZSH_PROJECTS, rest),
+                None => with_action,
+            }
+        }
+        Shell::Fish => generated + FISH_PROJECTS,
+        _ => generated,
+    }
+}
+
+/// Project names for completion, sorted, one per line
+///
+/// Errors are swallowed: completion must never print an error into the
+/// user's prompt, so failures yield no candidates.
+pub async fn project_names<P: SecretsProvider>(provider: P) -> String {
+    let mut names: Vec<String> = match provider.list_projects().await {
+        Ok(projects) => projects.into_iter().map(|p| p.name).collect(),
+        Err(_) => return String::new(),
+    };
+    names.retain(|name| !name.contains('\n'));
+    names.sort();
+    names.dedup();
+    names.into_iter().map(|name| name + "\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    #[tokio::test]
+    async fn test_project_names_sorted_and_unique() {
+        let provider = MockProvider::new();
+        for (id, name) in [("p1", "Web"), ("p2", "Api"), ("p3", "Api")] {
+            provider.add_project(MockProvider::project(id, name));
+        }
+
+        assert_eq!(project_names(provider).await, "Api\nWeb\n");
+    }
+
+    #[test]
+    fn test_scripts_complete_projects() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = script(shell, &mut Cli::command());
+            assert!(
+                script.contains("bwenv __complete projects"),
+                "{} script lacks project completion",
+                shell
+            );
+        }
+
+        let zsh = script(Shell::Zsh, &mut Cli::command());
+        assert!(zsh.starts_with("#compdef bwenv\n"));
+        assert!(zsh.contains(":PROJECT:_bwenv_projects"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Config command - Inspect the effective configuration
+//!
+//! Shows what bwenv resolved from flags, environment variables, .bwenv.toml
+//! and profiles, without contacting Bitwarden.
+
+use crate::bitwarden::token::{ACCESS_TOKEN_ENV, TOKEN_COMMAND_ENV};
+use crate::cache::{self, PASSPHRASE_ENV};
+use crate::cli::resolve_projects;
+use crate::config::{Config, ValueSource, CONFIG_FILE};
+use crate::output::ColorChoice;
+use crate::Result;
+use colored::Colorize;
+use std::path::Path;
+
+/// Characters of the access token left visible at each end
+const VISIBLE_TOKEN_CHARS: usize = 4;
+
+/// Global flags that influence the effective configuration
+#[derive(Debug, Clone, Default)]
+pub struct ShowOptions {
+    pub profile: Option<String>,
+    pub color: ColorChoice,
+    pub token_command: Option<String>,
+    pub project: Option<String>,
+    pub project_from_git: bool,
+}
+
+/// One resolved setting and where it came from
+struct Setting {
+    name: &'static str,
+    value: String,
+    source: Option<ValueSource>,
+}
+
+impl Setting {
+    fn new(name: &'static str, value: impl Into<String>, source: ValueSource) -> Self {
+        Self {
+            name,
+            value: value.into(),
+            source: Some(source),
+        }
+    }
+
+    fn unsourced(name: &'static str, value: impl Into<String>) -> Self {
+        Self {
+            name,
+            value: value.into(),
+            source: None,
+        }
+    }
+}
+
+/// Print the effective configuration
+pub async fn show(config: &Config, options: &ShowOptions) -> Result<()> {
+    let settings = resolve(config, options)?;
+
+    println!("{}", "Effective configuration:".bold());
+    let width = settings.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    for setting in settings {
+        match setting.source {
+            Some(source) => println!(
+                "  {:width$}  {} ({})",
+                setting.name,
+                setting.value,
+                source,
+                width = width
+            ),
+            None => println!(
+                "  {:width$}  {}",
+                setting.name,
+                setting.value,
+                width = width
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve(config: &Config, options: &ShowOptions) -> Result<Vec<Setting>> {
+    let profile = options.profile.as_deref();
+    let mut settings = Vec::new();
+
+    let config_state = if Path::new(CONFIG_FILE).exists() {
+        "found"
+    } else {
+        "not found"
+    };
+    settings.push(Setting::unsourced(
+        "config file",
+        format!("{} ({})", CONFIG_FILE, config_state),
+    ));
+
+    settings.push(match profile {
+        Some(name) => Setting::new("profile", name, ValueSource::Flag),
+        None => Setting::new("profile", "none", ValueSource::Default),
+    });
+
+    // Resolved as every other command does, so this shows what they'd use
+    let default_project = config.default_project.as_deref();
+    let project_source = match (&options.project, default_project) {
+        (Some(_), _) => ValueSource::Flag,
+        (None, Some(_)) if !options.project_from_git => ValueSource::ConfigFile,
+        _ => ValueSource::Git,
+    };
+    settings.push(
+        match resolve_projects(
+            options.project.clone().into_iter().collect(),
+            options.project_from_git,
+            default_project,
+        ) {
+            Ok(mut projects) => Setting::new("project", projects.remove(0), project_source),
+            Err(e) => Setting::unsourced("project", format!("unresolved ({}); pass --project", e)),
+        },
+    );
+
+    let (env_file, source) = config.resolve_env_file_with_source(None, profile)?;
+    settings.push(Setting::new("env file", env_file, source));
+
+    let color_source = match options.color {
+        ColorChoice::Auto if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => {
+            ValueSource::Environment("NO_COLOR".to_string())
+        }
+        ColorChoice::Auto => ValueSource::Default,
+        _ => ValueSource::Flag,
+    };
+    let color = if options.color.enabled() {
+        "enabled"
+    } else {
+        "disabled"
+    };
+    settings.push(Setting::new("color", color, color_source));
+
+    // The token command is not run here, so its output can't leak
+    let token_command_source = match std::env::var(TOKEN_COMMAND_ENV) {
+        Ok(command) if options.token_command.as_deref() == Some(command.as_str()) => {
+            ValueSource::Environment(TOKEN_COMMAND_ENV.to_string())
+        }
+        _ => ValueSource::Flag,
+    };
+    settings.push(
+        match (&options.token_command, std::env::var(ACCESS_TOKEN_ENV)) {
+            (Some(command), _) => Setting::new(
+                "access token",
+                format!("output of '{}'", command),
+                token_command_source,
+            ),
+            (None, Ok(token)) => Setting::new(
+                "access token",
+                mask_token(&token),
+                ValueSource::Environment(ACCESS_TOKEN_ENV.to_string()),
+            ),
+            (None, Err(_)) => Setting::unsourced("access token", "not set"),
+        },
+    );
+
+    settings.push(if std::env::var_os(PASSPHRASE_ENV).is_some() {
+        Setting::new(
+            "cache key",
+            "passphrase",
+            ValueSource::Environment(PASSPHRASE_ENV.to_string()),
+        )
+    } else {
+        Setting::new("cache key", "access token", ValueSource::Default)
+    });
+    settings.push(Setting::unsourced(
+        "cache dir",
+        cache::get_cache_directory().display().to_string(),
+    ));
+
+    Ok(settings)
+}
+
+/// Show only the first and last few characters of a token
+pub fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= VISIBLE_TOKEN_CHARS * 2 {
+        return "*".repeat(chars.len());
+    }
+
+    let head: String = chars[..VISIBLE_TOKEN_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - VISIBLE_TOKEN_CHARS..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_token() {
+        assert_eq!(
+            mask_token("0.48b4774c-68ca-4539-a3d7-ac00018b4377.secretdata"),
+            "0.48…data"
+        );
+    }
+
+    #[test]
+    fn test_mask_short_token_hides_everything() {
+        assert_eq!(mask_token("abcdefgh"), "********");
+        assert_eq!(mask_token(""), "");
+    }
+
+    fn project_setting(config: &Config, project: Option<&str>) -> Setting {
+        let options = ShowOptions {
+            profile: None,
+            color: ColorChoice::Never,
+            token_command: None,
+            project: project.map(str::to_string),
+            project_from_git: false,
+        };
+        resolve(config, &options)
+            .unwrap()
+            .into_iter()
+            .find(|s| s.name == "project")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_project_from_config_and_flag() {
+        let config = Config {
+            default_project: Some("MyApp".to_string()),
+            ..Default::default()
+        };
+
+        let setting = project_setting(&config, None);
+        assert_eq!(setting.value, "MyApp");
+        assert_eq!(setting.source, Some(ValueSource::ConfigFile));
+
+        let setting = project_setting(&config, Some("Other"));
+        assert_eq!(setting.value, "Other");
+        assert_eq!(setting.source, Some(ValueSource::Flag));
+    }
+}
@@ -0,0 +1,414 @@
+//! Doctor command - Diagnose configuration, token and connectivity problems
+//!
+//! Runs each check in order, printing ✓ or ✗ (`[OK]` or `[FAIL]` without
+//! emoji) with a hint for every failure.
+//! A check that depends on a failed one is skipped. The token and secret
+//! values are never printed.
+
+use crate::bitwarden::provider::SecretsProvider;
+use crate::bitwarden::retry::RetryPolicy;
+use crate::bitwarden::sdk_provider::SdkProvider;
+use crate::bitwarden::token::{self, ACCESS_TOKEN_ENV, TOKEN_COMMAND_ENV};
+use crate::config::{Config, CONFIG_FILE};
+use crate::output::{icon, Symbol};
+use crate::{AppError, Result};
+use colored::Colorize;
+use std::future::Future;
+use std::path::Path;
+
+/// Result of one diagnostic check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass(String),
+    Fail { problem: String, hint: String },
+    Skipped,
+}
+
+/// A named diagnostic check and its outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Pass(detail.into()),
+        }
+    }
+
+    fn fail(name: &'static str, problem: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Fail {
+                problem: problem.into(),
+                hint: hint.into(),
+            },
+        }
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Skipped,
+        }
+    }
+
+    fn failed(&self) -> bool {
+        matches!(self.outcome, Outcome::Fail { .. })
+    }
+}
+
+/// Run every check against Bitwarden and print the report
+pub async fn execute(token_command: Option<&str>, retry: RetryPolicy) -> Result<()> {
+    let checks = run_checks(
+        Path::new(CONFIG_FILE),
+        token::access_token(token_command),
+        |token| SdkProvider::with_retry_policy(token, retry),
+    )
+    .await;
+    report(&checks)
+}
+
+/// Run the checks in order, connecting with `connect` once the token is valid
+pub async fn run_checks<P, F, Fut>(
+    config_path: &Path,
+    token: Result<String>,
+    connect: F,
+) -> Vec<Check>
+where
+    P: SecretsProvider,
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<P>>,
+{
+    let (config_check, config) = check_config(config_path);
+    let mut checks = vec![config_check];
+
+    let token = match token {
+        Ok(token) if token.trim().is_empty() => {
+            checks.push(Check::fail(
+                "Access token present",
+                "the access token is empty",
+                format!(
+                    "export {}=<token> or pass --token-command",
+                    ACCESS_TOKEN_ENV
+                ),
+            ));
+            None
+        }
+        Ok(token) => {
+            checks.push(Check::pass(
+                "Access token present",
+                format!("{} characters", token.len()),
+            ));
+            Some(token)
+        }
+        Err(AppError::TokenCommandFailed(message)) => {
+            checks.push(Check::fail(
+                "Access token present",
+                message,
+                format!(
+                    "Check the command in --token-command or {}",
+                    TOKEN_COMMAND_ENV
+                ),
+            ));
+            None
+        }
+        Err(_) => {
+            checks.push(Check::fail(
+                "Access token present",
+                format!("{} is not set", ACCESS_TOKEN_ENV),
+                format!(
+                    "export {}=<token> or pass --token-command",
+                    ACCESS_TOKEN_ENV
+                ),
+            ));
+            None
+        }
+    };
+
+    let organization = match token.as_deref().map(|token| (token, format_problem(token))) {
+        None => None,
+        Some((_, Some(problem))) => {
+            checks.push(Check::fail(
+                "Access token well-formed",
+                problem,
+                "Copy the whole token from Secrets Manager > Machine accounts > Access tokens",
+            ));
+            checks.push(Check::skipped("Organization resolved"));
+            None
+        }
+        Some((token, None)) => {
+            checks.push(Check::pass("Access token well-formed", "version.id.secret"));
+            let organization = SdkProvider::parse_organization_id(token).ok();
+            checks.push(match organization {
+                Some(id) => Check::pass("Organization resolved", id.to_string()),
+                None => Check::fail(
+                    "Organization resolved",
+                    "the token does not contain an organization ID",
+                    "Create a new access token",
+                ),
+            });
+            organization
+        }
+    };
+
+    let provider = match (token, organization) {
+        (Some(token), Some(_)) => match connect(token).await {
+            Ok(provider) => {
+                checks.push(Check::pass("Authentication", "logged in"));
+                Some(provider)
+            }
+            Err(e) => {
+                checks.push(Check::fail(
+                    "Authentication",
+                    e.to_string(),
+                    "Check network access to bitwarden.com and that the token is not expired or revoked",
+                ));
+                None
+            }
+        },
+        _ => {
+            checks.push(Check::skipped("Authentication"));
+            None
+        }
+    };
+
+    checks.push(match &provider {
+        Some(provider) => match provider.list_projects().await {
+            Ok(projects) if projects.is_empty() => Check::fail(
+                "Projects accessible",
+                "no projects are visible to this token",
+                "Grant the machine account access to at least one project",
+            ),
+            Ok(projects) => Check::pass(
+                "Projects accessible",
+                format!("{} project(s)", projects.len()),
+            ),
+            Err(e) => Check::fail(
+                "Projects accessible",
+                e.to_string(),
+                "Check the machine account's project permissions",
+            ),
+        },
+        None => Check::skipped("Projects accessible"),
+    });
+
+    if let Some(default_project) = config.and_then(|c| c.default_project) {
+        const NAME: &str = "Default project exists";
+        checks.push(match &provider {
+            Some(provider) => match provider.project_exists(&default_project).await {
+                Ok(true) => Check::pass(NAME, default_project),
+                Ok(false) => Check::fail(
+                    NAME,
+                    format!("no project named or with ID {}", default_project),
+                    format!(
+                        "Fix default_project in {} or grant the machine account access to it",
+                        config_path.display()
+                    ),
+                ),
+                Err(e) => Check::fail(
+                    NAME,
+                    e.to_string(),
+                    "Use the project's ID if its name is ambiguous",
+                ),
+            },
+            None => Check::skipped(NAME),
+        });
+    }
+
+    checks
+}
+
+/// Check the config file, returning it when it parsed
+fn check_config(path: &Path) -> (Check, Option<Config>) {
+    const NAME: &str = "Config file";
+    if !path.exists() {
+        return (
+            Check::pass(
+                NAME,
+                format!("{} not found; using defaults", path.display()),
+            ),
+            None,
+        );
+    }
+    match Config::load_from(path) {
+        Ok(config) => (
+            Check::pass(NAME, format!("{} parsed", path.display())),
+            Some(config),
+        ),
+        Err(e) => (
+            Check::fail(
+                NAME,
+                e.to_string(),
+                format!("Fix the TOML syntax in {}", path.display()),
+            ),
+            None,
+        ),
+    }
+}
+
+/// Why `token` is not `version.id.secret`, if it isn't
+fn format_problem(token: &str) -> Option<String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 || parts.iter().any(|part| part.is_empty()) {
+        return Some(format!(
+            "expected 3 dot-separated parts, found {}",
+            parts.iter().filter(|part| !part.is_empty()).count()
+        ));
+    }
+    if parts[0].parse::<u32>().is_err() {
+        return Some("the version part is not a number".to_string());
+    }
+    None
+}
+
+/// Print each check, failing if any check failed
+fn report(checks: &[Check]) -> Result<()> {
+    for check in checks {
+        match &check.outcome {
+            Outcome::Pass(detail) => {
+                println!("{}{}: {}", icon(Symbol::Pass).green(), check.name, detail)
+            }
+            Outcome::Fail { problem, hint } => {
+                println!("{}{}: {}", icon(Symbol::Fail).red(), check.name, problem);
+                println!("    {}{}", icon(Symbol::Hint), hint);
+            }
+            Outcome::Skipped => {
+                println!("{}{}: skipped", icon(Symbol::Skip).dimmed(), check.name)
+            }
+        }
+    }
+
+    let failed = checks.iter().filter(|c| c.failed()).count();
+    if failed > 0 {
+        return Err(AppError::CommandExecutionError(format!(
+            "{} of {} checks failed",
+            failed,
+            checks.len()
+        )));
+    }
+    println!("\nAll checks passed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const TOKEN: &str = "0.48b4774c-68ca-4539-a3d7-ac00018b4377.secretpart:key";
+
+    fn outcomes(checks: &[Check]) -> Vec<(&'static str, bool)> {
+        checks.iter().map(|c| (c.name, c.failed())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_all_checks_pass() {
+        let dir = tempdir().unwrap();
+        let checks = run_checks(
+            &dir.path().join(".bwenv.toml"),
+            Ok(TOKEN.to_string()),
+            |_| async { Ok(MockProvider::with_project("proj_1", "App")) },
+        )
+        .await;
+
+        assert!(checks.iter().all(|c| matches!(c.outcome, Outcome::Pass(_))));
+        assert!(report(&checks).is_ok());
+        // The token is only ever shown masked
+        let details: String = checks.iter().map(|c| format!("{:?}", c.outcome)).collect();
+        assert!(!details.contains("secretpart"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_skips_remote_checks() {
+        let dir = tempdir().unwrap();
+        let checks = run_checks(
+            &dir.path().join(".bwenv.toml"),
+            Err(AppError::BitwardenAuthFailed),
+            |_| async { Ok(MockProvider::with_project("proj_1", "App")) },
+        )
+        .await;
+
+        assert_eq!(
+            outcomes(&checks),
+            vec![
+                ("Config file", false),
+                ("Access token present", true),
+                ("Authentication", false),
+                ("Projects accessible", false),
+            ]
+        );
+        assert_eq!(checks[2].outcome, Outcome::Skipped);
+        assert!(matches!(
+            report(&checks),
+            Err(AppError::CommandExecutionError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_token_and_bad_config_fail() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join(".bwenv.toml");
+        fs::write(&config, "default_project = [").unwrap();
+
+        let checks = run_checks(&config, Ok("not-a-token".to_string()), |_| async {
+            Ok(MockProvider::with_project("proj_1", "App"))
+        })
+        .await;
+
+        assert_eq!(
+            outcomes(&checks),
+            vec![
+                ("Config file", true),
+                ("Access token present", false),
+                ("Access token well-formed", true),
+                ("Organization resolved", false),
+                ("Authentication", false),
+                ("Projects accessible", false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_accessible_projects_fails() {
+        let dir = tempdir().unwrap();
+        let checks = run_checks(
+            &dir.path().join(".bwenv.toml"),
+            Ok(TOKEN.to_string()),
+            |_| async { Ok(MockProvider::new()) },
+        )
+        .await;
+
+        let last = checks.last().unwrap();
+        assert_eq!(last.name, "Projects accessible");
+        assert!(last.failed());
+    }
+
+    #[tokio::test]
+    async fn test_default_project_must_exist() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join(".bwenv.toml");
+
+        fs::write(&config, "default_project = \"App\"\n").unwrap();
+        let checks = run_checks(&config, Ok(TOKEN.to_string()), |_| async {
+            Ok(MockProvider::with_project("proj_1", "App"))
+        })
+        .await;
+        let last = checks.last().unwrap();
+        assert_eq!(last.outcome, Outcome::Pass("App".to_string()));
+
+        fs::write(&config, "default_project = \"Missing\"\n").unwrap();
+        let checks = run_checks(&config, Ok(TOKEN.to_string()), |_| async {
+            Ok(MockProvider::with_project("proj_1", "App"))
+        })
+        .await;
+        let last = checks.last().unwrap();
+        assert_eq!(last.name, "Default project exists");
+        assert!(last.failed());
+    }
+}
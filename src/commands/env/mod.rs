@@ -0,0 +1,134 @@
+//! Env command - Print secrets as shell `export` statements
+//!
+//! Output is meant for `eval "$(bwenv env --project X)"`, so only the
+//! statements go to stdout; nothing is written to disk.
+
+use crate::bitwarden::provider::SecretsProvider;
+use crate::env::parser;
+use crate::{AppError, Result};
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+/// Shell syntax to emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Shell {
+    /// `export KEY='value'` for sh, bash and zsh
+    #[default]
+    Posix,
+    /// `set -x KEY 'value'`
+    Fish,
+    /// `$env:KEY="value"`
+    Powershell,
+}
+
+pub async fn execute<P: SecretsProvider>(provider: P, project: &str, shell: Shell) -> Result<()> {
+    let proj = if let Some(p) = provider.get_project(project).await? {
+        p
+    } else if let Some(p) = provider.get_project_by_name(project).await? {
+        p
+    } else {
+        return Err(AppError::ItemNotFound(format!("Project: {}", project)));
+    };
+
+    let secrets = provider.get_secrets_map(&proj.id).await?;
+    print!("{}", render(&secrets, shell)?);
+    Ok(())
+}
+
+/// Render one statement per secret, sorted by key
+///
+/// Keys that are not valid shell variable names are rejected rather than
+/// emitted, since they would break or subvert the eval'd script.
+pub fn render(secrets: &HashMap<String, String>, shell: Shell) -> Result<String> {
+    let mut keys: Vec<&String> = secrets.keys().collect();
+    keys.sort();
+
+    let invalid: Vec<&str> = keys
+        .iter()
+        .filter(|key| !parser::is_valid_key_name(key))
+        .map(|key| key.as_str())
+        .collect();
+    if !invalid.is_empty() {
+        return Err(AppError::EnvVarError(format!(
+            "Not valid shell variable names: {}",
+            invalid.join(", ")
+        )));
+    }
+
+    let mut output = String::new();
+    for key in keys {
+        let value = &secrets[key];
+        let line = match shell {
+            Shell::Posix => format!("export {}={}", key, posix_quote(value)),
+            Shell::Fish => format!("set -x {} {}", key, fish_quote(value)),
+            Shell::Powershell => format!("$env:{}={}", key, powershell_quote(value)),
+        };
+        output.push_str(&line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Single-quote for sh, closing the quote around each embedded `'`
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Single-quote for fish, where `\` and `'` are backslash-escaped
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Double-quote for PowerShell, backtick-escaping characters it expands
+fn powershell_quote(value: &str) -> String {
+    let escaped = value
+        .replace('`', "``")
+        .replace('"', "`\"")
+        .replace('$', "`$");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_posix_escapes_single_quotes() {
+        let secrets = secrets(&[("B", "it's"), ("A", "$HOME")]);
+
+        assert_eq!(
+            render(&secrets, Shell::Posix).unwrap(),
+            "export A='$HOME'\nexport B='it'\\''s'\n"
+        );
+    }
+
+    #[test]
+    fn test_render_fish_and_powershell() {
+        let secrets = secrets(&[("KEY", "a'b\\c$d\"e")]);
+
+        assert_eq!(
+            render(&secrets, Shell::Fish).unwrap(),
+            "set -x KEY 'a\\'b\\\\c$d\"e'\n"
+        );
+        assert_eq!(
+            render(&secrets, Shell::Powershell).unwrap(),
+            "$env:KEY=\"a'b\\c`$d`\"e\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_rejects_invalid_names() {
+        let secrets = secrets(&[("OK", "1"), ("BAD;rm", "2"), ("1ST", "3")]);
+
+        let err = render(&secrets, Shell::Posix).unwrap_err();
+
+        assert!(err.to_string().contains("1ST, BAD;rm"));
+    }
+}
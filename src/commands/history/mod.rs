@@ -0,0 +1,76 @@
+//! History command - Show when a secret changed
+//!
+//! Lists revisions of a single secret with timestamps and authors when the
+//! provider exposes them. Values are never shown.
+
+use crate::bitwarden::provider::SecretsProvider;
+use crate::{AppError, Result};
+
+pub async fn execute<P: SecretsProvider>(provider: P, project: &str, key: &str) -> Result<()> {
+    let proj = if let Some(p) = provider.get_project(project).await? {
+        p
+    } else if let Some(p) = provider.get_project_by_name(project).await? {
+        p
+    } else {
+        return Err(AppError::ItemNotFound(format!("Project: {}", project)));
+    };
+
+    let secret = provider
+        .list_secrets(&proj.id)
+        .await?
+        .into_iter()
+        .find(|s| s.key == key)
+        .ok_or_else(|| AppError::ItemNotFound(format!("Secret {} in {}", key, proj.name)))?;
+
+    let mut history = provider.list_secret_history(&secret.id).await?;
+    history.sort_by_key(|r| std::cmp::Reverse(r.changed_at));
+
+    println!("History of {} in {} (newest first):", key, proj.name);
+    if history.is_empty() {
+        println!("  No revisions recorded");
+    }
+    for revision in history {
+        let by = revision
+            .changed_by
+            .map(|who| format!(" by {}", who))
+            .unwrap_or_default();
+        println!(
+            "  {}  {}{}",
+            revision.changed_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            revision.event,
+            by
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::provider::RevisionEvent;
+    use crate::bitwarden::MockProvider;
+
+    #[tokio::test]
+    async fn test_mock_records_synthetic_history() {
+        let provider = MockProvider::with_project("proj_1", "App");
+        let secret = provider
+            .create_secret("proj_1", "API_KEY", "v1", None)
+            .await
+            .unwrap();
+        provider
+            .update_secret(&secret.id, "API_KEY", "v2", None)
+            .await
+            .unwrap();
+
+        let history = provider.list_secret_history(&secret.id).await.unwrap();
+
+        let events: Vec<_> = history.iter().map(|r| r.event).collect();
+        assert_eq!(events, vec![RevisionEvent::Created, RevisionEvent::Updated]);
+        assert!(execute(provider.clone(), "App", "API_KEY").await.is_ok());
+        assert!(matches!(
+            execute(provider, "App", "MISSING").await,
+            Err(AppError::ItemNotFound(_))
+        ));
+    }
+}
@@ -2,15 +2,31 @@
 //!
 //! Creates configuration file and sets up project for bwenv management.
 
+use crate::git;
+use crate::output::{icon, Symbol};
 use crate::Result;
+use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
-pub async fn execute() -> Result<()> {
+/// `.gitignore` entries that keep local env files out of git
+///
+/// `.env.example` holds keys only (see `pull --example`) and stays tracked.
+const GITIGNORE_ENTRIES: &[&str] = &[".env", ".env.*", "!.env.example"];
+
+/// Paths that must be ignored for the env files to count as covered
+const ENV_SAMPLES: &[&str] = &[".env", ".env.local"];
+
+pub async fn execute(gitignore: bool) -> Result<()> {
+    write_config()?;
+    check_gitignore(Path::new(".gitignore"), gitignore)
+}
+
+fn write_config() -> Result<()> {
     let config_path = Path::new(".bwenv.toml");
 
     if config_path.exists() {
-        println!("⚠️  .bwenv.toml already exists");
+        println!("{}.bwenv.toml already exists", icon(Symbol::Warn));
         println!("   Use --force to overwrite (not yet implemented)");
         return Ok(());
     }
@@ -34,7 +50,10 @@ show_secrets = false
 
     fs::write(config_path, config_content)?;
 
-    println!("✓ Created .bwenv.toml configuration file");
+    println!(
+        "{}Created .bwenv.toml configuration file",
+        icon(Symbol::Pass)
+    );
     println!();
     println!("Next steps:");
     println!("  1. Edit .bwenv.toml and set your default project");
@@ -44,3 +63,147 @@ show_secrets = false
 
     Ok(())
 }
+
+/// Report whether env files are ignored, optionally fixing `.gitignore`
+fn check_gitignore(path: &Path, update: bool) -> Result<()> {
+    println!();
+
+    let tracked: Vec<String> = git::tracked_files(&[".env", ".env.*"])
+        .into_iter()
+        .filter(|file| file != ".env.example")
+        .collect();
+    if !tracked.is_empty() {
+        eprintln!(
+            "{}{}",
+            icon(Symbol::Alert),
+            "WARNING: env files are already tracked by git:"
+                .red()
+                .bold()
+        );
+        for file in &tracked {
+            eprintln!("   - {}", file);
+        }
+        eprintln!("   Their secrets are in your git history. Untrack them with");
+        eprintln!("   'git rm --cached <file>' and rotate every secret they contain.");
+    }
+
+    if update {
+        let added = update_gitignore(path)?;
+        if added.is_empty() {
+            println!(
+                "{}{} already ignores env files",
+                icon(Symbol::Pass),
+                path.display()
+            );
+        } else {
+            println!(
+                "{}Added to {}: {}",
+                icon(Symbol::Pass),
+                path.display(),
+                added.join(", ")
+            );
+        }
+    } else if is_covered(path)? {
+        println!("{}env files are ignored by git", icon(Symbol::Pass));
+    } else {
+        println!(
+            "{}env files are not ignored by git; run 'bwenv init --gitignore' to fix",
+            icon(Symbol::Warn)
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether git ignores the common env file names
+///
+/// Outside a git repository, `.gitignore` itself is checked for the entries.
+fn is_covered(path: &Path) -> Result<bool> {
+    let mut in_repo = true;
+    for sample in ENV_SAMPLES {
+        match git::is_ignored(sample) {
+            Some(true) => {}
+            Some(false) => return Ok(false),
+            None => in_repo = false,
+        }
+    }
+    if in_repo {
+        return Ok(true);
+    }
+
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    Ok(missing_entries(&existing).is_empty())
+}
+
+/// Entries of [`GITIGNORE_ENTRIES`] not present as lines of `gitignore`
+fn missing_entries(gitignore: &str) -> Vec<&'static str> {
+    GITIGNORE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| !gitignore.lines().any(|line| line.trim() == *entry))
+        .collect()
+}
+
+/// Append missing env entries to `.gitignore`, creating it if needed
+///
+/// Returns the entries that were added.
+fn update_gitignore(path: &Path) -> Result<Vec<&'static str>> {
+    let mut content = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+
+    let missing = missing_entries(&content);
+    if missing.is_empty() {
+        return Ok(missing);
+    }
+
+    if !content.is_empty() {
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+    content.push_str("# Local env files (bwenv)\n");
+    for entry in &missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(path, content)?;
+
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_update_gitignore_creates_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+
+        let added = update_gitignore(&path).unwrap();
+
+        assert_eq!(added, GITIGNORE_ENTRIES);
+        assert!(missing_entries(&fs::read_to_string(&path).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_update_gitignore_avoids_duplicates() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        fs::write(&path, "target/\n.env").unwrap();
+
+        let added = update_gitignore(&path).unwrap();
+        assert_eq!(added, vec![".env.*", "!.env.example"]);
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("target/\n.env\n"));
+        assert_eq!(content.lines().filter(|l| *l == ".env").count(), 1);
+
+        assert!(update_gitignore(&path).unwrap().is_empty());
+    }
+}
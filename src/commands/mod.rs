@@ -2,8 +2,16 @@
 //!
 //! Each subcommand has its own module for implementation.
 
+pub mod completions;
+pub mod config;
+pub mod doctor;
+pub mod env;
+pub mod history;
 pub mod init;
+pub mod prune;
 pub mod pull;
 pub mod push;
+pub mod rotate;
 pub mod status;
 pub mod validate;
+pub mod verify;
@@ -0,0 +1,361 @@
+//! Prune command - Delete remote secrets missing from the local .env
+//!
+//! Deletion is two-phase: `--plan` records what would be deleted in a
+//! tombstone file, and `--apply` deletes exactly those secrets, aborting if
+//! the remote project changed in between.
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog, AuditOutcome};
+use crate::bitwarden::provider::SecretsProvider;
+use crate::commands::push::{self, PushOptions};
+use crate::commands::status::find_project;
+use crate::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Default location of the tombstone file
+pub const DEFAULT_TOMBSTONE: &str = ".bwenv-prune.json";
+
+/// Secrets planned for deletion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tombstone {
+    pub project_id: String,
+    pub project_name: String,
+    pub created_at: DateTime<Utc>,
+    pub secrets: Vec<TombstoneEntry>,
+}
+
+/// A single secret planned for deletion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TombstoneEntry {
+    pub id: String,
+    pub key: String,
+}
+
+/// Record remote secrets absent from `input` in a tombstone file
+///
+/// `input` is read the way `push` with `input_options` reads it, so a plan
+/// deletes exactly what `push --prune` would.
+pub async fn plan<P: SecretsProvider>(
+    provider: P,
+    project: &str,
+    input: &str,
+    tombstone: &str,
+    allow_empty: bool,
+    input_options: &PushOptions,
+) -> Result<()> {
+    if !Path::new(input).exists() {
+        return Err(AppError::EnvFileReadError(format!(
+            "File {} not found",
+            input
+        )));
+    }
+
+    let proj = find_project(&provider, project).await?;
+    let local = push::load_input(input, input_options)?;
+    if local.is_empty() && !allow_empty {
+        return Err(AppError::InvalidArguments(format!(
+            "No secrets found in {}; pruning against it would delete every secret in {}. Pass --allow-empty if this is intended",
+            input, proj.name
+        )));
+    }
+
+    let mut secrets: Vec<TombstoneEntry> = provider
+        .list_secrets(&proj.id)
+        .await?
+        .into_iter()
+        .filter(|s| !local.contains_key(&s.key))
+        .map(|s| TombstoneEntry {
+            id: s.id,
+            key: s.key,
+        })
+        .collect();
+    secrets.sort_by(|a, b| a.key.cmp(&b.key));
+
+    if secrets.is_empty() {
+        println!(
+            "Nothing to prune: every secret in {} is in {}",
+            proj.name, input
+        );
+        return Ok(());
+    }
+
+    let plan = Tombstone {
+        project_id: proj.id.clone(),
+        project_name: proj.name.clone(),
+        created_at: Utc::now(),
+        secrets,
+    };
+    fs::write(tombstone, serde_json::to_string_pretty(&plan)?).map_err(|e| {
+        AppError::EnvFileWriteError(format!("Failed to write {}: {}", tombstone, e))
+    })?;
+
+    println!(
+        "Planned deletion of {} secrets from {}:",
+        plan.secrets.len(),
+        proj.name
+    );
+    for entry in &plan.secrets {
+        println!("  - {}", entry.key);
+    }
+    println!();
+    println!(
+        "Review {} and run 'bwenv prune --apply' to delete",
+        tombstone
+    );
+    Ok(())
+}
+
+/// Delete the secrets recorded in a tombstone file
+///
+/// Fails with [`AppError::Conflict`] without deleting anything if the tombstone
+/// belongs to another project or a planned secret was removed or renamed.
+/// Each deleted key is recorded in `audit`, if given.
+pub async fn apply<P: SecretsProvider>(
+    provider: P,
+    project: &str,
+    tombstone: &str,
+    audit: Option<&AuditLog>,
+) -> Result<()> {
+    let content = fs::read_to_string(tombstone).map_err(|e| {
+        AppError::EnvFileReadError(format!(
+            "Failed to read {}: {}. Run 'bwenv prune --plan' first",
+            tombstone, e
+        ))
+    })?;
+    let plan: Tombstone = serde_json::from_str(&content)?;
+
+    let proj = find_project(&provider, project).await?;
+    if proj.id != plan.project_id {
+        return Err(AppError::Conflict(format!(
+            "{} was planned for project {}, not {}",
+            tombstone, plan.project_name, proj.name
+        )));
+    }
+
+    let remote: HashMap<String, String> = provider
+        .list_secrets(&proj.id)
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s.key))
+        .collect();
+
+    let drifted: Vec<&str> = plan
+        .secrets
+        .iter()
+        .filter(|entry| remote.get(&entry.id) != Some(&entry.key))
+        .map(|entry| entry.key.as_str())
+        .collect();
+    if !drifted.is_empty() {
+        return Err(AppError::Conflict(format!(
+            "Remote secrets changed since the plan was made: {}. Run 'bwenv prune --plan' again",
+            drifted.join(", ")
+        )));
+    }
+
+    let ids: Vec<&str> = plan.secrets.iter().map(|entry| entry.id.as_str()).collect();
+    let deleted = provider.delete_secrets(&ids).await;
+    if let Some(audit) = audit {
+        let outcome = match deleted {
+            Ok(_) => AuditOutcome::Success,
+            Err(_) => AuditOutcome::Failure,
+        };
+        let entries: Vec<AuditEntry> = plan
+            .secrets
+            .iter()
+            .map(|entry| {
+                AuditEntry::new(
+                    "prune",
+                    &proj.id,
+                    Some(&entry.key),
+                    AuditAction::Delete,
+                    outcome,
+                )
+            })
+            .collect();
+        audit.append(&entries)?;
+    }
+    deleted?;
+
+    // A tombstone is single-use
+    fs::remove_file(tombstone)?;
+
+    println!("Deleted {} secrets from {}:", plan.secrets.len(), proj.name);
+    for entry in &plan.secrets {
+        println!("  - {}", entry.key);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use tempfile::tempdir;
+
+    async fn seed(provider: &MockProvider, keys: &[&str]) {
+        for key in keys {
+            provider
+                .create_secret("proj_1", key, "value", None)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_then_apply_deletes_remote_only_keys() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        seed(&provider, &["KEEP", "STALE_A", "STALE_B"]).await;
+
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        let tombstone = dir.path().join("prune.json");
+        fs::write(&input, "KEEP=value\n").unwrap();
+        let (input, tombstone) = (input.to_str().unwrap(), tombstone.to_str().unwrap());
+
+        plan(
+            provider.clone(),
+            "proj_1",
+            input,
+            tombstone,
+            false,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        let planned: Tombstone =
+            serde_json::from_str(&fs::read_to_string(tombstone).unwrap()).unwrap();
+        let keys: Vec<_> = planned.secrets.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["STALE_A", "STALE_B"]);
+
+        apply(provider.clone(), "proj_1", tombstone, None)
+            .await
+            .unwrap();
+
+        let remote = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(remote.len(), 1);
+        assert!(remote.contains_key("KEEP"));
+        assert!(!Path::new(tombstone).exists());
+    }
+
+    #[tokio::test]
+    async fn test_apply_aborts_when_remote_drifted() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        seed(&provider, &["KEEP", "STALE_A", "STALE_B"]).await;
+
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        let tombstone = dir.path().join("prune.json");
+        fs::write(&input, "KEEP=value\n").unwrap();
+        let (input, tombstone) = (input.to_str().unwrap(), tombstone.to_str().unwrap());
+
+        plan(
+            provider.clone(),
+            "proj_1",
+            input,
+            tombstone,
+            false,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // Someone removes a planned secret before the plan is applied
+        let stale = provider
+            .list_secrets("proj_1")
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|s| s.key == "STALE_A")
+            .unwrap();
+        provider.delete_secret(&stale.id).await.unwrap();
+
+        let result = apply(provider.clone(), "proj_1", tombstone, None).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(provider
+            .get_secrets_map("proj_1")
+            .await
+            .unwrap()
+            .contains_key("STALE_B"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_refuses_empty_input_unless_allowed() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        seed(&provider, &["A", "B"]).await;
+
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        let tombstone = dir.path().join("prune.json");
+        fs::write(&input, "").unwrap();
+        let (input, tombstone) = (input.to_str().unwrap(), tombstone.to_str().unwrap());
+
+        let result = plan(
+            provider.clone(),
+            "proj_1",
+            input,
+            tombstone,
+            false,
+            &Default::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+        assert!(!Path::new(tombstone).exists());
+
+        plan(
+            provider.clone(),
+            "proj_1",
+            input,
+            tombstone,
+            true,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        let planned: Tombstone =
+            serde_json::from_str(&fs::read_to_string(tombstone).unwrap()).unwrap();
+        assert_eq!(planned.secrets.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_plan_reads_input_like_push() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        seed(&provider, &["BASE", "INCLUDED", "STALE"]).await;
+
+        let dir = tempdir().unwrap();
+        let fragments = dir.path().join(".env.d");
+        fs::create_dir(&fragments).unwrap();
+        fs::write(
+            fragments.join("10-base.env"),
+            "BASE=1\n#!include ../shared.env\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("shared.env"), b"INCLUDED=caf\xe9\n").unwrap();
+        let tombstone = dir.path().join("prune.json");
+        let (input, tombstone) = (fragments.to_str().unwrap(), tombstone.to_str().unwrap());
+
+        let options = PushOptions {
+            allow_includes: true,
+            encoding: "latin1".parse().unwrap(),
+            ..Default::default()
+        };
+        plan(
+            provider.clone(),
+            "proj_1",
+            input,
+            tombstone,
+            false,
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let planned: Tombstone =
+            serde_json::from_str(&fs::read_to_string(tombstone).unwrap()).unwrap();
+        let keys: Vec<_> = planned.secrets.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["STALE"]);
+    }
+}
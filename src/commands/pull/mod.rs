@@ -2,64 +2,1245 @@
 //!
 //! Fetches secrets from Bitwarden Secrets Manager and writes to local .env file.
 
-use crate::bitwarden::provider::SecretsProvider;
+use crate::bitwarden::provider::{Project, SecretsProvider};
+use crate::cache::{CachedProject, SecretCache};
+use crate::env::encoding::TextEncoding;
+use crate::env::parser::{self, MergeStrategy, SortMode};
+use crate::env::template::PlaceholderStyle;
+use crate::env::{collisions, fragments, lock, permissions, sections, template};
+use crate::output::{self, icon, OutputFormat, Symbol};
 use crate::{AppError, Result};
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
+
+/// How to resolve a key present in more than one pulled project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CollisionPolicy {
+    /// Abort the pull
+    #[default]
+    Error,
+    /// Prefix colliding keys with their project name, e.g. `MY_APP_API_KEY`
+    Prefix,
+    /// Keep the value from the project listed last
+    LastWins,
+}
+
+/// Case change applied to every key before it is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum KeyTransform {
+    /// Keep keys as stored in Bitwarden
+    #[default]
+    None,
+    /// `API_KEY` becomes `api_key`
+    Lower,
+    /// `api_key` becomes `API_KEY`
+    Upper,
+}
+
+impl KeyTransform {
+    pub fn apply(self, key: &str) -> String {
+        match self {
+            KeyTransform::None => key.to_string(),
+            KeyTransform::Lower => key.to_lowercase(),
+            KeyTransform::Upper => key.to_uppercase(),
+        }
+    }
+
+    fn flag(self) -> &'static str {
+        match self {
+            KeyTransform::None => "--key-transform none",
+            KeyTransform::Lower => "--key-transform lower",
+            KeyTransform::Upper => "--key-transform upper",
+        }
+    }
+}
+
+/// Options controlling how secrets are pulled
+#[derive(Debug, Clone, Default)]
+pub struct PullOptions {
+    /// Overwrite an existing output file
+    pub force: bool,
+    /// Merge into an existing output file instead of replacing it
+    pub merge: bool,
+    /// Which value wins for a key already in the output file when merging
+    pub on_conflict: MergeStrategy,
+    /// Only update values of keys the existing output file already has,
+    /// never adding or removing keys
+    pub merge_from_remote: bool,
+    /// Collision handling when pulling several projects
+    pub on_collision: CollisionPolicy,
+    /// Print which project each key came from
+    pub verbose: bool,
+    /// Render this template file instead of writing a flat .env
+    pub template: Option<String>,
+    /// Leave unresolved template placeholders in place instead of failing
+    pub allow_missing: bool,
+    /// Placeholder syntax of the template
+    pub placeholder_style: PlaceholderStyle,
+    /// Key ordering in the written .env file
+    pub sort: SortMode,
+    /// Serve fresh projects from this cache and store live fetches in it
+    pub cache: Option<SecretCache>,
+    /// Write keys without real values, for a committable `.env.example`
+    pub example: bool,
+    /// Write each secret's note as a comment above its key
+    pub notes_as_comments: bool,
+    /// Write every key as `export KEY=VALUE`; otherwise keys keep the prefix
+    /// they had in the file being replaced
+    pub export: bool,
+    /// Format of the written file, env when not set
+    pub format: Option<OutputFormat>,
+    /// Lock the output while writing, waiting at most this long for another
+    /// process to finish; `None` disables locking
+    pub lock_timeout: Option<Duration>,
+    /// Only refresh keys under this `# === Name ===` header of the existing
+    /// output file, like `merge_from_remote` limited to one section
+    pub section: Option<String>,
+    /// Case change applied to keys before they are merged and written
+    pub key_transform: KeyTransform,
+    /// Refuse to pull a project with more secrets than this
+    pub max_secrets: Option<usize>,
+}
+
+impl PullOptions {
+    /// Whether only keys of the existing output file are refreshed
+    fn refreshes_existing(&self) -> bool {
+        self.merge_from_remote || self.section.is_some()
+    }
+}
+
+/// Formats `pull --format` accepts
+pub const FORMATS: [OutputFormat; 3] = [OutputFormat::Env, OutputFormat::Json, OutputFormat::Yaml];
+
+/// Notes of pulled secrets, by project name and then key
+type ProjectNotes = HashMap<String, HashMap<String, String>>;
+
+/// A pulled project with its secrets in the provider's order
+type ProjectSecrets = (Project, Vec<(String, String)>);
+
+/// A merged secret value together with the project it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcedValue {
+    pub value: String,
+    pub project: String,
+}
+
+/// How a pull wrote its output
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PullKind {
+    /// A .env file of the pulled secrets
+    #[default]
+    Env,
+    /// A .env file of keys with blank or descriptive values
+    Example,
+    /// A file in a structured `--format`
+    Structured(OutputFormat),
+    /// The rendered template at this path
+    Template(String),
+    /// `merge_from_remote` into a file holding `total` keys
+    Refresh { total: usize },
+    /// The keys of `section`, which holds `total` keys
+    Section { section: String, total: usize },
+}
+
+/// What a pull wrote, returned so callers and tests needn't parse stdout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullPlan {
+    /// The file written to
+    pub output: String,
+    /// Keys pulled from Bitwarden after merging projects, in order
+    pub pulled: Vec<String>,
+    /// The project each of `pulled` came from
+    pub sources: Vec<String>,
+    /// With `merge_from_remote` or `section`, the keys whose values were
+    /// refreshed
+    pub refreshed: Option<Vec<String>>,
+    /// Whether the output was written; false when there was nothing to pull
+    pub written: bool,
+    /// What kind of file was written
+    pub kind: PullKind,
+}
+
+impl PullPlan {
+    fn new(output: &str, merged: &[(String, SourcedValue)]) -> Self {
+        Self {
+            output: output.to_string(),
+            pulled: merged.iter().map(|(key, _)| key.clone()).collect(),
+            sources: merged.iter().map(|(_, s)| s.project.clone()).collect(),
+            refreshed: None,
+            written: true,
+            kind: PullKind::Env,
+        }
+    }
+
+    /// Print what was written; `verbose` also lists the project of each key
+    pub fn render(&self, verbose: bool) {
+        if !self.written {
+            println!("No secrets found in project");
+            return;
+        }
+        let listed = matches!(
+            self.kind,
+            PullKind::Env | PullKind::Example | PullKind::Refresh { .. }
+        );
+        if verbose && listed {
+            for (key, project) in self.pulled.iter().zip(&self.sources) {
+                println!("  {} <- {}", key, project);
+            }
+        }
+
+        let output = &self.output;
+        let count = self.pulled.len();
+        let refreshed = self.refreshed.as_ref().map_or(0, Vec::len);
+        match &self.kind {
+            PullKind::Env => println!("Successfully pulled {} secrets to {}", count, output),
+            PullKind::Example => println!("Wrote {} example keys to {}", count, output),
+            PullKind::Structured(format) => println!(
+                "Successfully pulled {} secrets to {} as {}",
+                count, output, format
+            ),
+            PullKind::Template(template) => {
+                println!("Successfully rendered {} to {}", template, output)
+            }
+            PullKind::Refresh { total } => println!(
+                "Refreshed {} of {} keys in {} from Bitwarden",
+                refreshed, total, output
+            ),
+            PullKind::Section { section, total } => println!(
+                "Refreshed {} of {} keys in section {} of {} from Bitwarden",
+                refreshed, total, section, output
+            ),
+        }
+    }
+}
 
 pub async fn execute<P: SecretsProvider>(
     provider: P,
-    project: &str,
+    projects: &[String],
     output: &str,
-    force: bool,
-) -> Result<()> {
-    // Check if output file exists
-    if Path::new(output).exists() && !force {
+    options: &PullOptions,
+) -> Result<PullPlan> {
+    let output = &output_path(output);
+    check_output(output, options)?;
+
+    // Example mode and comments need notes, which the cache does not keep
+    let cache = options
+        .cache
+        .as_ref()
+        .filter(|_| !options.example && !options.notes_as_comments);
+
+    let mut sources = Vec::new();
+    let mut notes = ProjectNotes::new();
+    for project in projects {
+        if let Some(cache) = cache {
+            match cache.load(project) {
+                Ok(Some(entry)) if cache.is_fresh(&entry) => {
+                    println!("Using cached secrets for project: {}", entry.project.name);
+                    sources.push(from_cache(entry));
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("{}Ignoring unreadable cache: {}", icon(Symbol::Warn), e),
+            }
+        }
+
+        // Get project by name or ID
+        let proj = if let Some(p) = provider.get_project(project).await? {
+            p
+        } else if let Some(p) = provider.get_project_by_name(project).await? {
+            p
+        } else {
+            return Err(AppError::ItemNotFound(format!("Project: {}", project)));
+        };
+
+        println!("Pulling secrets from project: {}", proj.name);
+
+        // Get secrets, keeping the order the provider returns them in
+        let remote = provider.list_secrets(&proj.id).await?;
+        if options.notes_as_comments {
+            let project_notes = remote
+                .iter()
+                .filter_map(|s| Some((s.key.clone(), s.note.clone()?)))
+                .collect();
+            notes.insert(proj.name.clone(), project_notes);
+        }
+        if options.example {
+            let placeholders = remote
+                .into_iter()
+                .map(|s| (s.key, example_value(s.note.as_deref())))
+                .collect();
+            sources.push((proj, placeholders));
+            continue;
+        }
+        let secrets: Vec<(String, String)> = remote.into_iter().map(|s| (s.key, s.value)).collect();
+
+        if let Some(cache) = cache {
+            if let Err(e) = cache.store(project, &proj, &secrets) {
+                eprintln!("{}Failed to update cache: {}", icon(Symbol::Warn), e);
+            }
+        }
+        sources.push((proj, secrets));
+    }
+
+    write_sources(&sources, &notes, output, options)
+}
+
+/// Pull using only the local cache, without contacting Bitwarden
+///
+/// Entries older than the cache TTL are still used, with a warning.
+pub fn execute_offline(
+    projects: &[String],
+    output: &str,
+    options: &PullOptions,
+) -> Result<PullPlan> {
+    let cache = options
+        .cache
+        .as_ref()
+        .ok_or_else(|| AppError::InvalidArguments("Offline pull requires a cache".to_string()))?;
+
+    let output = &output_path(output);
+    check_output(output, options)?;
+
+    let mut sources = Vec::new();
+    for project in projects {
+        let entry = cache.load(project)?.ok_or_else(|| {
+            AppError::ItemNotFound(format!("No cached secrets for project: {}", project))
+        })?;
+
+        if !cache.is_fresh(&entry) {
+            eprintln!(
+                "{}Cached secrets for {} are stale (fetched {})",
+                icon(Symbol::Warn),
+                entry.project.name,
+                entry.fetched_at.to_rfc3339()
+            );
+        }
+        println!("Using cached secrets for project: {}", entry.project.name);
+        let (proj, secrets) = from_cache(entry);
+        if options.example {
+            let blank = secrets.into_iter().map(|(key, _)| (key, String::new()));
+            sources.push((proj, blank.collect()));
+        } else {
+            sources.push((proj, secrets));
+        }
+    }
+
+    write_sources(&sources, &ProjectNotes::new(), output, options)
+}
+
+/// The file to write for `output`; a `.env.d/` directory gets a single
+/// fragment holding every pulled secret
+fn output_path(output: &str) -> String {
+    if !fragments::is_fragment_dir(output) {
+        return output.to_string();
+    }
+    let path = Path::new(output).join(fragments::PULL_FRAGMENT);
+    eprintln!(
+        "{}{} is a directory of fragments; writing every secret to {}, which overrides earlier fragments",
+        icon(Symbol::Warn),
+        output,
+        path.display()
+    );
+    path.display().to_string()
+}
+
+/// Reject unsupported formats, then refuse to replace an existing output
+/// file unless forced
+fn check_output(output: &str, options: &PullOptions) -> Result<()> {
+    let format = options.format.unwrap_or(OutputFormat::Env);
+    format.ensure_supported("pull", &FORMATS)?;
+    if format.is_structured()
+        && (options.template.is_some()
+            || options.notes_as_comments
+            || options.export
+            || options.merge
+            || options.refreshes_existing())
+    {
+        return Err(AppError::InvalidArguments(format!(
+            "--format {} cannot be combined with --template, --notes-as-comments, --export, --merge, --merge-from-remote or --section",
+            format
+        )));
+    }
+
+    if options.refreshes_existing() && !Path::new(output).exists() {
+        let flag = if options.section.is_some() {
+            "--section"
+        } else {
+            "--merge-from-remote"
+        };
+        return Err(AppError::EnvFileReadError(format!(
+            "File {} not found. {} only refreshes keys of an existing file",
+            output, flag
+        )));
+    }
+    // An example file holds no secrets, so it is regenerated without --force
+    if Path::new(output).exists()
+        && !options.force
+        && !options.merge
+        && !options.refreshes_existing()
+        && !options.example
+    {
         return Err(AppError::EnvFileWriteError(format!(
-            "File {} already exists. Use --force to overwrite",
+            "File {} already exists. Use --force to overwrite or --merge to merge into it",
             output
         )));
     }
+    permissions::check_writable(Path::new(output))?;
+    Ok(())
+}
 
-    // Get project by name or ID
-    let proj = if let Ok(Some(p)) = provider.get_project(project).await {
-        p
-    } else if let Ok(Some(p)) = provider.get_project_by_name(project).await {
-        p
-    } else {
-        return Err(AppError::ItemNotFound(format!("Project: {}", project)));
+/// Placeholder written instead of a value in example mode
+///
+/// Derived only from the secret's note, never its value.
+fn example_value(note: Option<&str>) -> String {
+    match note.map(|n| n.split_whitespace().collect::<Vec<_>>().join(" ")) {
+        Some(note) if !note.is_empty() => format!("<{}>", note),
+        _ => String::new(),
+    }
+}
+
+fn from_cache(entry: CachedProject) -> (Project, Vec<(String, String)>) {
+    let secrets = entry
+        .secrets
+        .iter()
+        .map(|(key, value)| (key.clone(), value.to_string()))
+        .collect();
+    (entry.project, secrets)
+}
+
+/// Fail before anything is written if a project has more than `max` secrets,
+/// e.g. an organization-wide project pulled into one service's .env
+fn check_max_secrets(sources: &[ProjectSecrets], max: Option<usize>) -> Result<()> {
+    let Some(max) = max else {
+        return Ok(());
     };
+    match sources.iter().find(|(_, secrets)| secrets.len() > max) {
+        Some((project, secrets)) => Err(AppError::CommandExecutionError(format!(
+            "Project {} has {} secrets, more than --max-secrets {}; nothing was written. \
+             Pull a smaller project, refresh only known keys with --merge-from-remote or \
+             --section, or raise the limit",
+            project.name,
+            secrets.len(),
+            max
+        ))),
+        None => Ok(()),
+    }
+}
 
-    println!("Pulling secrets from project: {}", proj.name);
+/// Merge the pulled projects and write them to `output`
+fn write_sources(
+    sources: &[(Project, Vec<(String, String)>)],
+    notes: &ProjectNotes,
+    output: &str,
+    options: &PullOptions,
+) -> Result<PullPlan> {
+    check_max_secrets(sources, options.max_secrets)?;
+    let (sources, notes) = &transform_keys(sources, notes, options.key_transform)?;
+    let merged = merge_projects(sources, options.on_collision)?;
+    let _lock = lock::maybe_lock_exclusive(output, options.lock_timeout)?;
 
-    // Get secrets
-    let secrets_map = provider.get_secrets_map(&proj.id).await?;
+    let mut plan = PullPlan::new(output, &merged);
 
-    if secrets_map.is_empty() {
-        println!("No secrets found in project");
-        return Ok(());
+    if let Some(section) = &options.section {
+        let (refreshed, total) = refresh_section(output, section, &merged)?;
+        plan.refreshed = Some(refreshed);
+        plan.kind = PullKind::Section {
+            section: section.clone(),
+            total,
+        };
+        return Ok(plan);
+    }
+    if let Some(template_path) = &options.template {
+        render_to_file(template_path, output, &merged, options)?;
+        plan.kind = PullKind::Template(template_path.clone());
+        return Ok(plan);
+    }
+
+    if merged.is_empty() {
+        plan.written = false;
+        return Ok(plan);
+    }
+
+    let format = options.format.unwrap_or(OutputFormat::Env);
+    if format.is_structured() {
+        // Structured output is always sorted by key
+        let values: BTreeMap<&str, &str> = merged
+            .iter()
+            .map(|(key, sourced)| (key.as_str(), sourced.value.as_str()))
+            .collect();
+        let content = format!("{}\n", output::serialize(&values, format)?);
+        fs::write(output, content).map_err(|e| {
+            AppError::EnvFileWriteError(format!("Failed to write {}: {}", output, e))
+        })?;
+        plan.kind = PullKind::Structured(format);
+        return Ok(plan);
     }
 
     // Build .env content
     let mut content = String::new();
-    content.push_str(&format!(
-        "# Secrets from Bitwarden project: {}\n",
-        proj.name
-    ));
-    content.push_str(&format!("# Project ID: {}\n\n", proj.id));
-
-    for (key, value) in secrets_map.iter() {
-        content.push_str(&format!("{}={}\n", key, value));
+    let names: Vec<_> = sources.iter().map(|(p, _)| p.name.as_str()).collect();
+    if names.len() == 1 {
+        content.push_str(&format!("# Secrets from Bitwarden project: {}\n", names[0]));
+    } else {
+        content.push_str(&format!(
+            "# Secrets from Bitwarden projects: {}\n",
+            names.join(", ")
+        ));
     }
+    for (proj, _) in sources {
+        content.push_str(&format!("# Project ID: {}\n", proj.id));
+    }
+    if options.example {
+        content.push_str("# Example file: values are blank or describe the secret\n");
+    }
+    content.push('\n');
+
+    let entries: Vec<(String, String)> = merged
+        .iter()
+        .map(|(key, sourced)| (key.clone(), sourced.value.clone()))
+        .collect();
+    let mut refreshed = None;
+    let entries = if options.merge_from_remote {
+        let mut existing =
+            parser::read_env_entries(output).map_err(|e| parser::read_error(output, e))?;
+        refreshed = Some(parser::refresh_entries(&mut existing, &entries));
+        existing
+    } else if options.merge && Path::new(output).exists() {
+        let mut existing =
+            parser::read_env_entries(output).map_err(|e| parser::read_error(output, e))?;
+        parser::merge_entries(&mut existing, &entries, options.on_conflict)
+            .map_err(|e| parser::read_error(output, e))?;
+        existing
+    } else {
+        entries
+    };
+    let key_notes: HashMap<String, String> = merged
+        .iter()
+        .filter_map(|(key, sourced)| Some((key.clone(), note_for(notes, key, sourced)?.clone())))
+        .collect();
+    let exported: HashSet<String> = if options.export {
+        entries.iter().map(|(key, _)| key.clone()).collect()
+    } else if Path::new(output).exists() {
+        parser::read_exported_keys(output).unwrap_or_default()
+    } else {
+        HashSet::new()
+    };
+    content.push_str(&parser::format_entries_with_exports(
+        &entries,
+        options.sort,
+        &key_notes,
+        &exported,
+    ));
 
     // Write to file
     fs::write(output, content)
         .map_err(|e| AppError::EnvFileWriteError(format!("Failed to write {}: {}", output, e)))?;
 
-    println!(
-        "Successfully pulled {} secrets to {}",
-        secrets_map.len(),
-        output
-    );
-    Ok(())
+    plan.kind = if refreshed.is_some() {
+        PullKind::Refresh {
+            total: entries.len(),
+        }
+    } else if options.example {
+        PullKind::Example
+    } else {
+        PullKind::Env
+    };
+    plan.refreshed = refreshed;
+    Ok(plan)
+}
+
+/// Refresh the values of keys under `section` of `output`, rewriting only
+/// their lines so comments, headers and other sections stay as written
+///
+/// Returns the refreshed keys and the number of keys in the section.
+fn refresh_section(
+    output: &str,
+    section: &str,
+    merged: &[(String, SourcedValue)],
+) -> Result<(Vec<String>, usize)> {
+    let keys = sections::section_keys(output, section, TextEncoding::default())?;
+    let values: HashMap<String, String> = merged
+        .iter()
+        .filter(|(key, _)| keys.contains(key))
+        .map(|(key, sourced)| (key.clone(), sourced.value.clone()))
+        .collect();
+
+    let content = parser::read_text_as(Path::new(output), TextEncoding::default())
+        .map_err(|e| parser::read_error(output, e))?;
+    let (content, refreshed) = parser::set_values_in_place(&content, &values)
+        .map_err(|e| parser::read_error(output, e))?;
+    fs::write(output, content)
+        .map_err(|e| AppError::EnvFileWriteError(format!("Failed to write {}: {}", output, e)))?;
+
+    Ok((refreshed, keys.len()))
+}
+
+/// The note of a merged key, looking through a `--on-collision prefix` rename
+fn note_for<'a>(notes: &'a ProjectNotes, key: &str, sourced: &SourcedValue) -> Option<&'a String> {
+    let project_notes = notes.get(&sourced.project)?;
+    project_notes.get(key).or_else(|| {
+        let prefix = format!("{}_", project_prefix(&sourced.project));
+        project_notes.get(key.strip_prefix(&prefix)?)
+    })
+}
+
+/// Apply `transform` to the keys of every project and their notes
+///
+/// Keys of one project that the transform maps onto the same name are an
+/// error; the same key from different projects is left to the collision
+/// policy.
+fn transform_keys(
+    sources: &[ProjectSecrets],
+    notes: &ProjectNotes,
+    transform: KeyTransform,
+) -> Result<(Vec<ProjectSecrets>, ProjectNotes)> {
+    if transform == KeyTransform::None {
+        return Ok((sources.to_vec(), notes.clone()));
+    }
+
+    let mut transformed = Vec::with_capacity(sources.len());
+    for (proj, secrets) in sources {
+        let mappings = secrets.iter().map(|(key, _)| {
+            let label = if sources.len() > 1 {
+                format!("{} ({})", key, proj.name)
+            } else {
+                key.clone()
+            };
+            (label, transform.apply(key))
+        });
+        collisions::ensure_no_collisions(mappings, transform.flag())?;
+
+        let secrets = secrets
+            .iter()
+            .map(|(key, value)| (transform.apply(key), value.clone()))
+            .collect();
+        transformed.push((proj.clone(), secrets));
+    }
+
+    let notes = notes
+        .iter()
+        .map(|(project, project_notes)| {
+            let project_notes = project_notes
+                .iter()
+                .map(|(key, note)| (transform.apply(key), note.clone()))
+                .collect();
+            (project.clone(), project_notes)
+        })
+        .collect();
+    Ok((transformed, notes))
+}
+
+/// Render a template with the pulled secrets and write the result
+fn render_to_file(
+    template_path: &str,
+    output: &str,
+    merged: &[(String, SourcedValue)],
+    options: &PullOptions,
+) -> Result<()> {
+    let template_content = fs::read_to_string(template_path).map_err(|e| {
+        AppError::EnvFileReadError(format!("Failed to read {}: {}", template_path, e))
+    })?;
+
+    let vars: HashMap<String, String> = merged
+        .iter()
+        .map(|(key, sourced)| (key.clone(), sourced.value.clone()))
+        .collect();
+    let rendered = template::render_template_with(
+        &template_content,
+        &vars,
+        options.placeholder_style,
+        options.allow_missing,
+    )?;
+
+    fs::write(output, rendered)
+        .map_err(|e| AppError::EnvFileWriteError(format!("Failed to write {}: {}", output, e)))
+}
+
+/// Merge secrets from several projects according to a collision policy
+///
+/// Keys keep the order they first appear in across `sources`.
+pub fn merge_projects(
+    sources: &[(Project, Vec<(String, String)>)],
+    policy: CollisionPolicy,
+) -> Result<Vec<(String, SourcedValue)>> {
+    // Find keys defined by more than one project
+    let mut seen = HashSet::new();
+    let mut colliding = HashSet::new();
+    for (_, secrets) in sources {
+        for (key, _) in secrets {
+            if !seen.insert(key.as_str()) {
+                colliding.insert(key.as_str());
+            }
+        }
+    }
+
+    if policy == CollisionPolicy::Error && !colliding.is_empty() {
+        let mut keys: Vec<_> = colliding.into_iter().collect();
+        keys.sort();
+        return Err(AppError::InvalidArguments(format!(
+            "Keys defined in more than one project: {}. Use --on-collision prefix or last-wins",
+            keys.join(", ")
+        )));
+    }
+
+    let target = |proj: &Project, key: &str| {
+        if policy == CollisionPolicy::Prefix && colliding.contains(key) {
+            format!("{}_{}", project_prefix(&proj.name), key)
+        } else {
+            key.to_string()
+        }
+    };
+    if policy == CollisionPolicy::Prefix {
+        // Prefixing can itself produce a key another project already has
+        let mappings = sources.iter().flat_map(|(proj, secrets)| {
+            secrets
+                .iter()
+                .map(move |(key, _)| (format!("{} ({})", key, proj.name), target(proj, key)))
+        });
+        collisions::ensure_no_collisions(mappings, "--on-collision prefix")?;
+    }
+
+    let mut merged: Vec<(String, SourcedValue)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (proj, secrets) in sources {
+        for (key, value) in secrets {
+            let key = target(proj, key);
+
+            let sourced = SourcedValue {
+                value: value.clone(),
+                project: proj.name.clone(),
+            };
+            match positions.get(&key) {
+                Some(&index) => merged[index].1 = sourced,
+                None => {
+                    positions.insert(key.clone(), merged.len());
+                    merged.push((key, sourced));
+                }
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Turn a project name into an env-style key prefix (`My App` -> `MY_APP`)
+fn project_prefix(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+
+    fn source(name: &str, entries: &[(&str, &str)]) -> (Project, Vec<(String, String)>) {
+        let project = MockProvider::project(&format!("{}_id", name), name);
+        let secrets = entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        (project, secrets)
+    }
+
+    #[test]
+    fn test_example_value_uses_note_only() {
+        assert_eq!(example_value(None), "");
+        assert_eq!(example_value(Some("  ")), "");
+        assert_eq!(
+            example_value(Some("Stripe API key\nfrom dashboard")),
+            "<Stripe API key from dashboard>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pull_example_writes_no_values() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        provider
+            .create_secret("App_id", "API_KEY", "sk_live_secret", Some("Stripe key"))
+            .await
+            .unwrap();
+        provider
+            .create_secret("App_id", "DB_PASSWORD", "hunter2hunter2", None)
+            .await
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env.example");
+        let options = PullOptions {
+            example: true,
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            execute(
+                provider.clone(),
+                &["App".to_string()],
+                output.to_str().unwrap(),
+                &options,
+            )
+            .await
+            .unwrap();
+        }
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("API_KEY=<Stripe key>\n"));
+        assert!(content.contains("DB_PASSWORD=\n"));
+        assert!(!content.contains("sk_live_secret"));
+        assert!(!content.contains("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn test_pull_force_keeps_export_prefixes() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        for (key, value) in [("API_KEY", "new"), ("PORT", "8080")] {
+            provider
+                .create_secret("App_id", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env");
+        fs::write(&output, "export API_KEY=old\nPORT=80\n").unwrap();
+        let output = output.to_str().unwrap();
+        let options = PullOptions {
+            force: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), &["App".to_string()], output, &options)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(output).unwrap();
+        assert!(content.ends_with("export API_KEY=new\nPORT=8080\n"));
+
+        let options = PullOptions {
+            export: true,
+            ..options
+        };
+        execute(provider, &["App".to_string()], output, &options)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(output).unwrap();
+        assert!(content.ends_with("export API_KEY=new\nexport PORT=8080\n"));
+    }
+
+    #[tokio::test]
+    async fn test_pull_merge_local_wins_keeps_local_values() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        for (key, value) in [("SHARED", "remote"), ("REMOTE_ONLY", "r")] {
+            provider
+                .create_secret("App_id", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env");
+        fs::write(&output, "SHARED=local\nLOCAL_ONLY=l\n").unwrap();
+        let options = PullOptions {
+            merge: true,
+            on_conflict: MergeStrategy::LocalWins,
+            ..Default::default()
+        };
+        execute(
+            provider,
+            &["App".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let result = parser::read_env_file(&output).unwrap();
+        assert_eq!(result.get("SHARED"), Some(&"local".to_string()));
+        assert_eq!(result.get("LOCAL_ONLY"), Some(&"l".to_string()));
+        assert_eq!(result.get("REMOTE_ONLY"), Some(&"r".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_pull_merge_from_remote_keeps_key_set() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        for (key, value) in [("SHARED", "remote"), ("REMOTE_ONLY", "r")] {
+            provider
+                .create_secret("App_id", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env");
+        let output_str = output.to_str().unwrap();
+        let options = PullOptions {
+            merge_from_remote: true,
+            ..Default::default()
+        };
+
+        // There is nothing to refresh without an existing file
+        let result = execute(provider.clone(), &["App".to_string()], output_str, &options).await;
+        assert!(matches!(result, Err(AppError::EnvFileReadError(_))));
+
+        fs::write(&output, "SHARED=local\nLOCAL_ONLY=l\n").unwrap();
+        let plan = execute(provider, &["App".to_string()], output_str, &options)
+            .await
+            .unwrap();
+        assert_eq!(plan.refreshed, Some(vec!["SHARED".to_string()]));
+        assert_eq!(plan.kind, PullKind::Refresh { total: 2 });
+        assert!(plan.written);
+
+        let result = parser::read_env_file(&output).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.get("SHARED"), Some(&"remote".to_string()));
+        assert_eq!(result.get("LOCAL_ONLY"), Some(&"l".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_pull_section_rewrites_only_its_keys() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        for (key, value) in [("DB_HOST", "prod-db"), ("REDIS_URL", "prod-redis")] {
+            provider
+                .create_secret("App_id", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env");
+        let content = "# === Database ===\nexport DB_HOST=localhost\n\n# === Cache ===\nREDIS_URL=localhost\n";
+        fs::write(&output, content).unwrap();
+        let options = PullOptions {
+            section: Some("database".to_string()),
+            ..Default::default()
+        };
+        execute(
+            provider,
+            &["App".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            content.replace("DB_HOST=localhost", "DB_HOST=prod-db")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pull_into_fragment_directory_writes_one_fragment() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        provider
+            .create_secret("App_id", "API_KEY", "abc", None)
+            .await
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("10-base.env"), "API_KEY=old\n").unwrap();
+        execute(
+            provider,
+            &["App".to_string()],
+            dir.path().to_str().unwrap(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let pulled = parser::read_env_file(dir.path().join(fragments::PULL_FRAGMENT)).unwrap();
+        assert_eq!(pulled.get("API_KEY"), Some(&"abc".to_string()));
+        let merged =
+            fragments::read_fragments(dir.path(), parser::Whitespace::Trim, Default::default())
+                .unwrap();
+        assert_eq!(merged.get("API_KEY"), Some(&"abc".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_pull_json_format_writes_sorted_object() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+        for (key, value) in [("PORT", "8080"), ("API_KEY", "abc")] {
+            provider
+                .create_secret("App_id", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join("secrets.json");
+        let options = PullOptions {
+            format: Some(OutputFormat::Json),
+            ..Default::default()
+        };
+        execute(
+            provider,
+            &["App".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(
+            content,
+            "{\n  \"API_KEY\": \"abc\",\n  \"PORT\": \"8080\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_pull_rejects_text_format_and_template_with_json() {
+        let text = PullOptions {
+            format: Some(OutputFormat::Text),
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_output("unused.env", &text),
+            Err(AppError::InvalidArguments(_))
+        ));
+
+        let template = PullOptions {
+            format: Some(OutputFormat::Json),
+            template: Some("config.tpl".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_output("unused.env", &template),
+            Err(AppError::InvalidArguments(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_comments_roundtrip_through_push_and_pull() {
+        use crate::commands::push::{self, PushOptions};
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("App", &[]);
+        provider.add_project(project);
+
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(
+            &input,
+            "# Stripe secret key\n# Rotate every 90 days\nAPI_KEY=sk_test\n\n# Detached comment\n\nPORT=8080\n",
+        )
+        .unwrap();
+        let push_options = PushOptions {
+            comments_as_notes: true,
+            ..Default::default()
+        };
+        push::execute(
+            provider.clone(),
+            "App",
+            input.to_str().unwrap(),
+            &push_options,
+        )
+        .await
+        .unwrap();
+
+        let output = dir.path().join(".env.pulled");
+        let options = PullOptions {
+            notes_as_comments: true,
+            ..Default::default()
+        };
+        execute(
+            provider,
+            &["App".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let pushed = parser::read_env_comments(&input).unwrap();
+        let pulled = parser::read_env_comments(&output).unwrap();
+        assert_eq!(pulled, pushed);
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("# Stripe secret key\n# Rotate every 90 days\nAPI_KEY=sk_test\n"));
+    }
+
+    fn as_map(merged: Vec<(String, SourcedValue)>) -> HashMap<String, SourcedValue> {
+        merged.into_iter().collect()
+    }
+
+    #[test]
+    fn test_merge_projects_keeps_source_order() {
+        let sources = vec![
+            source("A", &[("ZEBRA", "z"), ("ALPHA", "a")]),
+            source("B", &[("MIDDLE", "m")]),
+        ];
+
+        let merged = merge_projects(&sources, CollisionPolicy::Error).unwrap();
+        let keys: Vec<_> = merged.iter().map(|(k, _)| k.as_str()).collect();
+
+        assert_eq!(keys, vec!["ZEBRA", "ALPHA", "MIDDLE"]);
+    }
+
+    #[test]
+    fn test_merge_projects_without_collisions() {
+        let sources = vec![
+            source("A", &[("KEY_A", "a")]),
+            source("B", &[("KEY_B", "b")]),
+        ];
+
+        let merged = as_map(merge_projects(&sources, CollisionPolicy::Error).unwrap());
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged["KEY_A"].project, "A");
+        assert_eq!(merged["KEY_B"].project, "B");
+    }
+
+    #[test]
+    fn test_merge_projects_collision_error() {
+        let sources = vec![
+            source("A", &[("SHARED", "a")]),
+            source("B", &[("SHARED", "b")]),
+        ];
+
+        let result = merge_projects(&sources, CollisionPolicy::Error);
+        assert!(result.unwrap_err().to_string().contains("SHARED"));
+    }
+
+    #[test]
+    fn test_merge_projects_collision_prefix() {
+        let sources = vec![
+            source("App One", &[("SHARED", "a"), ("ONLY_A", "x")]),
+            source("B", &[("SHARED", "b")]),
+        ];
+
+        let merged = as_map(merge_projects(&sources, CollisionPolicy::Prefix).unwrap());
+
+        assert_eq!(merged["APP_ONE_SHARED"].value, "a");
+        assert_eq!(merged["B_SHARED"].value, "b");
+        assert_eq!(merged["ONLY_A"].value, "x");
+        assert!(!merged.contains_key("SHARED"));
+    }
+
+    #[test]
+    fn test_merge_projects_collision_last_wins() {
+        let sources = vec![
+            source("A", &[("SHARED", "a")]),
+            source("B", &[("SHARED", "b")]),
+        ];
+
+        let merged = as_map(merge_projects(&sources, CollisionPolicy::LastWins).unwrap());
+
+        assert_eq!(merged["SHARED"].value, "b");
+        assert_eq!(merged["SHARED"].project, "B");
+    }
+
+    #[test]
+    fn test_transform_keys_lowercases_keys_and_notes() {
+        let sources = vec![source("App", &[("API_KEY", "a"), ("Db_Url", "b")])];
+        let notes = ProjectNotes::from([(
+            "App".to_string(),
+            HashMap::from([("API_KEY".to_string(), "Stripe".to_string())]),
+        )]);
+
+        let (sources, notes) = transform_keys(&sources, &notes, KeyTransform::Lower).unwrap();
+        let keys: Vec<&str> = sources[0].1.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["api_key", "db_url"]);
+        assert_eq!(notes["App"]["api_key"], "Stripe");
+    }
+
+    #[test]
+    fn test_transform_keys_rejects_collisions() {
+        let sources = vec![source("App", &[("API_KEY", "a"), ("api_key", "b")])];
+
+        let err = transform_keys(&sources, &ProjectNotes::new(), KeyTransform::Upper).unwrap_err();
+        assert!(
+            matches!(&err, AppError::InvalidArguments(m) if m.contains("API_KEY <- API_KEY, api_key"))
+        );
+        assert!(transform_keys(&sources, &ProjectNotes::new(), KeyTransform::None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pull_max_secrets_refuses_large_project() {
+        use tempfile::tempdir;
+
+        let provider = MockProvider::new();
+        let (project, _) = source("Shared", &[]);
+        provider.add_project(project);
+        for key in ["A", "B", "C"] {
+            provider
+                .create_secret("Shared_id", key, "value", None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let output = dir.path().join(".env");
+        let options = PullOptions {
+            max_secrets: Some(2),
+            ..Default::default()
+        };
+        let result = execute(
+            provider.clone(),
+            &["Shared".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await;
+        match result {
+            Err(AppError::CommandExecutionError(message)) => {
+                assert!(message.contains("has 3 secrets"));
+                assert!(message.contains("--max-secrets 2"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(!output.exists());
+
+        let options = PullOptions {
+            max_secrets: Some(3),
+            ..Default::default()
+        };
+        let plan = execute(
+            provider,
+            &["Shared".to_string()],
+            output.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(plan.pulled.len(), 3);
+    }
 }
@@ -0,0 +1,74 @@
+//! `KEY=@path` file references for push
+//!
+//! With `--allow-file-refs`, a value starting with `@` names a file whose
+//! contents are pushed instead, so certificates and keys need not be inlined
+//! into the .env. Errors name the key and path, never the contents.
+
+use crate::{AppError, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Replace `@path` values with the contents of `path`
+///
+/// Relative paths are resolved against `base_dir`, the .env file's directory.
+pub fn resolve_file_refs(env_vars: &mut HashMap<String, String>, base_dir: &Path) -> Result<()> {
+    for (key, value) in env_vars.iter_mut() {
+        let Some(reference) = value.strip_prefix('@') else {
+            continue;
+        };
+
+        let path = base_dir.join(reference);
+        let contents = fs::read(&path).map_err(|e| {
+            AppError::EnvFileReadError(format!(
+                "{} references {}: {}",
+                key,
+                path.display(),
+                e.kind()
+            ))
+        })?;
+        *value = String::from_utf8(contents).map_err(|_| {
+            AppError::EnvFileFormatError(format!(
+                "{} references {}, which is not valid UTF-8 text",
+                key,
+                path.display()
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_file_refs_relative_to_base_dir() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("certs")).unwrap();
+        fs::write(dir.path().join("certs/tls.pem"), "-----BEGIN-----\nabc\n").unwrap();
+
+        let mut env_vars = HashMap::from([
+            ("TLS_CERT".to_string(), "@certs/tls.pem".to_string()),
+            ("PLAIN".to_string(), "value".to_string()),
+        ]);
+        resolve_file_refs(&mut env_vars, dir.path()).unwrap();
+
+        assert_eq!(env_vars["TLS_CERT"], "-----BEGIN-----\nabc\n");
+        assert_eq!(env_vars["PLAIN"], "value");
+    }
+
+    #[test]
+    fn test_resolve_missing_file_names_key_and_path() {
+        let dir = tempdir().unwrap();
+        let mut env_vars = HashMap::from([("TLS_KEY".to_string(), "@missing.key".to_string())]);
+
+        let err = resolve_file_refs(&mut env_vars, dir.path())
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("TLS_KEY"));
+        assert!(err.contains("missing.key"));
+    }
+}
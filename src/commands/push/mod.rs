@@ -2,17 +2,398 @@
 //!
 //! Reads local .env file and uploads secrets to Bitwarden Secrets Manager.
 
-use crate::bitwarden::provider::SecretsProvider;
-use crate::env::parser;
+use crate::audit::{AuditAction, AuditLog, AuditOutcome};
+use crate::bitwarden::provider::{Project, SecretsProvider, SyncFailure, SyncOptions, SyncResult};
+use crate::config::ValidationConfig;
+use crate::env::encoding::TextEncoding;
+use crate::env::input::{self, InputFormat};
+use crate::env::parser::{self, EnvOp, Whitespace};
+use crate::env::{fragments, lock, permissions, sections};
+use crate::interrupt::Interrupt;
+use crate::output::{self, icon, Symbol};
 use crate::{AppError, Result};
+use clap::ValueEnum;
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
+mod file_refs;
+mod prune;
+mod report;
+mod since;
+mod weak;
+
+use report::PushReport;
+use since::PushState;
+
+/// Output format for the push summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SummaryFormat {
+    /// Human-readable breakdown
+    #[default]
+    Text,
+    /// Structured JSON listing affected keys
+    Json,
+}
+
+/// What `--create-only` does when an incoming key already exists remotely
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictAction {
+    /// Abort the push without changing anything
+    Error,
+    /// Report the conflicting keys and push only the new ones
+    Warn,
+}
+
+/// What `push` does with a key whose value is empty, e.g. `KEY=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum EmptyAs {
+    /// Set the secret to an empty value
+    #[default]
+    Clear,
+    /// Leave the remote secret as it is
+    Skip,
+    /// Refuse to push
+    Error,
+}
+
+/// Options controlling how local secrets are pushed
+#[derive(Debug, Clone, Default)]
+pub struct PushOptions {
+    /// Overwrite values of keys that already exist remotely
+    pub overwrite: bool,
+    /// Only create new keys, reacting to existing ones as configured
+    pub create_only: Option<ConflictAction>,
+    /// Summary output format
+    pub summary: SummaryFormat,
+    /// Warn about empty, placeholder or short secret values
+    pub warn_weak: bool,
+    /// Refuse to push when weak values are found
+    pub fail_weak: bool,
+    /// Minimum length for values of sensitive keys
+    pub min_secret_length: usize,
+    /// Abort on the first failed secret instead of pushing the rest
+    pub fail_fast: bool,
+    /// Only create keys missing remotely, leaving existing ones untouched
+    pub add_only_missing: bool,
+    /// Creates and updates sent concurrently per chunk; 0 uses the
+    /// provider's `--concurrency`
+    pub chunk_size: usize,
+    /// Files layered over the input in order, later values winning
+    pub overlays: Vec<String>,
+    /// Treat `KEY+=value` as appending to the value underneath
+    pub allow_append: bool,
+    /// Inserted between an existing value and an appended one
+    pub append_separator: String,
+    /// Push the contents of `path` for `KEY=@path` values
+    pub allow_file_refs: bool,
+    /// Inline the files named by `#!include path` lines
+    pub allow_includes: bool,
+    /// Store the comment directly above each key as its note
+    pub comments_as_notes: bool,
+    /// Key naming policy from `[validation]`
+    pub policy: ValidationConfig,
+    /// Create the project when it does not exist
+    pub create_project: bool,
+    /// Ask before creating the project
+    pub confirm_create: bool,
+    /// Lock the input while reading it, waiting at most this long for a
+    /// writer to finish; `None` disables locking
+    pub lock_timeout: Option<Duration>,
+    /// Only push keys changed since the push recorded in this file
+    pub since_file: Option<String>,
+    /// Record each change made to Bitwarden in this log
+    pub audit: Option<AuditLog>,
+    /// Format of the input file; overlays are always .env files
+    pub input_format: InputFormat,
+    /// Keep whitespace around .env values instead of trimming it
+    pub no_trim: bool,
+    /// Write a JSON report of the affected keys to this file
+    pub report_file: Option<String>,
+    /// Encoding of the input and overlay files
+    pub encoding: TextEncoding,
+    /// Succeed on an input without any keys instead of refusing it
+    pub allow_empty: bool,
+    /// Only push keys under this `# === Name ===` section header
+    pub section: Option<String>,
+    /// Delete remote secrets missing from the input after pushing
+    pub prune: bool,
+    /// Prune without asking
+    pub yes: bool,
+    /// Ask before pruning; otherwise `yes` is required
+    pub confirm_prune: bool,
+    /// Push a bare `KEY` line as an empty value
+    pub allow_bare_keys: bool,
+    /// With `allow_bare_keys`, accept bare keys but leave them out of the push
+    pub skip_bare: bool,
+    /// Warn about each .env line skipped because it isn't `KEY=VALUE`
+    pub warn_skipped: bool,
+    /// Refuse to push when any .env line would be skipped
+    pub strict_lines: bool,
+    /// How keys with empty values are pushed
+    pub empty_as: EmptyAs,
+}
+
+impl PushOptions {
+    fn whitespace(&self) -> Whitespace {
+        if self.no_trim {
+            Whitespace::Preserve
+        } else {
+            Whitespace::Trim
+        }
+    }
+}
+
+/// The .env files `input` stands for: itself, or the fragments of a
+/// `.env.d/` directory in the order they apply
+fn input_files(input: &str) -> Result<Vec<String>> {
+    if !fragments::is_fragment_dir(input) {
+        return Ok(vec![input.to_string()]);
+    }
+    Ok(fragments::fragment_files(Path::new(input))?
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect())
+}
+
+/// The concrete format of `input`; fragment directories are always .env
+fn input_format(input: &str, options: &PushOptions) -> Result<InputFormat> {
+    if !fragments::is_fragment_dir(input) {
+        return options.input_format.resolve(Path::new(input));
+    }
+    match options.input_format {
+        InputFormat::Auto | InputFormat::Env => Ok(InputFormat::Env),
+        format => Err(AppError::InvalidArguments(format!(
+            "--input-format {} needs a file, but {} is a directory of .env fragments",
+            format, input
+        ))),
+    }
+}
+
+/// Read every key of `input` as push does - fragments, overlays, includes
+/// and the encoding of `options` - under a shared lock on it
+pub(crate) fn load_input(input: &str, options: &PushOptions) -> Result<HashMap<String, String>> {
+    let _lock = lock::maybe_lock_shared(input, options.lock_timeout)?;
+    let format = input_format(input, options)?;
+    read_layers(input, format, options)
+}
+
+/// Read `input` in `format` and apply each overlay file on top of it
+fn read_layers(
+    input: &str,
+    format: InputFormat,
+    options: &PushOptions,
+) -> Result<HashMap<String, String>> {
+    if format != InputFormat::Env {
+        let env_vars = input::read_structured(Path::new(input), format, options.encoding)?;
+        return apply_overlays(env_vars, &options.overlays, options);
+    }
+    let fragment_dir = fragments::is_fragment_dir(input);
+    if !fragment_dir
+        && !options.allow_append
+        && !options.allow_includes
+        && options.overlays.is_empty()
+    {
+        return parser::read_env_file_as(input, options.whitespace(), options.encoding)
+            .map_err(|e| parser::read_error(input, e));
+    }
+
+    // Fragments apply like overlays, each over the ones before it
+    let layers: Vec<String> = input_files(input)?
+        .into_iter()
+        .chain(options.overlays.iter().cloned())
+        .collect();
+    apply_overlays(HashMap::new(), &layers, options)
+}
+
+/// Apply .env files on top of `env_vars` in order
+fn apply_overlays(
+    mut env_vars: HashMap<String, String>,
+    paths: &[String],
+    options: &PushOptions,
+) -> Result<HashMap<String, String>> {
+    for path in paths {
+        let path = path.as_str();
+        let ops = if options.allow_includes {
+            parser::read_env_ops_with_includes(path, options.whitespace(), options.encoding)
+        } else {
+            parser::read_env_ops(path, options.whitespace(), options.encoding)
+        }
+        .map_err(|e| parser::read_error(path, e))?;
+        if !options.allow_append {
+            if let Some((key, _)) = ops.iter().find(|(_, op)| matches!(op, EnvOp::Append(_))) {
+                return Err(AppError::InvalidArguments(format!(
+                    "{} uses {}+=; pass --allow-append to append values",
+                    path, key
+                )));
+            }
+        }
+        parser::apply_ops(&mut env_vars, ops, &options.append_separator);
+    }
+    Ok(env_vars)
+}
+
+/// Report the lines of the .env layers the parser skipped because they
+/// aren't assignments, as warnings or with `strict_lines` as an error
+///
+/// Only line numbers are shown: a malformed line may still hold a secret.
+fn check_skipped_lines(files: &[String], options: &PushOptions) -> Result<()> {
+    let mut skipped = Vec::new();
+    for path in files.iter().chain(&options.overlays) {
+        let errors =
+            parser::find_format_errors_with(path, options.encoding, options.allow_bare_keys)
+                .map_err(|e| parser::read_error(path, e))?;
+        skipped.extend(
+            errors
+                .into_iter()
+                .map(|error| (format!("{} line {}", path, error.line), error.message)),
+        );
+    }
+    if skipped.is_empty() {
+        return Ok(());
+    }
+
+    if options.strict_lines {
+        let lines: Vec<&str> = skipped.iter().map(|(line, _)| line.as_str()).collect();
+        return Err(AppError::EnvFileFormatError(format!(
+            "{} line(s) are not KEY=VALUE and would be skipped: {}. Run 'bwenv validate' for details",
+            lines.len(),
+            lines.join(", ")
+        )));
+    }
+    for (line, message) in &skipped {
+        eprintln!("{}Skipped {}: {}", icon(Symbol::Warn), line, message);
+    }
+    Ok(())
+}
+
+/// Apply `empty_as` to the keys with empty values, returning those removed
+/// from `env_vars` by `skip`
+fn apply_empty_as(
+    env_vars: &mut HashMap<String, String>,
+    options: &PushOptions,
+) -> Result<Vec<String>> {
+    let mut empty: Vec<String> = env_vars
+        .iter()
+        .filter(|(_, value)| value.is_empty())
+        .map(|(key, _)| key.clone())
+        .collect();
+    if empty.is_empty() || options.empty_as == EmptyAs::Clear {
+        return Ok(Vec::new());
+    }
+    empty.sort();
+
+    if options.empty_as == EmptyAs::Error {
+        return Err(AppError::EnvVarError(format!(
+            "{} key(s) have empty values: {}; refusing to push with --empty-as error",
+            empty.len(),
+            empty.join(", ")
+        )));
+    }
+    env_vars.retain(|_, value| !value.is_empty());
+    if options.summary == SummaryFormat::Text {
+        println!(
+            "Leaving {} key(s) with empty values untouched: {}",
+            empty.len(),
+            empty.join(", ")
+        );
+    }
+    Ok(empty)
+}
+
+/// Add each bare `KEY` line of the .env layers as an empty value, or only
+/// report it with `skip_bare`; a key also assigned a value keeps that value
+fn add_bare_keys(
+    env_vars: &mut HashMap<String, String>,
+    files: &[String],
+    options: &PushOptions,
+) -> Result<()> {
+    let mut skipped = Vec::new();
+    for path in files.iter().chain(&options.overlays) {
+        let bare = parser::read_bare_keys(path, options.encoding)
+            .map_err(|e| parser::read_error(path, e))?;
+        for (key, _) in bare {
+            if env_vars.contains_key(&key) || skipped.contains(&key) {
+                continue;
+            }
+            if options.skip_bare {
+                skipped.push(key);
+            } else {
+                env_vars.insert(key, String::new());
+            }
+        }
+    }
+
+    if !skipped.is_empty() && options.summary == SummaryFormat::Text {
+        println!("Skipping bare keys without a value: {}", skipped.join(", "));
+    }
+    Ok(())
+}
+
+/// What a push did, returned so the caller decides how to show it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushPlan {
+    /// The project pushed to
+    pub project: Project,
+    /// Keys by the action taken for them
+    pub result: SyncResult,
+    /// Whether the sync ran; false when there was nothing to push
+    pub attempted: bool,
+}
+
+impl PushPlan {
+    fn new(project: Project, result: SyncResult, attempted: bool) -> Self {
+        Self {
+            project,
+            result,
+            attempted,
+        }
+    }
+
+    /// Print the outcome as a breakdown by action or as JSON
+    pub fn render(&self, summary: SummaryFormat) -> Result<()> {
+        match summary {
+            SummaryFormat::Text if self.attempted => print_summary(&self.result),
+            SummaryFormat::Text => {}
+            SummaryFormat::Json => println!("{}", serde_json::to_string_pretty(&self.result)?),
+        }
+        Ok(())
+    }
+
+    /// Fail if any key failed or the push was interrupted
+    pub fn check(&self) -> Result<()> {
+        let result = &self.result;
+        // With --fail-fast the keys after a failure are not attempted either
+        if result.has_failures() {
+            return Err(AppError::CommandExecutionError(format!(
+                "{} of {} secrets failed to push",
+                result.failed.len(),
+                result.total()
+            )));
+        }
+
+        if result.was_interrupted() {
+            return Err(AppError::Interrupted(format!(
+                "{} of {} secrets were not pushed",
+                result.not_attempted.len(),
+                result.total()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Push the secrets of `input`, returning what was done without printing
+/// the summary; see [`PushPlan::render`] and [`PushPlan::check`]
 pub async fn execute<P: SecretsProvider>(
     provider: P,
     project: &str,
     input: &str,
-    overwrite: bool,
-) -> Result<()> {
+    options: &PushOptions,
+) -> Result<PushPlan> {
+    let summary = options.summary;
+
     // Check if input file exists
     if !Path::new(input).exists() {
         return Err(AppError::EnvFileReadError(format!(
@@ -20,32 +401,1139 @@ pub async fn execute<P: SecretsProvider>(
             input
         )));
     }
+    permissions::check_readable(Path::new(input))?;
+    let files = input_files(input)?;
+    for file in &files {
+        permissions::check_readable(Path::new(file))?;
+        permissions::warn_if_exposed(Path::new(file));
+    }
 
     // Get project by name or ID
-    let proj = if let Ok(Some(p)) = provider.get_project(project).await {
+    let proj = if let Some(p) = provider.get_project(project).await? {
         p
-    } else if let Ok(Some(p)) = provider.get_project_by_name(project).await {
+    } else if let Some(p) = provider.get_project_by_name(project).await? {
         p
+    } else if options.create_project {
+        create_project(&provider, project, options).await?
     } else {
-        return Err(AppError::ItemNotFound(format!("Project: {}", project)));
+        return Err(AppError::ItemNotFound(format!(
+            "Project: {}. Use --create-project to create it",
+            project
+        )));
     };
 
-    println!("Pushing secrets to project: {}", proj.name);
+    if proj.is_read_only() {
+        return Err(AppError::CommandExecutionError(format!(
+            "This access token can only read project {}; grant the machine account write access to push",
+            proj.name
+        )));
+    }
 
-    // Parse .env file
-    let env_vars = parser::read_env_file(input)
-        .map_err(|e| AppError::EnvFileReadError(format!("Failed to read {}: {}", input, e)))?;
+    if summary == SummaryFormat::Text {
+        println!("Pushing secrets to project: {}", proj.name);
+    }
+
+    // Parse .env file and any overlays on top of it
+    let lock = lock::maybe_lock_shared(input, options.lock_timeout)?;
+    let format = input_format(input, options)?;
+    let mut env_vars = read_layers(input, format, options)?;
+    if format == InputFormat::Env && (options.warn_skipped || options.strict_lines) {
+        check_skipped_lines(&files, options)?;
+    }
+    if options.allow_bare_keys && format == InputFormat::Env {
+        add_bare_keys(&mut env_vars, &files, options)?;
+    }
+    let mut notes = HashMap::new();
+    if options.comments_as_notes && format == InputFormat::Env {
+        for file in &files {
+            notes.extend(
+                parser::read_env_comments_as(file, options.encoding)
+                    .map_err(|e| parser::read_error(file, e))?,
+            );
+        }
+    }
+    if let Some(section) = &options.section {
+        if format != InputFormat::Env {
+            return Err(AppError::InvalidArguments(format!(
+                "--section needs a .env input, but {} is {}",
+                input, format
+            )));
+        }
+        let keys = sections::section_keys(input, section, options.encoding)?;
+        env_vars.retain(|key, _| keys.contains(key));
+    }
+    drop(lock);
+    if options.allow_file_refs {
+        let base_dir = if fragments::is_fragment_dir(input) {
+            Path::new(input)
+        } else {
+            Path::new(input).parent().unwrap_or(Path::new(""))
+        };
+        file_refs::resolve_file_refs(&mut env_vars, base_dir)?;
+    }
+    options.policy.enforce(env_vars.keys())?;
 
     if env_vars.is_empty() {
-        println!("No secrets found in {}", input);
-        return Ok(());
+        if !options.allow_empty {
+            return Err(AppError::InvalidArguments(format!(
+                "No secrets found in {}; refusing to push an empty input. Pass --allow-empty if this is intended",
+                input
+            )));
+        }
+        if summary == SummaryFormat::Text {
+            println!("No secrets found in {}", input);
+        }
+    }
+    let skipped_empty = apply_empty_as(&mut env_vars, options)?;
+
+    if options.warn_weak || options.fail_weak {
+        let weak_values = weak::find_weak_values(&env_vars, options.min_secret_length);
+        if !weak_values.is_empty() {
+            eprintln!(
+                "{}Weak secret values ({}):",
+                icon(Symbol::Warn),
+                weak_values.len()
+            );
+            for weak_value in &weak_values {
+                eprintln!("   - {}: {}", weak_value.key, weak_value.reason);
+            }
+            if options.fail_weak {
+                return Err(AppError::EnvVarError(format!(
+                    "{} weak secret value(s) found; refusing to push",
+                    weak_values.len()
+                )));
+            }
+        }
+    }
+
+    if let Some(action) = options.create_only {
+        let keys: Vec<&str> = env_vars.keys().map(String::as_str).collect();
+        let remote = provider.get_secrets_by_keys(&proj.id, &keys).await?;
+        let mut conflicts: Vec<_> = env_vars
+            .keys()
+            .filter(|key| remote.contains_key(*key))
+            .collect();
+        conflicts.sort();
+
+        if !conflicts.is_empty() {
+            let keys = conflicts
+                .iter()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            match action {
+                ConflictAction::Error => {
+                    return Err(AppError::InvalidArguments(format!(
+                        "--create-only: {} key(s) already exist in project {}: {}",
+                        conflicts.len(),
+                        proj.name,
+                        keys
+                    )));
+                }
+                ConflictAction::Warn => {
+                    eprintln!(
+                        "{}Skipping {} key(s) that already exist remotely: {}",
+                        icon(Symbol::Warn),
+                        conflicts.len(),
+                        keys
+                    );
+                }
+            }
+        }
+    }
+
+    // Settle what gets deleted while nothing has been written yet
+    let doomed = if options.prune {
+        // Keys left untouched for being empty are not missing
+        let mut keep = env_vars.clone();
+        keep.extend(skipped_empty.into_iter().map(|key| (key, String::new())));
+        prune::plan(&provider, &proj.id, &keep).await?
+    } else {
+        Vec::new()
+    };
+    if !doomed.is_empty() {
+        prune::confirm(&doomed, &proj.name, options.yes, options.confirm_prune)?;
+    }
+
+    let mut since = match &options.since_file {
+        Some(path) => Some(PushState::load(path, &proj.id)?),
+        None => None,
+    };
+    let all_vars = env_vars;
+    let env_vars = match &since {
+        Some(state) => state.changed(&all_vars),
+        None => all_vars.clone(),
+    };
+    if env_vars.is_empty() && doomed.is_empty() {
+        if since.is_some() && summary == SummaryFormat::Text {
+            println!("No changes since the last push; nothing to do");
+        }
+        if let Some(path) = &options.report_file {
+            PushReport::new(&proj.id, &SyncResult::default()).save(path)?;
+        }
+        return Ok(PushPlan::new(proj, SyncResult::default(), false));
     }
 
     // Sync secrets to Bitwarden
-    let results = provider
-        .sync_secrets(&proj.id, &env_vars, overwrite)
+    let sync_options = SyncOptions {
+        overwrite: options.overwrite && options.create_only.is_none(),
+        fail_fast: options.fail_fast,
+        add_only_missing: options.add_only_missing,
+        chunk_size: options.chunk_size,
+        interrupt: Interrupt::on_ctrl_c(),
+        notes,
+    };
+    let mut result = provider
+        .sync_secrets_with(&proj.id, &env_vars, &sync_options)
         .await?;
+    let mut prune_error = None;
+    if !doomed.is_empty() {
+        if result.was_interrupted() || result.has_failures() {
+            eprintln!(
+                "{}Not pruning {} secret(s) because the push did not complete",
+                icon(Symbol::Warn),
+                doomed.len()
+            );
+        } else {
+            match prune::apply(&provider, &doomed).await {
+                Ok(pruned) => result.pruned = pruned,
+                Err(e) => prune_error = Some(e),
+            }
+        }
+    }
+    // What was written is recorded even when the prune failed
+    if let Some(audit) = &options.audit {
+        audit.record_sync("push", &proj.id, &result)?;
+        if prune_error.is_some() {
+            audit.append(&prune::failed_entries(&proj.id, &doomed))?;
+        }
+    }
+    if let Some(e) = &prune_error {
+        result.failed.extend(doomed.iter().map(|s| SyncFailure {
+            key: s.key.clone(),
+            error: e.to_string(),
+        }));
+    }
+    if let Some(path) = &options.report_file {
+        PushReport::new(&proj.id, &result).save(path)?;
+    }
+    if let Some(e) = prune_error {
+        return Err(e);
+    }
 
-    println!("Successfully pushed {} secrets to Bitwarden", results.len());
-    Ok(())
+    if let (Some(state), Some(path)) = (since.as_mut(), &options.since_file) {
+        let pushed = result
+            .created
+            .iter()
+            .chain(&result.updated)
+            .chain(&result.unchanged);
+        state.record(&all_vars, pushed);
+        state.save(path)?;
+        if summary == SummaryFormat::Text {
+            println!(
+                "{} unchanged secrets not pushed (--since-file {})",
+                all_vars.len() - env_vars.len(),
+                path
+            );
+        }
+    }
+
+    Ok(PushPlan::new(proj, result, true))
+}
+
+/// Create the missing project `name`, asking first if configured to
+async fn create_project<P: SecretsProvider>(
+    provider: &P,
+    name: &str,
+    options: &PushOptions,
+) -> Result<Project> {
+    if options.confirm_create
+        && !output::confirm(&format!("Project '{}' does not exist. Create it?", name))?
+    {
+        return Err(AppError::ItemNotFound(format!(
+            "Project: {} (creation declined)",
+            name
+        )));
+    }
+    // Someone may have created it while the prompt was open
+    if options.confirm_create && provider.project_exists(name).await? {
+        return Err(AppError::CommandExecutionError(format!(
+            "Project '{}' was created while waiting for confirmation; run push again to use it",
+            name
+        )));
+    }
+
+    let proj = provider.create_project(name).await?;
+    if let Some(audit) = &options.audit {
+        audit.record(
+            "push",
+            &proj.id,
+            None,
+            AuditAction::CreateProject,
+            AuditOutcome::Success,
+        )?;
+    }
+    // Keep stdout parseable when the summary is JSON
+    let message = format!("Created project {} ({})", proj.name, proj.id);
+    match options.summary {
+        SummaryFormat::Text => println!("{}", message),
+        SummaryFormat::Json => eprintln!("{}", message),
+    }
+    Ok(proj)
+}
+
+/// Print a human-readable breakdown of a sync result
+fn print_summary(result: &SyncResult) {
+    println!(
+        "Successfully pushed {} secrets to Bitwarden",
+        result.created.len() + result.updated.len()
+    );
+
+    let sections = [
+        ("Created", &result.created),
+        ("Updated", &result.updated),
+        ("Skipped (already exist, use --overwrite)", &result.skipped),
+        ("Unchanged", &result.unchanged),
+        ("Pruned", &result.pruned),
+    ];
+
+    for (label, keys) in sections {
+        if keys.is_empty() {
+            continue;
+        }
+        println!("  {} ({}):", label, keys.len());
+        for key in keys {
+            println!("    - {}", key);
+        }
+    }
+
+    if result.has_failures() {
+        println!("  Failed ({}):", result.failed.len());
+        for failure in &result.failed {
+            println!("    - {}: {}", failure.key, failure.error);
+        }
+    }
+
+    if result.was_interrupted() {
+        println!(
+            "  Not pushed, stopped early ({}):",
+            result.not_attempted.len()
+        );
+        for key in &result.not_attempted {
+            println!("    - {}", key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::{MockProvider, Project, ProjectAccess};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_push_create_only_errors_on_existing_key() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "API_KEY", "remote", None)
+            .await
+            .unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=local\nNEW_KEY=value\n").unwrap();
+
+        let options = PushOptions {
+            create_only: Some(ConflictAction::Error),
+            ..Default::default()
+        };
+        let result = execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("API_KEY"));
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_push_create_only_warn_pushes_new_keys() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "API_KEY", "remote", None)
+            .await
+            .unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=local\nNEW_KEY=value\n").unwrap();
+
+        let options = PushOptions {
+            create_only: Some(ConflictAction::Warn),
+            ..Default::default()
+        };
+        execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("API_KEY"), Some(&"remote".to_string()));
+        assert_eq!(map.get("NEW_KEY"), Some(&"value".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_create_project_when_missing() {
+        let provider = MockProvider::new();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=value\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        let result = execute(provider.clone(), "New Service", input, &Default::default()).await;
+        assert!(matches!(result, Err(AppError::ItemNotFound(_))));
+
+        let options = PushOptions {
+            create_project: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "New Service", input, &options)
+            .await
+            .unwrap();
+
+        let proj = provider
+            .get_project_by_name("New Service")
+            .await
+            .unwrap()
+            .unwrap();
+        let map = provider.get_secrets_map(&proj.id).await.unwrap();
+        assert_eq!(map.get("API_KEY"), Some(&"value".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_since_file_only_pushes_changes() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        let since_file = temp_dir.path().join("push-state.json");
+        let input_path = input.to_str().unwrap();
+        let options = PushOptions {
+            overwrite: true,
+            since_file: Some(since_file.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+
+        fs::write(&input, "A=1\nB=2\n").unwrap();
+        execute(provider.clone(), "proj_1", input_path, &options)
+            .await
+            .unwrap();
+
+        // A remote edit to an unchanged key is left alone by the next push
+        let a = provider
+            .list_secrets("proj_1")
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|s| s.key == "A")
+            .unwrap();
+        provider
+            .update_secret(&a.id, "A", "edited remotely", None)
+            .await
+            .unwrap();
+
+        fs::write(&input, "A=1\nB=3\n").unwrap();
+        execute(provider.clone(), "proj_1", input_path, &options)
+            .await
+            .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("A"), Some(&"edited remotely".to_string()));
+        assert_eq!(map.get("B"), Some(&"3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_writes_audit_entry_per_changed_key() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "EXISTING", "old", None)
+            .await
+            .unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        let audit_path = temp_dir.path().join("audit.jsonl");
+        fs::write(&input, "EXISTING=new_secret\nFRESH=fresh_secret\n").unwrap();
+        let options = PushOptions {
+            overwrite: true,
+            audit: Some(AuditLog::new(&audit_path)),
+            ..Default::default()
+        };
+        execute(provider, "proj_1", input.to_str().unwrap(), &options)
+            .await
+            .unwrap();
+
+        let mut entries = AuditLog::new(&audit_path).read().unwrap();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(entries.len(), 2);
+        for entry in &entries {
+            assert_eq!(entry.command, "push");
+            assert_eq!(entry.project_id, "proj_1");
+            assert_eq!(entry.outcome, AuditOutcome::Success);
+        }
+        assert_eq!(entries[0].key.as_deref(), Some("EXISTING"));
+        assert_eq!(entries[0].action, AuditAction::Update);
+        assert_eq!(entries[1].key.as_deref(), Some("FRESH"));
+        assert_eq!(entries[1].action, AuditAction::Create);
+
+        let log = fs::read_to_string(&audit_path).unwrap();
+        assert!(!log.contains("_secret"));
+    }
+
+    #[tokio::test]
+    async fn test_push_detects_json_input() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("secrets.json");
+        fs::write(&input, r#"{"API_KEY": "abc", "PORT": 8080}"#).unwrap();
+        execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &PushOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("API_KEY"), Some(&"abc".to_string()));
+        assert_eq!(map.get("PORT"), Some(&"8080".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_no_trim_round_trips_trailing_spaces() {
+        use crate::commands::pull::{self, PullOptions};
+
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "SUFFIX=-dev  \nPLAIN=value\n").unwrap();
+        let options = PushOptions {
+            no_trim: true,
+            ..Default::default()
+        };
+        execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("SUFFIX"), Some(&"-dev  ".to_string()));
+
+        let output = temp_dir.path().join("pulled.env");
+        pull::execute(
+            provider,
+            &["proj_1".to_string()],
+            output.to_str().unwrap(),
+            &PullOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let pulled = parser::read_env_file_with(&output, Whitespace::Preserve).unwrap();
+        assert_eq!(pulled.get("SUFFIX"), Some(&"-dev  ".to_string()));
+        assert_eq!(pulled.get("PLAIN"), Some(&"value".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_overlay_appends_to_input() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        let overlay = temp_dir.path().join(".env.local");
+        fs::write(&input, "FEATURES=auth\n").unwrap();
+        fs::write(&overlay, "FEATURES+=billing\nEXTRA+=1\n").unwrap();
+
+        let options = PushOptions {
+            overlays: vec![overlay.to_str().unwrap().to_string()],
+            allow_append: true,
+            append_separator: ",".to_string(),
+            ..Default::default()
+        };
+        execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("FEATURES"), Some(&"auth,billing".to_string()));
+        assert_eq!(map.get("EXTRA"), Some(&"1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_overlay_append_requires_flag() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        let overlay = temp_dir.path().join(".env.local");
+        fs::write(&input, "FEATURES=auth\n").unwrap();
+        fs::write(&overlay, "FEATURES+=billing\n").unwrap();
+
+        let options = PushOptions {
+            overlays: vec![overlay.to_str().unwrap().to_string()],
+            ..Default::default()
+        };
+        let result = execute(provider, "proj_1", input.to_str().unwrap(), &options).await;
+
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_push_writes_report_file() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "API_KEY", "remote", None)
+            .await
+            .unwrap();
+        provider
+            .create_secret("proj_1", "SAME", "same", None)
+            .await
+            .unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=sk_local\nSAME=same\nNEW_KEY=value\n").unwrap();
+        let report = temp_dir.path().join("report.json");
+
+        let options = PushOptions {
+            overwrite: true,
+            report_file: Some(report.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        execute(provider, "proj_1", input.to_str().unwrap(), &options)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(&report).unwrap();
+        assert!(!content.contains("sk_local"));
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["project_id"], "proj_1");
+        assert!(json["timestamp"].is_string());
+        assert_eq!(json["created"], serde_json::json!(["NEW_KEY"]));
+        assert_eq!(json["updated"], serde_json::json!(["API_KEY"]));
+        assert_eq!(json["unchanged"], serde_json::json!(["SAME"]));
+        assert_eq!(json["skipped"], serde_json::json!([]));
+        assert_eq!(json["pruned"], serde_json::json!([]));
+        assert_eq!(json["counts"]["total"], 3);
+        assert_eq!(json["counts"]["created"], 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&report).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_decodes_windows_1252_input() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, b"CITY=Z\xFCrich\n").unwrap();
+
+        let options = PushOptions {
+            encoding: "windows-1252".parse().unwrap(),
+            ..Default::default()
+        };
+        execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.get("CITY"), Some(&"Zürich".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_merges_fragment_directory_in_order() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let fragments = temp_dir.path().join(".env.d");
+        fs::create_dir(&fragments).unwrap();
+        fs::write(fragments.join("10-base.env"), "HOST=db\nPORT=8080\n").unwrap();
+        fs::write(fragments.join("20-local.env"), "PORT=9000\n").unwrap();
+        fs::write(fragments.join("notes.txt"), "PORT=1\n").unwrap();
+
+        execute(
+            provider.clone(),
+            "proj_1",
+            fragments.to_str().unwrap(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("HOST"), Some(&"db".to_string()));
+        assert_eq!(map.get("PORT"), Some(&"9000".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_push_refuses_empty_input_unless_allowed() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "# nothing here yet\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        let result = execute(provider.clone(), "proj_1", input, &Default::default()).await;
+        match result {
+            Err(AppError::InvalidArguments(message)) => assert!(message.contains("--allow-empty")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let options = PushOptions {
+            allow_empty: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+        assert!(provider.list_secrets("proj_1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_push_only_database_section() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(
+            &input,
+            "APP_NAME=demo\n\n# === Database ===\nDB_HOST=db\nDB_PORT=5432\n\n# === Cache ===\nREDIS_URL=redis://cache\n",
+        )
+        .unwrap();
+        let input = input.to_str().unwrap();
+
+        let options = PushOptions {
+            section: Some("Database".to_string()),
+            ..Default::default()
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("DB_HOST"), Some(&"db".to_string()));
+        assert_eq!(map.get("DB_PORT"), Some(&"5432".to_string()));
+
+        let options = PushOptions {
+            section: Some("Queue".to_string()),
+            ..Default::default()
+        };
+        let result = execute(provider, "proj_1", input, &options).await;
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_push_prune_deletes_remote_only_keys() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        for (key, value) in [("KEEP", "old"), ("STALE", "x"), ("ALSO_STALE", "y")] {
+            provider
+                .create_secret("proj_1", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "KEEP=new\nNEW=1\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        // Without --yes and nobody to ask, nothing at all is changed
+        let options = PushOptions {
+            overwrite: true,
+            prune: true,
+            ..Default::default()
+        };
+        let result = execute(provider.clone(), "proj_1", input, &options).await;
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+        assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 3);
+
+        let report = temp_dir.path().join("report.json");
+        let options = PushOptions {
+            yes: true,
+            report_file: Some(report.to_str().unwrap().to_string()),
+            ..options
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(
+            map,
+            HashMap::from([
+                ("KEEP".to_string(), "new".to_string()),
+                ("NEW".to_string(), "1".to_string()),
+            ])
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+        assert_eq!(json["pruned"], serde_json::json!(["ALSO_STALE", "STALE"]));
+    }
+
+    #[tokio::test]
+    async fn test_push_returns_plan_with_failures() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider.fail_writes_for("BROKEN");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "BROKEN=1\nOK=2\n").unwrap();
+
+        // Failures are part of the plan; only check() turns them into an error
+        let plan = execute(
+            provider,
+            "proj_1",
+            input.to_str().unwrap(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert!(plan.attempted);
+        assert_eq!(plan.project.id, "proj_1");
+        assert_eq!(plan.result.created, vec!["OK".to_string()]);
+        assert_eq!(plan.result.failed.len(), 1);
+        assert!(matches!(
+            plan.check(),
+            Err(AppError::CommandExecutionError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_push_refuses_read_only_project() {
+        let provider = MockProvider::new();
+        provider.add_project(Project {
+            access: Some(ProjectAccess::Read),
+            ..MockProvider::project("proj_ro", "Shared")
+        });
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=value\n").unwrap();
+
+        let result = execute(
+            provider.clone(),
+            "Shared",
+            input.to_str().unwrap(),
+            &Default::default(),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AppError::CommandExecutionError(m)) if m.contains("only read"))
+        );
+        assert!(provider.list_secrets("proj_ro").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_push_bare_keys_as_empty_or_skipped() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(
+            &input,
+            "API_URL=https://api\nFEATURE_FLAG\nexport PASSTHROUGH\n",
+        )
+        .unwrap();
+        let input = input.to_str().unwrap();
+
+        // Strict by default: bare lines are not keys
+        execute(provider.clone(), "proj_1", input, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(provider.get_secrets_map("proj_1").await.unwrap().len(), 1);
+
+        let options = PushOptions {
+            allow_bare_keys: true,
+            skip_bare: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+        assert_eq!(provider.get_secrets_map("proj_1").await.unwrap().len(), 1);
+
+        let options = PushOptions {
+            allow_bare_keys: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("FEATURE_FLAG"), Some(&String::new()));
+        assert_eq!(map.get("PASSTHROUGH"), Some(&String::new()));
+    }
+
+    #[tokio::test]
+    async fn test_push_warns_or_fails_on_skipped_lines() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_URL=https://api\nsk_live_no_equals\n=orphan\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        let options = PushOptions {
+            strict_lines: true,
+            ..Default::default()
+        };
+        match execute(provider.clone(), "proj_1", input, &options).await {
+            Err(AppError::EnvFileFormatError(message)) => {
+                assert!(message.contains("line 2, "));
+                assert!(message.contains("line 3."));
+                assert!(!message.contains("sk_live"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(provider.list_secrets("proj_1").await.unwrap().is_empty());
+
+        let options = PushOptions {
+            warn_skipped: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "proj_1", input, &options)
+            .await
+            .unwrap();
+        assert_eq!(provider.get_secrets_map("proj_1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_push_empty_as_clear_skip_and_error() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "API_KEY=\nAPP_NAME=demo\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        for (empty_as, expected) in [
+            (EmptyAs::Clear, Some("")),
+            (EmptyAs::Skip, Some("live-key")),
+            (EmptyAs::Error, None),
+        ] {
+            let provider = MockProvider::with_project("proj_1", "Test Project");
+            provider
+                .create_secret("proj_1", "API_KEY", "live-key", None)
+                .await
+                .unwrap();
+            let options = PushOptions {
+                overwrite: true,
+                prune: true,
+                yes: true,
+                empty_as,
+                ..Default::default()
+            };
+
+            let result = execute(provider.clone(), "proj_1", input, &options).await;
+            let map = provider.get_secrets_map("proj_1").await.unwrap();
+            match expected {
+                Some(value) => {
+                    result.unwrap();
+                    assert_eq!(map["API_KEY"], value, "{:?}", empty_as);
+                    assert_eq!(map["APP_NAME"], "demo");
+                }
+                None => {
+                    match result {
+                        Err(AppError::EnvVarError(message)) => assert!(message.contains("API_KEY")),
+                        other => panic!("unexpected result: {:?}", other),
+                    }
+                    assert_eq!(map.len(), 1);
+                    assert_eq!(map["API_KEY"], "live-key");
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_prune_empty_input_same_for_every_summary() {
+        for summary in [SummaryFormat::Text, SummaryFormat::Json] {
+            let provider = MockProvider::with_project("proj_1", "Test Project");
+            provider
+                .create_secret("proj_1", "STALE", "x", None)
+                .await
+                .unwrap();
+
+            let temp_dir = tempdir().unwrap();
+            let input = temp_dir.path().join(".env");
+            fs::write(&input, "# nothing here yet\n").unwrap();
+            let input = input.to_str().unwrap();
+
+            // The prune is still confirmed before anything is deleted
+            let options = PushOptions {
+                allow_empty: true,
+                prune: true,
+                summary,
+                ..Default::default()
+            };
+            let result = execute(provider.clone(), "proj_1", input, &options).await;
+            assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+            assert_eq!(provider.list_secrets("proj_1").await.unwrap().len(), 1);
+
+            let options = PushOptions {
+                yes: true,
+                ..options
+            };
+            let plan = execute(provider.clone(), "proj_1", input, &options)
+                .await
+                .unwrap();
+            assert_eq!(plan.result.pruned, vec!["STALE".to_string()]);
+            assert!(provider.list_secrets("proj_1").await.unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_prune_failure_still_writes_audit_and_report() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "STALE", "x", None)
+            .await
+            .unwrap();
+        provider.fail_writes_for("STALE");
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "NEW=1\n").unwrap();
+        let audit_path = temp_dir.path().join("audit.log");
+        let report = temp_dir.path().join("report.json");
+
+        let options = PushOptions {
+            prune: true,
+            yes: true,
+            audit: Some(AuditLog::new(&audit_path)),
+            report_file: Some(report.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        let result = execute(
+            provider.clone(),
+            "proj_1",
+            input.to_str().unwrap(),
+            &options,
+        )
+        .await;
+        assert!(result.is_err());
+
+        let mut entries = AuditLog::new(&audit_path).read().unwrap();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key.as_deref(), Some("NEW"));
+        assert_eq!(entries[0].action, AuditAction::Create);
+        assert_eq!(entries[0].outcome, AuditOutcome::Success);
+        assert_eq!(entries[1].key.as_deref(), Some("STALE"));
+        assert_eq!(entries[1].action, AuditAction::Delete);
+        assert_eq!(entries[1].outcome, AuditOutcome::Failure);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+        assert_eq!(json["created"], serde_json::json!(["NEW"]));
+        assert_eq!(json["pruned"], serde_json::json!([]));
+        assert_eq!(json["failed"], serde_json::json!(["STALE"]));
+    }
+
+    #[tokio::test]
+    async fn test_pull_then_push_keeps_values_that_need_quoting() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        let values = [
+            ("QUOTED", "\"already quoted\""),
+            ("INNER_QUOTE", "say \"hi\""),
+            ("BACKSLASH", "C:\\path\\"),
+            ("HASH", "abc#123"),
+            ("PADDED", "  spaced  "),
+            ("PLAIN", "value"),
+        ];
+        for (key, value) in values {
+            provider
+                .create_secret("proj_1", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let env_file = temp_dir.path().join(".env");
+        let env_file = env_file.to_str().unwrap();
+        crate::commands::pull::execute(
+            provider.clone(),
+            &["Test Project".to_string()],
+            env_file,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let options = PushOptions {
+            overwrite: true,
+            ..Default::default()
+        };
+        let plan = execute(provider.clone(), "proj_1", env_file, &options)
+            .await
+            .unwrap();
+        assert!(plan.result.updated.is_empty());
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        for (key, value) in values {
+            assert_eq!(map[key], value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_since_file_records_unchanged_keys() {
+        let provider = MockProvider::with_project("proj_1", "Test Project");
+        provider
+            .create_secret("proj_1", "A", "1", None)
+            .await
+            .unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join(".env");
+        fs::write(&input, "A=1\n").unwrap();
+        let since_file = temp_dir.path().join("push-state.json");
+        let since_file = since_file.to_str().unwrap();
+        let options = PushOptions {
+            overwrite: true,
+            since_file: Some(since_file.to_string()),
+            ..Default::default()
+        };
+        let plan = execute(provider, "proj_1", input.to_str().unwrap(), &options)
+            .await
+            .unwrap();
+        assert_eq!(plan.result.unchanged, vec!["A".to_string()]);
+
+        let state = PushState::load(since_file, "proj_1").unwrap();
+        assert!(state.hashes.contains_key("A"));
+    }
 }
@@ -0,0 +1,89 @@
+//! `push --prune` - delete remote secrets missing from the input
+//!
+//! The deletion is planned before anything is written, so the exact keys can
+//! be shown and confirmed while nothing has changed yet. Only key names are
+//! printed.
+
+use crate::audit::{AuditAction, AuditEntry, AuditOutcome};
+use crate::bitwarden::provider::SecretsProvider;
+use crate::commands::prune::TombstoneEntry;
+use crate::output::{self, icon, Symbol};
+use crate::{AppError, Result};
+use std::collections::HashMap;
+
+/// Remote secrets of `project_id` whose keys are not in `keep`, sorted by key
+pub async fn plan<P: SecretsProvider>(
+    provider: &P,
+    project_id: &str,
+    keep: &HashMap<String, String>,
+) -> Result<Vec<TombstoneEntry>> {
+    let mut doomed: Vec<TombstoneEntry> = provider
+        .list_secrets(project_id)
+        .await?
+        .into_iter()
+        .filter(|s| !keep.contains_key(&s.key))
+        .map(|s| TombstoneEntry {
+            id: s.id,
+            key: s.key,
+        })
+        .collect();
+    doomed.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(doomed)
+}
+
+/// Show the secrets about to be deleted and make sure that is intended
+///
+/// `yes` skips the question; without it the user must be able to answer,
+/// since a script silently deleting secrets is the failure this prevents.
+pub fn confirm(doomed: &[TombstoneEntry], project: &str, yes: bool, ask: bool) -> Result<()> {
+    let keys: Vec<&str> = doomed.iter().map(|s| s.key.as_str()).collect();
+    eprintln!(
+        "{}The following {} remote secrets will be DELETED from {}: {}",
+        icon(Symbol::Warn),
+        doomed.len(),
+        project,
+        keys.join(", ")
+    );
+
+    if yes {
+        return Ok(());
+    }
+    if !ask {
+        return Err(AppError::InvalidArguments(format!(
+            "Refusing to delete {} secret(s) without confirmation; pass --yes to prune non-interactively",
+            doomed.len()
+        )));
+    }
+    if !output::confirm("Delete them?")? {
+        return Err(AppError::CommandExecutionError(
+            "Push aborted before any change; nothing was pushed or deleted".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Delete the planned secrets, returning their keys
+pub async fn apply<P: SecretsProvider>(
+    provider: &P,
+    doomed: &[TombstoneEntry],
+) -> Result<Vec<String>> {
+    let ids: Vec<&str> = doomed.iter().map(|s| s.id.as_str()).collect();
+    provider.delete_secrets(&ids).await?;
+    Ok(doomed.iter().map(|s| s.key.clone()).collect())
+}
+
+/// Audit entries for planned deletes that did not go through
+pub fn failed_entries(project_id: &str, doomed: &[TombstoneEntry]) -> Vec<AuditEntry> {
+    doomed
+        .iter()
+        .map(|s| {
+            AuditEntry::new(
+                "push",
+                project_id,
+                Some(&s.key),
+                AuditAction::Delete,
+                AuditOutcome::Failure,
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,100 @@
+//! `--report-file` result artifact
+//!
+//! A JSON record of what a push did, for CI pipelines that keep stdout for
+//! other output. Only key names are written, never values or provider errors.
+
+use crate::bitwarden::provider::SyncResult;
+use crate::cache::open_private;
+use crate::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Number of keys in each list of a [`PushReport`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportCounts {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub unchanged: usize,
+    pub pruned: usize,
+    pub failed: usize,
+    pub not_attempted: usize,
+    pub total: usize,
+}
+
+/// Outcome of a push, by key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushReport {
+    pub timestamp: DateTime<Utc>,
+    pub project_id: String,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub pruned: Vec<String>,
+    pub failed: Vec<String>,
+    pub not_attempted: Vec<String>,
+    pub counts: ReportCounts,
+}
+
+impl PushReport {
+    pub fn new(project_id: &str, result: &SyncResult) -> Self {
+        let failed: Vec<String> = result.failed.iter().map(|f| f.key.clone()).collect();
+        Self {
+            timestamp: Utc::now(),
+            project_id: project_id.to_string(),
+            counts: ReportCounts {
+                created: result.created.len(),
+                updated: result.updated.len(),
+                skipped: result.skipped.len(),
+                unchanged: result.unchanged.len(),
+                pruned: result.pruned.len(),
+                failed: failed.len(),
+                not_attempted: result.not_attempted.len(),
+                total: result.total(),
+            },
+            created: result.created.clone(),
+            updated: result.updated.clone(),
+            skipped: result.skipped.clone(),
+            unchanged: result.unchanged.clone(),
+            pruned: result.pruned.clone(),
+            failed,
+            not_attempted: result.not_attempted.clone(),
+        }
+    }
+
+    /// Write the report to `path` with owner-only permissions
+    pub fn save(&self, path: &str) -> Result<()> {
+        let write = || -> Result<()> {
+            let mut file = open_private(path.as_ref())?;
+            file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+            Ok(())
+        };
+        write().map_err(|e| AppError::EnvFileWriteError(format!("Failed to write {}: {}", path, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::provider::SyncFailure;
+
+    #[test]
+    fn test_report_lists_keys_without_errors() {
+        let result = SyncResult {
+            created: vec!["A".to_string()],
+            failed: vec![SyncFailure {
+                key: "B".to_string(),
+                error: "rejected value sk_live_secret".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let report = PushReport::new("proj_1", &result);
+        assert_eq!(report.failed, vec!["B"]);
+        assert_eq!(report.counts.total, 2);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("sk_live_secret"));
+    }
+}
@@ -0,0 +1,198 @@
+//! `--since-file` incremental push state
+//!
+//! Records an HMAC-SHA256 of each pushed key and value so the next push can
+//! skip keys that haven't changed. Values themselves are never written, and
+//! the HMAC is keyed by a random salt kept in the file so that a leaked file
+//! can't be checked against guessed values with a precomputed table.
+
+use crate::cache::open_private;
+use crate::{AppError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Hashes of the values last pushed to a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushState {
+    pub project_id: String,
+    /// Hex-encoded HMAC key, generated when the file is first written
+    #[serde(default)]
+    pub salt: String,
+    pub hashes: BTreeMap<String, String>,
+}
+
+impl PushState {
+    /// Load the state for `project_id`, or an empty one if the file is
+    /// missing or was written for another project
+    pub fn load(path: &str, project_id: &str) -> Result<Self> {
+        let empty = Self {
+            project_id: project_id.to_string(),
+            salt: new_salt(),
+            ..Default::default()
+        };
+        if !Path::new(path).exists() {
+            return Ok(empty);
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| AppError::EnvFileReadError(format!("Failed to read {}: {}", path, e)))?;
+        let state: Self = serde_json::from_str(&content)?;
+        // Files written before the salt existed are started over
+        Ok(
+            if state.project_id == project_id && !state.salt.is_empty() {
+                state
+            } else {
+                empty
+            },
+        )
+    }
+
+    /// Write the state to `path`, readable only by its owner
+    pub fn save(&self, path: &str) -> Result<()> {
+        let write = || -> Result<()> {
+            let mut file = open_private(path.as_ref())?;
+            file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+            Ok(())
+        };
+        write().map_err(|e| AppError::EnvFileWriteError(format!("Failed to write {}: {}", path, e)))
+    }
+
+    /// Keep only the keys that are new or changed since the last push
+    pub fn changed(&self, env_vars: &HashMap<String, String>) -> HashMap<String, String> {
+        env_vars
+            .iter()
+            .filter(|(key, value)| self.hashes.get(*key) != Some(&self.hash(key, value)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Record `keys` as pushed with their values from `env_vars`
+    pub fn record<'a>(
+        &mut self,
+        env_vars: &HashMap<String, String>,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) {
+        for key in keys {
+            if let Some(value) = env_vars.get(key) {
+                let hash = self.hash(key, value);
+                self.hashes.insert(key.clone(), hash);
+            }
+        }
+    }
+
+    /// Hash a key and value together, so equal values of different keys differ
+    fn hash(&self, key: &str, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.salt.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(key.as_bytes());
+        mac.update(&[0]);
+        mac.update(value.as_bytes());
+        hex(&mac.finalize().into_bytes())
+    }
+}
+
+/// 32 random bytes, hex-encoded
+fn new_salt() -> String {
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    hex(&salt)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn vars(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_changed_after_record() {
+        let pushed = vars(&[("A", "1"), ("B", "2")]);
+        let mut state = PushState {
+            salt: new_salt(),
+            ..Default::default()
+        };
+        state.record(&pushed, pushed.keys());
+
+        let next = vars(&[("A", "1"), ("B", "changed"), ("C", "new")]);
+        let mut changed: Vec<_> = state.changed(&next).into_keys().collect();
+        changed.sort();
+
+        assert_eq!(changed, vec!["B", "C"]);
+    }
+
+    #[test]
+    fn test_state_file_has_no_values_and_is_project_scoped() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("push-state.json");
+        let path = path.to_str().unwrap();
+
+        let pushed = vars(&[("API_KEY", "sk_live_secret")]);
+        let mut state = PushState::load(path, "proj_1").unwrap();
+        state.record(&pushed, pushed.keys());
+        state.save(path).unwrap();
+
+        assert!(!fs::read_to_string(path).unwrap().contains("sk_live_secret"));
+        assert_eq!(PushState::load(path, "proj_1").unwrap(), state);
+        assert!(PushState::load(path, "proj_2").unwrap().hashes.is_empty());
+    }
+
+    #[test]
+    fn test_hashes_depend_on_the_salt() {
+        let dir = tempdir().unwrap();
+        let pushed = vars(&[("API_KEY", "sk_live_secret")]);
+
+        let mut hashes = Vec::new();
+        for name in ["a.json", "b.json"] {
+            let path = dir.path().join(name);
+            let path = path.to_str().unwrap();
+            let mut state = PushState::load(path, "proj_1").unwrap();
+            state.record(&pushed, pushed.keys());
+            state.save(path).unwrap();
+
+            // The salt is kept, so a reload still sees the key as pushed
+            let reloaded = PushState::load(path, "proj_1").unwrap();
+            assert!(reloaded.changed(&pushed).is_empty());
+            hashes.push(reloaded.hashes["API_KEY"].clone());
+        }
+        assert_ne!(hashes[0], hashes[1]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_state_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("push-state.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let path = path.to_str().unwrap();
+        PushState {
+            project_id: "proj_1".to_string(),
+            salt: new_salt(),
+            ..Default::default()
+        }
+        .save(path)
+        .unwrap();
+
+        let mode = fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,117 @@
+//! Weak value detection for push
+//!
+//! Flags empty values, well-known placeholders and short secrets before they
+//! are uploaded. Only keys and reasons are reported, never the values.
+
+use std::collections::HashMap;
+
+/// Key suffixes that mark a value as sensitive
+const SENSITIVE_SUFFIXES: &[&str] = &["_SECRET", "_PASSWORD", "_KEY", "_TOKEN"];
+
+/// Values commonly left behind from templates and examples
+const PLACEHOLDERS: &[&str] = &[
+    "changeme",
+    "change_me",
+    "change-me",
+    "replaceme",
+    "replace_me",
+    "todo",
+    "fixme",
+    "placeholder",
+    "secret",
+    "password",
+    "xxx",
+    "xxxx",
+    "dummy",
+    "example",
+    "your_key_here",
+];
+
+/// A value that looks unintentionally weak
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeakValue {
+    pub key: String,
+    pub reason: String,
+}
+
+/// Find weak values among the secrets about to be pushed
+///
+/// Sensitive keys (`*_SECRET`, `*_PASSWORD`, `*_KEY`, `*_TOKEN`) are also
+/// checked against `min_length`; pass `0` to disable the length check.
+pub fn find_weak_values(vars: &HashMap<String, String>, min_length: usize) -> Vec<WeakValue> {
+    let mut weak: Vec<WeakValue> = vars
+        .iter()
+        .filter_map(|(key, value)| {
+            weakness(key, value, min_length).map(|reason| WeakValue {
+                key: key.clone(),
+                reason,
+            })
+        })
+        .collect();
+    weak.sort_by(|a, b| a.key.cmp(&b.key));
+    weak
+}
+
+fn weakness(key: &str, value: &str, min_length: usize) -> Option<String> {
+    if value.is_empty() {
+        return Some("empty value".to_string());
+    }
+
+    let normalized = value.trim_matches(|c| c == '"' || c == '\'').to_lowercase();
+    if PLACEHOLDERS.contains(&normalized.as_str()) {
+        return Some("placeholder value".to_string());
+    }
+
+    let upper = key.to_uppercase();
+    let sensitive = SENSITIVE_SUFFIXES.iter().any(|s| upper.ends_with(s));
+    if sensitive && value.chars().count() < min_length {
+        return Some(format!("shorter than {} characters", min_length));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_weak_values() {
+        let input = vars(&[
+            ("EMPTY", ""),
+            ("API_KEY", "CHANGEME"),
+            ("DB_PASSWORD", "short"),
+            ("JWT_SECRET", "a-long-enough-random-value"),
+            ("LOG_LEVEL", "info"),
+        ]);
+
+        let weak = find_weak_values(&input, 12);
+
+        assert_eq!(weak.len(), 3);
+        assert_eq!(weak[0].key, "API_KEY");
+        assert_eq!(weak[0].reason, "placeholder value");
+        assert_eq!(weak[1].key, "DB_PASSWORD");
+        assert!(weak[1].reason.contains("12"));
+        assert_eq!(weak[2].key, "EMPTY");
+    }
+
+    #[test]
+    fn test_find_weak_values_never_includes_value() {
+        let input = vars(&[("DB_PASSWORD", "hunter2")]);
+        let weak = find_weak_values(&input, 12);
+        assert!(!format!("{:?}", weak).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_find_weak_values_length_check_disabled() {
+        let input = vars(&[("DB_PASSWORD", "short")]);
+        assert!(find_weak_values(&input, 0).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+//! Rotate command - Replace a secret with a freshly generated random value
+//!
+//! The value comes from the operating system's CSPRNG. It replaces the live
+//! secret in Bitwarden and, optionally, the key in a local .env file. The
+//! value itself is only printed with `--show`, and `--dry-run` only says
+//! what would be rotated.
+
+use crate::audit::{AuditAction, AuditLog, AuditOutcome};
+use crate::bitwarden::provider::SecretsProvider;
+use crate::commands::status::find_project;
+use crate::env::encoding::TextEncoding;
+use crate::env::{lock, parser, permissions};
+use crate::output::{self, icon, DryRun, Symbol};
+use crate::{AppError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Length of a generated value unless `--length` says otherwise
+pub const DEFAULT_LENGTH: usize = 32;
+
+/// Characters a generated value is drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Charset {
+    /// Letters and digits
+    #[default]
+    Alnum,
+    /// Lowercase hexadecimal digits
+    Hex,
+    /// The URL-safe base64 alphabet, without padding
+    Base64,
+}
+
+impl Charset {
+    fn alphabet(self) -> &'static [u8] {
+        match self {
+            Charset::Alnum => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            Charset::Hex => b"0123456789abcdef",
+            Charset::Base64 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+        }
+    }
+}
+
+/// Options controlling a rotation
+#[derive(Debug, Clone, Default)]
+pub struct RotateOptions {
+    /// Number of characters to generate
+    pub length: usize,
+    pub charset: Charset,
+    /// Print the new value after rotating
+    pub show: bool,
+    /// Also set the key in this .env file
+    pub env_file: Option<String>,
+    /// Lock `env_file` while updating it, waiting at most this long for
+    /// another process to finish; `None` disables locking
+    pub lock_timeout: Option<Duration>,
+    /// Rotate without asking
+    pub yes: bool,
+    /// Ask before rotating, e.g. when stdin is a terminal
+    pub confirm: bool,
+    /// Record the update in this audit log
+    pub audit: Option<AuditLog>,
+    /// Only print what would be rotated
+    pub dry_run: bool,
+}
+
+/// Generate `length` characters of `charset`, each equally likely
+pub fn generate(length: usize, charset: Charset) -> Zeroizing<String> {
+    let alphabet = charset.alphabet();
+    // Bytes past the last whole multiple of the alphabet would favour its
+    // first characters, so they are drawn again
+    let limit = 256 - 256 % alphabet.len();
+
+    let mut value = Zeroizing::new(String::with_capacity(length));
+    let mut bytes = Zeroizing::new([0u8; 64]);
+    while value.len() < length {
+        OsRng.fill_bytes(bytes.as_mut());
+        for &byte in bytes.iter().filter(|&&b| (b as usize) < limit) {
+            if value.len() == length {
+                break;
+            }
+            value.push(alphabet[byte as usize % alphabet.len()] as char);
+        }
+    }
+    value
+}
+
+pub async fn execute<P: SecretsProvider>(
+    provider: P,
+    project: &str,
+    key: &str,
+    options: &RotateOptions,
+) -> Result<()> {
+    if options.length == 0 {
+        return Err(AppError::InvalidArguments(
+            "--length must be at least 1".to_string(),
+        ));
+    }
+
+    let proj = find_project(&provider, project).await?;
+    let secret = provider
+        .list_secrets(&proj.id)
+        .await?
+        .into_iter()
+        .find(|s| s.key == key)
+        .ok_or_else(|| AppError::ItemNotFound(format!("Secret {} in {}", key, proj.name)))?;
+
+    if options.dry_run {
+        let charset = options
+            .charset
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+        let mut action = format!(
+            "replace the value with a new {}-character {} value",
+            options.length, charset
+        );
+        if let Some(path) = &options.env_file {
+            action.push_str(&format!(" and set it in {}", path));
+        }
+        DryRun::new(&proj.name, &proj.id, key, action).print();
+        return Ok(());
+    }
+
+    if options.confirm
+        && !options.yes
+        && !output::confirm(&format!(
+            "Replace the live value of {} in {} with a new random value?",
+            key, proj.name
+        ))?
+    {
+        return Err(AppError::CommandExecutionError(
+            "Rotation cancelled; nothing was changed".to_string(),
+        ));
+    }
+
+    let value = generate(options.length, options.charset);
+    let updated = provider
+        .update_secret(&secret.id, key, &value, secret.note.as_deref())
+        .await;
+    if let Some(audit) = &options.audit {
+        let outcome = match updated {
+            Ok(_) => AuditOutcome::Success,
+            Err(_) => AuditOutcome::Failure,
+        };
+        audit.record("rotate", &proj.id, Some(key), AuditAction::Update, outcome)?;
+    }
+    updated?;
+    println!("{}Rotated {} in {}", icon(Symbol::Ok), key, proj.name);
+
+    if let Some(path) = &options.env_file {
+        write_back(path, key, &value, options.lock_timeout).map_err(|e| {
+            AppError::EnvFileWriteError(format!(
+                "{} was rotated in Bitwarden, but {} was not updated: {}",
+                key, path, e
+            ))
+        })?;
+        println!("Updated {} in {}", key, path);
+    }
+
+    if options.show {
+        println!("{}", value.as_str());
+    }
+    Ok(())
+}
+
+/// Set `key` to `value` in the .env file at `path`, in place if the key is
+/// already there and appended otherwise
+fn write_back(path: &str, key: &str, value: &str, lock_timeout: Option<Duration>) -> Result<()> {
+    let file = Path::new(path);
+    permissions::check_writable(file)?;
+    let _lock = lock::maybe_lock_exclusive(path, lock_timeout)?;
+    let content = if file.exists() {
+        parser::read_text_as(file, TextEncoding::default())
+            .map_err(|e| parser::read_error(path, e))?
+    } else {
+        String::new()
+    };
+
+    let values = HashMap::from([(key.to_string(), value.to_string())]);
+    let (mut content, changed) =
+        parser::set_values_in_place(&content, &values).map_err(|e| parser::read_error(path, e))?;
+    if changed.is_empty() {
+        content.push_str(&format!("{}={}\n", key, parser::format_value(value)));
+    }
+
+    fs::write(file, content)?;
+    permissions::warn_if_exposed(file);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generate_uses_length_and_charset() {
+        for charset in [Charset::Alnum, Charset::Hex, Charset::Base64] {
+            let value = generate(100, charset);
+            assert_eq!(value.len(), 100);
+            assert!(value.bytes().all(|b| charset.alphabet().contains(&b)));
+        }
+        assert_ne!(
+            generate(32, Charset::Alnum).as_str(),
+            generate(32, Charset::Alnum).as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate_updates_remote_and_local() {
+        let provider = MockProvider::with_project("proj_1", "App");
+        provider
+            .create_secret("proj_1", "API_TOKEN", "old", Some("rotated monthly"))
+            .await
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, "# tokens\nAPI_TOKEN=old\nOTHER=1\n").unwrap();
+        let audit = dir.path().join("audit.jsonl");
+
+        let options = RotateOptions {
+            length: 24,
+            charset: Charset::Hex,
+            env_file: Some(env_file.to_str().unwrap().to_string()),
+            audit: Some(AuditLog::new(&audit)),
+            ..Default::default()
+        };
+        execute(provider.clone(), "App", "API_TOKEN", &options)
+            .await
+            .unwrap();
+
+        let secret = provider
+            .list_secrets("proj_1")
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|s| s.key == "API_TOKEN")
+            .unwrap();
+        assert_eq!(secret.value.len(), 24);
+        assert_eq!(secret.note.as_deref(), Some("rotated monthly"));
+
+        let content = fs::read_to_string(&env_file).unwrap();
+        assert_eq!(
+            content,
+            format!("# tokens\nAPI_TOKEN={}\nOTHER=1\n", secret.value)
+        );
+
+        let entries = AuditLog::new(&audit).read().unwrap();
+        assert_eq!(entries[0].command, "rotate");
+        assert_eq!(entries[0].key.as_deref(), Some("API_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_missing_key_changes_nothing() {
+        let provider = MockProvider::with_project("proj_1", "App");
+
+        let options = RotateOptions {
+            length: DEFAULT_LENGTH,
+            ..Default::default()
+        };
+        let result = execute(provider, "App", "MISSING", &options).await;
+        assert!(matches!(result, Err(AppError::ItemNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_dry_run_changes_nothing() {
+        let provider = MockProvider::with_project("proj_1", "App");
+        provider
+            .create_secret("proj_1", "API_TOKEN", "old", None)
+            .await
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, "API_TOKEN=old\n").unwrap();
+        let audit = dir.path().join("audit.jsonl");
+
+        let options = RotateOptions {
+            length: DEFAULT_LENGTH,
+            env_file: Some(env_file.to_str().unwrap().to_string()),
+            audit: Some(AuditLog::new(&audit)),
+            confirm: true,
+            dry_run: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "App", "API_TOKEN", &options)
+            .await
+            .unwrap();
+
+        let map = provider.get_secrets_map("proj_1").await.unwrap();
+        assert_eq!(map["API_TOKEN"], "old");
+        assert_eq!(fs::read_to_string(&env_file).unwrap(), "API_TOKEN=old\n");
+        assert!(!audit.exists());
+    }
+
+    #[test]
+    fn test_write_back_waits_for_the_lock() {
+        let dir = tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, "API_TOKEN=old\n").unwrap();
+        let path = env_file.to_str().unwrap();
+
+        let held = lock::lock_exclusive(path, Duration::from_secs(1)).unwrap();
+        let result = write_back(path, "API_TOKEN", "new", Some(Duration::ZERO));
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(fs::read_to_string(&env_file).unwrap(), "API_TOKEN=old\n");
+
+        drop(held);
+        write_back(path, "API_TOKEN", "new", Some(Duration::ZERO)).unwrap();
+        assert_eq!(fs::read_to_string(&env_file).unwrap(), "API_TOKEN=new\n");
+    }
+}
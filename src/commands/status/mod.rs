@@ -1,128 +1,830 @@
 //! Status command - Show sync state between local and remote
 //!
-//! Compares local .env with Bitwarden Secrets Manager state.
+//! Compares local .env with Bitwarden Secrets Manager state, or with
+//! `--against` one project with another, e.g. staging with production.
 
-use crate::bitwarden::provider::SecretsProvider;
-use crate::env::parser;
+use crate::bitwarden::provider::{Project, SecretsProvider};
+use crate::env::encoding::TextEncoding;
+use crate::env::parser::{self, Whitespace};
+use crate::env::{fragments, sections};
+use crate::output::{self, icon, OutputFormat, Symbol};
 use crate::Result;
-use std::collections::HashSet;
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
+/// Formats `status --format` accepts
+pub const STATUS_FORMATS: [OutputFormat; 3] =
+    [OutputFormat::Text, OutputFormat::Json, OutputFormat::Yaml];
+
+/// Formats `list --format` accepts
+pub const LIST_FORMATS: [OutputFormat; 3] =
+    [OutputFormat::Text, OutputFormat::Json, OutputFormat::Yaml];
+
+/// Key-level differences between a local .env and the remote project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Drift {
+    /// Keys present only in Bitwarden
+    pub only_remote: Vec<String>,
+    /// Keys present only in the local file
+    pub only_local: Vec<String>,
+    /// Keys present on both sides with different values
+    pub different: Vec<String>,
+    /// Number of keys whose values match
+    pub matching: usize,
+    /// Number of drifted keys dropped by [`Drift::ignore`]
+    pub ignored: usize,
+}
+
+impl Drift {
+    /// Whether local and remote are identical
+    pub fn is_clean(&self) -> bool {
+        self.only_remote.is_empty() && self.only_local.is_empty() && self.different.is_empty()
+    }
+
+    /// Counts of each category, relative to Bitwarden like [`short_lines`]:
+    /// `In sync: 42, Added: 3, Removed: 1, Changed: 2`
+    pub fn summary_line(&self) -> String {
+        format!(
+            "In sync: {}, Added: {}, Removed: {}, Changed: {}",
+            self.matching,
+            self.only_local.len(),
+            self.only_remote.len(),
+            self.different.len()
+        )
+    }
+
+    /// Drop `keys` from every drift category, counting them as ignored
+    pub fn ignore(mut self, keys: &[String]) -> Self {
+        if keys.is_empty() {
+            return self;
+        }
+
+        let before = self.only_remote.len() + self.only_local.len() + self.different.len();
+        for list in [
+            &mut self.only_remote,
+            &mut self.only_local,
+            &mut self.different,
+        ] {
+            list.retain(|key| !keys.contains(key));
+        }
+        let after = self.only_remote.len() + self.only_local.len() + self.different.len();
+        self.ignored += before - after;
+        self
+    }
+}
+
+/// Compare remote and local secrets key by key
+pub fn compare(remote: &HashMap<String, String>, local: &HashMap<String, String>) -> Drift {
+    let remote_keys: HashSet<_> = remote.keys().collect();
+    let local_keys: HashSet<_> = local.keys().collect();
+
+    let mut drift = Drift {
+        only_remote: remote_keys
+            .difference(&local_keys)
+            .map(|k| k.to_string())
+            .collect(),
+        only_local: local_keys
+            .difference(&remote_keys)
+            .map(|k| k.to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    // Check for value differences
+    for key in remote_keys.intersection(&local_keys) {
+        if remote.get(*key) != local.get(*key) {
+            drift.different.push(key.to_string());
+        } else {
+            drift.matching += 1;
+        }
+    }
+
+    drift.only_remote.sort();
+    drift.only_local.sort();
+    drift.different.sort();
+    drift
+}
+
+/// Longest note shown next to a key before it is truncated
+const NOTE_PREVIEW_LEN: usize = 60;
+
+/// Shown in place of the hidden part of a partially revealed value
+const MASK: &str = "•••";
+
+/// Options controlling the status report
+#[derive(Debug, Clone, Default)]
+pub struct StatusOptions {
+    /// .env files to compare (`-` reads stdin)
+    pub env_files: Vec<String>,
+    /// Glob matching additional .env files
+    pub file_glob: Option<String>,
+    /// Annotate remote-only and changed keys with their Bitwarden note
+    pub with_notes: bool,
+    /// One `+`/`-`/`~` line per drifted key instead of the sectioned report
+    pub short: bool,
+    /// Keys excluded from drift, e.g. intentionally local-only variables
+    pub ignore: Vec<String>,
+    /// Output format, text when not set
+    pub format: Option<OutputFormat>,
+    /// Show both values of changed keys, revealing at most this many
+    /// leading characters of each
+    pub reveal_prefix: Option<usize>,
+    /// Only compare keys under this `# === Name ===` header of each file
+    pub section: Option<String>,
+    /// Fail if a local file has keys missing from Bitwarden, whatever other
+    /// drift there is
+    pub fail_on_extra: bool,
+    /// Compare the project with this project instead of with local files
+    pub against: Option<String>,
+}
+
+/// Structured status output for `--format json|yaml`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    pub project: Project,
+    pub files: Vec<FileStatus>,
+}
+
+/// Structured output of `status --against`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectComparison {
+    pub project: Project,
+    pub against: Project,
+    pub in_sync: bool,
+    /// Keys of `project` missing from `against`
+    pub only_in_project: Vec<String>,
+    /// Keys of `against` missing from `project`
+    pub only_in_against: Vec<String>,
+    pub different: Vec<String>,
+    pub matching: usize,
+    pub ignored: usize,
+}
+
+/// Drift of one local file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStatus {
+    pub path: String,
+    pub in_sync: bool,
+    #[serde(flatten)]
+    pub drift: Drift,
+}
+
 pub async fn execute<P: SecretsProvider>(
     provider: P,
     project: &str,
-    env_file: Option<&str>,
+    options: &StatusOptions,
 ) -> Result<()> {
-    let env_path = env_file.unwrap_or(".env");
+    let format = options.format.unwrap_or(OutputFormat::Text);
+    format.ensure_supported("status", &STATUS_FORMATS)?;
+    if format.is_structured()
+        && (options.short || options.with_notes || options.reveal_prefix.is_some())
+    {
+        return Err(crate::AppError::InvalidArguments(format!(
+            "--format {} cannot be combined with --short, --with-notes or --reveal-prefix",
+            format
+        )));
+    }
+    if let Some(against) = &options.against {
+        return compare_projects(&provider, project, against, options).await;
+    }
+
+    let file_glob = options.file_glob.as_deref();
+    let env_paths = resolve_env_files(&options.env_files, file_glob)?;
+    let mut extra = BTreeSet::new();
+
+    if format.is_structured() {
+        let proj = find_project(&provider, project).await?;
+        let remote_secrets = provider.get_secrets_map(&proj.id).await?;
+        let mut files = Vec::new();
+        for env_path in &env_paths {
+            let (_, drift) = local_drift(&remote_secrets, env_path, options)?;
+            extra.extend(drift.only_local.iter().cloned());
+            files.push(FileStatus {
+                path: env_path.clone(),
+                in_sync: drift.is_clean(),
+                drift,
+            });
+        }
+
+        let drifted = files.iter().filter(|file| !file.in_sync).count();
+        let multiple = file_glob.is_some() || env_paths.len() > 1;
+        println!(
+            "{}",
+            output::serialize(
+                &StatusReport {
+                    project: proj,
+                    files
+                },
+                format
+            )?
+        );
+        check_extra(&extra, options)?;
+        if multiple && drifted > 0 {
+            return Err(crate::AppError::CommandExecutionError(format!(
+                "Drift detected in {} of {} files",
+                drifted,
+                env_paths.len()
+            )));
+        }
+        return Ok(());
+    }
+
+    if !options.short {
+        println!("{}Checking sync status...", icon(Symbol::Search));
+        println!();
+    }
+
+    let proj = find_project(&provider, project).await?;
+
+    if options.short {
+        let remote_secrets = provider.get_secrets_map(&proj.id).await?;
+        let multiple = file_glob.is_some() || env_paths.len() > 1;
+        for env_path in &env_paths {
+            if multiple {
+                println!("## {}", env_path);
+            }
+            let (_, drift) = local_drift(&remote_secrets, env_path, options)?;
+            extra.extend(drift.only_local.iter().cloned());
+            for line in short_lines(&drift) {
+                println!("{}", color_short_line(&line));
+            }
+        }
+        return check_extra(&extra, options);
+    }
 
-    println!("🔍 Checking sync status...");
+    println!(
+        "{}Project: {} ({})",
+        icon(Symbol::Project),
+        proj.name,
+        proj.id
+    );
     println!();
 
-    // Get project
-    let proj = if let Ok(Some(p)) = provider.get_project(project).await {
-        p
-    } else if let Ok(Some(p)) = provider.get_project_by_name(project).await {
-        p
+    // Get remote secrets from Bitwarden, with notes only when asked for
+    let mut notes = HashMap::new();
+    let remote_secrets = if options.with_notes {
+        let mut secrets = HashMap::new();
+        for secret in provider.list_secrets(&proj.id).await? {
+            if let Some(note) = secret.note {
+                notes.insert(secret.key.clone(), note);
+            }
+            secrets.insert(secret.key, secret.value);
+        }
+        secrets
     } else {
-        return Err(crate::AppError::ItemNotFound(format!(
-            "Project: {}",
-            project
-        )));
+        provider.get_secrets_map(&proj.id).await?
     };
 
-    println!("📦 Project: {} ({})", proj.name, proj.id);
+    if file_glob.is_none() && env_paths.len() == 1 {
+        let (local_secrets, drift) = local_drift(&remote_secrets, &env_paths[0], options)?;
+        let values = options
+            .reveal_prefix
+            .map(|reveal| (&remote_secrets, &local_secrets, reveal));
+        print_drift(&drift, &notes, values);
+        print_ignored(drift.ignored);
+        println!("{}", drift.summary_line().dimmed());
+        extra.extend(drift.only_local);
+        return check_extra(&extra, options);
+    }
+
+    // Multi-file mode: group output by file and fail if any file drifted
+    let mut drifted = 0;
+    let mut ignored = 0;
+    for env_path in &env_paths {
+        println!("{}{}", icon(Symbol::File), env_path.bold());
+        let (local_secrets, drift) = local_drift(&remote_secrets, env_path, options)?;
+        ignored += drift.ignored;
+        extra.extend(drift.only_local.iter().cloned());
+        if drift.is_clean() {
+            println!("   In sync ({} secrets match)", drift.matching);
+        } else {
+            drifted += 1;
+            println!(
+                "   {} only in Bitwarden, {} only local, {} different",
+                drift.only_remote.len(),
+                drift.only_local.len(),
+                drift.different.len()
+            );
+            for key in &drift.only_remote {
+                println!(
+                    "   - {} (only in Bitwarden){}",
+                    key,
+                    note_suffix(&notes, key)
+                );
+            }
+            for key in &drift.only_local {
+                println!("   - {} (only local)", key);
+            }
+            let values = options
+                .reveal_prefix
+                .map(|reveal| (&remote_secrets, &local_secrets, reveal));
+            for key in &drift.different {
+                println!(
+                    "   - {} (different value){}{}",
+                    key,
+                    value_suffix(values, key),
+                    note_suffix(&notes, key)
+                );
+            }
+        }
+        println!("   {}", drift.summary_line().dimmed());
+        println!();
+    }
+    print_ignored(ignored);
+
+    check_extra(&extra, options)?;
+    if drifted > 0 {
+        return Err(crate::AppError::CommandExecutionError(format!(
+            "Drift detected in {} of {} files",
+            drifted,
+            env_paths.len()
+        )));
+    }
+
+    println!(
+        "{}{}",
+        icon(Symbol::Ok),
+        format!("All {} files in sync", env_paths.len()).green()
+    );
+    Ok(())
+}
+
+/// Compare the secrets of `project` with those of `against`
+///
+/// Keys only in `project` are reported like local-only keys, so
+/// `--fail-on-extra` fails when `project` has keys `against` lacks.
+async fn compare_projects<P: SecretsProvider>(
+    provider: &P,
+    project: &str,
+    against: &str,
+    options: &StatusOptions,
+) -> Result<()> {
+    if options.section.is_some() {
+        return Err(crate::AppError::InvalidArguments(
+            "--section applies to .env files and cannot be combined with --against".to_string(),
+        ));
+    }
+
+    let proj = find_project(provider, project).await?;
+    let other = find_project(provider, against).await?;
+    let secrets = provider.get_secrets_map(&proj.id).await?;
+    let against_secrets = provider.get_secrets_map(&other.id).await?;
+    let drift = compare(&against_secrets, &secrets).ignore(&options.ignore);
+    let extra: BTreeSet<String> = drift.only_local.iter().cloned().collect();
+
+    let format = options.format.unwrap_or(OutputFormat::Text);
+    if format.is_structured() {
+        let comparison = ProjectComparison {
+            in_sync: drift.is_clean(),
+            project: proj,
+            against: other,
+            only_in_project: drift.only_local,
+            only_in_against: drift.only_remote,
+            different: drift.different,
+            matching: drift.matching,
+            ignored: drift.ignored,
+        };
+        println!("{}", output::serialize(&comparison, format)?);
+        return check_extra(&extra, options);
+    }
+
+    if options.short {
+        for line in short_lines(&drift) {
+            println!("{}", color_short_line(&line));
+        }
+        return check_extra(&extra, options);
+    }
+
+    println!(
+        "{}Comparing {} ({}) with {} ({})",
+        icon(Symbol::Project),
+        proj.name,
+        proj.id,
+        other.name,
+        other.id
+    );
     println!();
 
-    // Get remote secrets from Bitwarden
-    let remote_secrets = provider.get_secrets_map(&proj.id).await?;
+    if drift.is_clean() {
+        println!(
+            "{}{}",
+            icon(Symbol::Ok),
+            format!("In sync - {} and {} are identical", proj.name, other.name).green()
+        );
+        println!("   {} secrets match", drift.matching);
+    } else {
+        println!("{}{}", icon(Symbol::Warn), "Drift detected:".yellow());
+        println!();
+        for (name, keys) in [
+            (&proj.name, &drift.only_local),
+            (&other.name, &drift.only_remote),
+        ] {
+            if keys.is_empty() {
+                continue;
+            }
+            println!(
+                "{}{} ({}):",
+                icon(Symbol::Alert),
+                format!("Only in {}", name).cyan(),
+                keys.len()
+            );
+            for key in keys {
+                println!("   - {}", key);
+            }
+            println!();
+        }
+        if !drift.different.is_empty() {
+            println!(
+                "{}{} ({}):",
+                icon(Symbol::Changed),
+                "Different values".yellow(),
+                drift.different.len()
+            );
+            let values = options
+                .reveal_prefix
+                .map(|reveal| (&against_secrets, &secrets, reveal));
+            for key in &drift.different {
+                println!("   - {}{}", key, value_suffix(values, key));
+            }
+            println!();
+        }
+    }
+    print_ignored(drift.ignored);
+    println!(
+        "{}",
+        format!(
+            "In sync: {}, Only in {}: {}, Only in {}: {}, Changed: {}",
+            drift.matching,
+            proj.name,
+            drift.only_local.len(),
+            other.name,
+            drift.only_remote.len(),
+            drift.different.len()
+        )
+        .dimmed()
+    );
+    check_extra(&extra, options)
+}
 
-    // Get local secrets from .env file
-    let local_secrets = if Path::new(env_path).exists() {
-        parser::read_env_file(env_path).map_err(|e| {
-            crate::AppError::EnvFileReadError(format!("Failed to read {}: {}", env_path, e))
-        })?
+/// With `fail_on_extra`, fail if any local file had keys Bitwarden lacks
+fn check_extra(extra: &BTreeSet<String>, options: &StatusOptions) -> Result<()> {
+    if !options.fail_on_extra || extra.is_empty() {
+        return Ok(());
+    }
+    let keys: Vec<&str> = extra.iter().map(String::as_str).collect();
+    Err(crate::AppError::CommandExecutionError(format!(
+        "{} local key(s) missing from Bitwarden: {}. Push them or add them to --ignore",
+        keys.len(),
+        keys.join(", ")
+    )))
+}
+
+/// Look a project up by ID, then by name
+pub(crate) async fn find_project<P: SecretsProvider>(
+    provider: &P,
+    project: &str,
+) -> Result<Project> {
+    if let Some(p) = provider.get_project(project).await? {
+        Ok(p)
+    } else if let Some(p) = provider.get_project_by_name(project).await? {
+        Ok(p)
     } else {
-        println!("⚠️  Local file '{}' not found", env_path);
-        Default::default()
+        Err(crate::AppError::ItemNotFound(format!(
+            "Project: {}",
+            project
+        )))
+    }
+}
+
+/// Expand `--env-file` values and an optional glob into a list of paths
+fn resolve_env_files(env_files: &[String], file_glob: Option<&str>) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = env_files.to_vec();
+
+    if let Some(pattern) = file_glob {
+        let entries = glob::glob(pattern).map_err(|e| {
+            crate::AppError::InvalidArguments(format!("Invalid glob '{}': {}", pattern, e))
+        })?;
+        let mut matched: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        if matched.is_empty() {
+            return Err(crate::AppError::EnvFileReadError(format!(
+                "No files match '{}'",
+                pattern
+            )));
+        }
+        matched.sort();
+        paths.extend(matched);
+    }
+
+    if paths.is_empty() {
+        paths.push(".env".to_string());
+    }
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    Ok(paths)
+}
+
+/// Read a local .env file, treating a missing file as empty
+///
+/// A path of `-` reads the content from stdin; a directory is read as
+/// `.env.d/` fragments.
+/// Secrets of `env_path` and their drift from `remote`, limited to keys of
+/// `--section` when it is set
+fn local_drift(
+    remote: &HashMap<String, String>,
+    env_path: &str,
+    options: &StatusOptions,
+) -> Result<(HashMap<String, String>, Drift)> {
+    let mut local = read_local(env_path)?;
+    let drift = match &options.section {
+        Some(section) if env_path == "-" || !Path::new(env_path).exists() => {
+            return Err(crate::AppError::InvalidArguments(format!(
+                "--section {} needs an existing .env file, not {}",
+                section, env_path
+            )));
+        }
+        Some(section) => {
+            let keys = sections::section_keys(env_path, section, TextEncoding::default())?;
+            local.retain(|key, _| keys.contains(key));
+            let remote: HashMap<String, String> = remote
+                .iter()
+                .filter(|(key, _)| keys.contains(*key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            compare(&remote, &local)
+        }
+        None => compare(remote, &local),
     };
+    Ok((local, drift.ignore(&options.ignore)))
+}
+
+fn read_local(env_path: &str) -> Result<HashMap<String, String>> {
+    if env_path == "-" {
+        parser::read_env_from_reader(std::io::stdin().lock())
+            .map_err(|e| crate::AppError::EnvFileReadError(format!("Failed to read stdin: {}", e)))
+    } else if fragments::is_fragment_dir(env_path) {
+        fragments::read_fragments(
+            Path::new(env_path),
+            Whitespace::Trim,
+            TextEncoding::default(),
+        )
+    } else if Path::new(env_path).exists() {
+        parser::read_env_file(env_path).map_err(|e| parser::read_error(env_path, e))
+    } else {
+        eprintln!("{}Local file '{}' not found", icon(Symbol::Warn), env_path);
+        Ok(Default::default())
+    }
+}
 
-    // Compare
-    let remote_keys: HashSet<_> = remote_secrets.keys().collect();
-    let local_keys: HashSet<_> = local_secrets.keys().collect();
+/// Format a key's note for display after the key, or nothing if it has none
+///
+/// Notes are flattened to one line and truncated so they cannot be mistaken
+/// for a value.
+fn note_suffix(notes: &HashMap<String, String>, key: &str) -> String {
+    match notes.get(key) {
+        Some(note) => format!("  {}", format!("[note: {}]", preview_note(note)).dimmed()),
+        None => String::new(),
+    }
+}
 
-    let only_remote: Vec<_> = remote_keys.difference(&local_keys).collect();
-    let only_local: Vec<_> = local_keys.difference(&remote_keys).collect();
-    let in_both: Vec<_> = remote_keys.intersection(&local_keys).collect();
+/// Remote and local values of a drift, and how many characters to reveal
+type DriftValues<'a> = Option<(
+    &'a HashMap<String, String>,
+    &'a HashMap<String, String>,
+    usize,
+)>;
 
-    // Check for value differences
-    let mut different_values = Vec::new();
-    for key in &in_both {
-        if remote_secrets.get(*key as &str) != local_secrets.get(*key as &str) {
-            different_values.push(*key);
+/// Format the masked remote and local values of a changed key
+fn value_suffix(values: DriftValues<'_>, key: &str) -> String {
+    match values {
+        Some((remote, local, reveal)) => {
+            let remote = remote.get(key).map(String::as_str).unwrap_or_default();
+            let local = local.get(key).map(String::as_str).unwrap_or_default();
+            // Colored like a diff so the two masked sides stay apart
+            format!(
+                ": {} → {}",
+                mask_value(remote, reveal).red(),
+                mask_value(local, reveal).green()
+            )
         }
+        None => String::new(),
+    }
+}
+
+/// Mask a secret value, revealing up to `reveal` leading characters
+///
+/// At most half of the value is ever revealed, so short values are never
+/// shown in full. With nothing revealed only the length is shown.
+pub fn mask_value(value: &str, reveal: usize) -> String {
+    let len = value.chars().count();
+    let reveal = reveal.min(len / 2);
+    if reveal == 0 {
+        return format!("<{} chars>", len);
     }
+    let prefix: String = value.chars().take(reveal).collect();
+    format!("{}{}", prefix, MASK)
+}
 
-    // Print status
-    if only_remote.is_empty() && only_local.is_empty() && different_values.is_empty() {
-        println!("✅ In sync - Local and remote are identical");
-        println!("   {} secrets match", in_both.len());
+fn preview_note(note: &str) -> String {
+    let flat = note.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > NOTE_PREVIEW_LEN {
+        let truncated: String = flat.chars().take(NOTE_PREVIEW_LEN - 1).collect();
+        format!("{}…", truncated)
     } else {
-        println!("⚠️  Out of sync detected:");
+        flat
+    }
+}
+
+/// Report how many drifted keys `--ignore` suppressed
+fn print_ignored(ignored: usize) {
+    if ignored > 0 {
+        println!("   {} ignored key(s) not reported", ignored);
+    }
+}
+
+/// Porcelain drift lines sorted by key: `+` local only, `-` remote only,
+/// `~` different value
+pub fn short_lines(drift: &Drift) -> Vec<String> {
+    let mut entries: Vec<(&str, char)> = drift
+        .only_local
+        .iter()
+        .map(|key| (key.as_str(), '+'))
+        .chain(drift.only_remote.iter().map(|key| (key.as_str(), '-')))
+        .chain(drift.different.iter().map(|key| (key.as_str(), '~')))
+        .collect();
+    entries.sort();
+    entries
+        .into_iter()
+        .map(|(key, marker)| format!("{} {}", marker, key))
+        .collect()
+}
+
+/// A [`short_lines`] line colored like a diff: green for local additions,
+/// red for keys only in Bitwarden, yellow for changed values
+///
+/// Plain whenever color is off, e.g. with `--color never`, `NO_COLOR` or
+/// stdout piped to another program.
+fn color_short_line(line: &str) -> ColoredString {
+    match line.chars().next() {
+        Some('+') => line.green(),
+        Some('-') => line.red(),
+        Some('~') => line.yellow(),
+        _ => line.normal(),
+    }
+}
+
+/// Print the detailed drift report for a single file
+fn print_drift(drift: &Drift, notes: &HashMap<String, String>, values: DriftValues<'_>) {
+    if drift.is_clean() {
+        println!(
+            "{}{}",
+            icon(Symbol::Ok),
+            "In sync - Local and remote are identical".green()
+        );
+        println!("   {} secrets match", drift.matching);
+    } else {
+        println!("{}{}", icon(Symbol::Warn), "Out of sync detected:".yellow());
         println!();
 
-        if !only_remote.is_empty() {
-            println!("📥 Only in Bitwarden ({}):", only_remote.len());
-            for key in only_remote {
-                println!("   - {}", key);
+        if !drift.only_remote.is_empty() {
+            println!(
+                "{}{} ({}):",
+                icon(Symbol::Download),
+                "Only in Bitwarden".cyan(),
+                drift.only_remote.len()
+            );
+            for key in &drift.only_remote {
+                println!("   - {}{}", key, note_suffix(notes, key));
             }
-            println!("   → Run 'bwenv pull' to download these");
+            println!(
+                "   {}Run 'bwenv pull' to download these",
+                icon(Symbol::Hint)
+            );
             println!();
         }
 
-        if !only_local.is_empty() {
-            println!("📤 Only in local .env ({}):", only_local.len());
-            for key in only_local {
+        if !drift.only_local.is_empty() {
+            println!(
+                "{}{} ({}):",
+                icon(Symbol::Upload),
+                "Only in local .env".cyan(),
+                drift.only_local.len()
+            );
+            for key in &drift.only_local {
                 println!("   - {}", key);
             }
-            println!("   → Run 'bwenv push' to upload these");
+            println!("   {}Run 'bwenv push' to upload these", icon(Symbol::Hint));
             println!();
         }
 
-        if !different_values.is_empty() {
-            println!("🔄 Different values ({}):", different_values.len());
-            for key in different_values {
-                println!("   - {}", key);
+        if !drift.different.is_empty() {
+            println!(
+                "{}{} ({}):",
+                icon(Symbol::Changed),
+                "Different values".yellow(),
+                drift.different.len()
+            );
+            for key in &drift.different {
+                println!(
+                    "   - {}{}{}",
+                    key,
+                    value_suffix(values, key),
+                    note_suffix(notes, key)
+                );
             }
-            println!("   → Run 'bwenv pull --force' to overwrite local");
-            println!("   → Run 'bwenv push --overwrite' to overwrite remote");
+            println!(
+                "   {}Run 'bwenv pull --force' to overwrite local",
+                icon(Symbol::Hint)
+            );
+            println!(
+                "   {}Run 'bwenv push --overwrite' to overwrite remote",
+                icon(Symbol::Hint)
+            );
             println!();
         }
     }
+}
 
-    Ok(())
+/// Ordering of projects in `list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ProjectSort {
+    /// Sort by project name
+    #[default]
+    Name,
+    /// Sort by project ID
+    Id,
+}
+
+/// Options controlling the `list` output
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Case-insensitive substring filter on project names, or on secret keys
+    /// when a project is given
+    pub search: Option<String>,
+    /// Project ordering; secrets are always sorted by key
+    pub sort: ProjectSort,
+    /// Output format, text when not set
+    pub format: Option<OutputFormat>,
+    /// Group projects under their organization
+    pub tree: bool,
+    /// Hide projects the token is known to only be able to read
+    pub writable: bool,
+}
+
+/// A secret as shown by `list --format json|yaml`; values are never included
+#[derive(Debug, Clone, Serialize)]
+pub struct ListedSecret {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 /// List projects and optionally secrets within a project
-pub async fn list<P: SecretsProvider>(provider: P, project: Option<&str>) -> Result<()> {
+pub async fn list<P: SecretsProvider>(
+    provider: P,
+    project: Option<&str>,
+    options: &ListOptions,
+) -> Result<()> {
+    let search = options.search.as_deref();
+    let format = options.format.unwrap_or(OutputFormat::Text);
+    format.ensure_supported("list", &LIST_FORMATS)?;
+
     if let Some(project_filter) = project {
         // List secrets in specific project
-        let proj = if let Ok(Some(p)) = provider.get_project(project_filter).await {
-            p
-        } else if let Ok(Some(p)) = provider.get_project_by_name(project_filter).await {
-            p
-        } else {
-            return Err(crate::AppError::ItemNotFound(format!(
-                "Project: {}",
-                project_filter
-            )));
-        };
+        let proj = find_project(&provider, project_filter).await?;
 
-        println!("Project: {} ({})", proj.name, proj.id);
-        println!("\nSecrets:");
+        let mut secrets: Vec<_> = provider
+            .list_secrets(&proj.id)
+            .await?
+            .into_iter()
+            .filter(|s| matches_search(&s.key, search))
+            .collect();
+        secrets.sort_by(|a, b| a.key.cmp(&b.key));
+
+        if format.is_structured() {
+            let listed: Vec<ListedSecret> = secrets
+                .into_iter()
+                .map(|s| ListedSecret {
+                    key: s.key,
+                    note: s.note,
+                })
+                .collect();
+            println!("{}", output::serialize(&listed, format)?);
+            return Ok(());
+        }
 
-        let secrets = provider.list_secrets(&proj.id).await?;
+        println!("Project: {} ({})", proj.name, proj.id);
+        println!("\n{}", "Secrets:".bold());
         if secrets.is_empty() {
-            println!("  No secrets found");
+            match search {
+                Some(pattern) => println!("  No secrets match '{}'", pattern),
+                None => println!("  No secrets found"),
+            }
         } else {
             for secret in secrets {
                 if let Some(note) = &secret.note {
@@ -134,14 +836,52 @@ pub async fn list<P: SecretsProvider>(provider: P, project: Option<&str>) -> Res
         }
     } else {
         // List all projects
-        let projects = provider.list_projects().await?;
+        let projects: Vec<_> = provider
+            .list_projects()
+            .await?
+            .into_iter()
+            .filter(|p| matches_search(&p.name, search) && !(options.writable && p.is_read_only()))
+            .collect();
+        let projects = sort_projects(projects, options.sort);
+
+        if format.is_structured() {
+            if options.tree {
+                let tree = group_by_organization(&projects);
+                println!("{}", output::serialize(&tree, format)?);
+            } else {
+                println!("{}", output::serialize(&projects, format)?);
+            }
+            return Ok(());
+        }
 
         if projects.is_empty() {
-            println!("No projects found");
+            match search {
+                Some(pattern) => println!("No projects match '{}'", pattern),
+                None => println!("No projects found"),
+            }
+        } else if options.tree {
+            println!("{}", "Projects by organization:".bold());
+            for (organization, projects) in group_by_organization(&projects) {
+                println!("  Organization {}", organization);
+                for project in projects {
+                    println!(
+                        "    {} ({}){}",
+                        project.name,
+                        project.id,
+                        access_suffix(project)
+                    );
+                }
+            }
+            println!("\nUse 'bwenv list --project <name>' to see secrets in a project");
         } else {
-            println!("Projects:");
+            println!("{}", "Projects:".bold());
             for project in projects {
-                println!("  {} ({})", project.name, project.id);
+                println!(
+                    "  {} ({}){}",
+                    project.name,
+                    project.id,
+                    access_suffix(&project)
+                );
             }
             println!("\nUse 'bwenv list --project <name>' to see secrets in a project");
         }
@@ -149,3 +889,358 @@ pub async fn list<P: SecretsProvider>(provider: P, project: Option<&str>) -> Res
 
     Ok(())
 }
+
+/// Marks projects the token can't push to; unknown access is not marked
+fn access_suffix(project: &Project) -> &'static str {
+    if project.is_read_only() {
+        " [read-only]"
+    } else {
+        ""
+    }
+}
+
+/// Sort projects for stable output, breaking ties by the other field
+fn sort_projects(mut projects: Vec<Project>, sort: ProjectSort) -> Vec<Project> {
+    match sort {
+        ProjectSort::Name => projects.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id))),
+        ProjectSort::Id => projects.sort_by(|a, b| a.id.cmp(&b.id)),
+    }
+    projects
+}
+
+/// Projects keyed by organization ID, keeping their order within each one
+fn group_by_organization(projects: &[Project]) -> BTreeMap<&str, Vec<&Project>> {
+    let mut tree: BTreeMap<&str, Vec<&Project>> = BTreeMap::new();
+    for project in projects {
+        tree.entry(project.organization_id.as_str())
+            .or_default()
+            .push(project);
+    }
+    tree
+}
+
+/// Case-insensitive substring match; no pattern matches everything
+fn matches_search(name: &str, search: Option<&str>) -> bool {
+    match search {
+        Some(pattern) => name.to_lowercase().contains(&pattern.to_lowercase()),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ignore_removes_keys_from_all_categories() {
+        let remote = map(&[("CHANGED", "remote"), ("REMOTE_ONLY", "r"), ("KEPT", "r")]);
+        let local = map(&[("CHANGED", "local"), ("LOCAL_DEV_PORT", "3000")]);
+
+        let ignore = ["CHANGED", "REMOTE_ONLY", "LOCAL_DEV_PORT", "UNUSED"].map(String::from);
+        let drift = compare(&remote, &local).ignore(&ignore);
+
+        assert_eq!(drift.only_remote, vec!["KEPT"]);
+        assert!(drift.only_local.is_empty());
+        assert!(drift.different.is_empty());
+        assert_eq!(drift.ignored, 3);
+    }
+
+    #[test]
+    fn test_summary_line_counts_each_category() {
+        let remote = map(&[
+            ("SAME", "1"),
+            ("ALSO_SAME", "2"),
+            ("CHANGED", "a"),
+            ("GONE", "x"),
+        ]);
+        let local = map(&[
+            ("SAME", "1"),
+            ("ALSO_SAME", "2"),
+            ("CHANGED", "b"),
+            ("NEW", "y"),
+        ]);
+
+        assert_eq!(
+            compare(&remote, &local).summary_line(),
+            "In sync: 2, Added: 1, Removed: 1, Changed: 1"
+        );
+    }
+
+    #[test]
+    fn test_short_lines() {
+        let remote = map(&[("SHARED", "a"), ("CHANGED", "remote"), ("REMOTE_ONLY", "r")]);
+        let local = map(&[("SHARED", "a"), ("CHANGED", "local"), ("LOCAL_KEY", "l")]);
+
+        let lines = short_lines(&compare(&remote, &local));
+
+        assert_eq!(lines, vec!["~ CHANGED", "+ LOCAL_KEY", "- REMOTE_ONLY"]);
+    }
+
+    #[test]
+    fn test_short_lines_are_colored_by_marker() {
+        use colored::Color;
+
+        let colors: Vec<_> = ["+ NEW", "- GONE", "~ CHANGED"]
+            .iter()
+            .map(|line| color_short_line(line).fgcolor)
+            .collect();
+
+        assert_eq!(
+            colors,
+            vec![Some(Color::Green), Some(Color::Red), Some(Color::Yellow)]
+        );
+        // The text itself is unchanged, so piped output stays porcelain
+        assert_eq!(color_short_line("+ NEW").input, "+ NEW");
+    }
+
+    #[test]
+    fn test_compare_identical() {
+        let secrets = map(&[("A", "1"), ("B", "2")]);
+        let drift = compare(&secrets, &secrets);
+        assert!(drift.is_clean());
+        assert_eq!(drift.matching, 2);
+    }
+
+    #[test]
+    fn test_compare_detects_drift() {
+        let remote = map(&[("A", "1"), ("B", "2"), ("REMOTE", "r")]);
+        let local = map(&[("A", "1"), ("B", "changed"), ("LOCAL", "l")]);
+
+        let drift = compare(&remote, &local);
+
+        assert!(!drift.is_clean());
+        assert_eq!(drift.only_remote, vec!["REMOTE".to_string()]);
+        assert_eq!(drift.only_local, vec!["LOCAL".to_string()]);
+        assert_eq!(drift.different, vec!["B".to_string()]);
+        assert_eq!(drift.matching, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sort_projects_from_mock_provider() {
+        use crate::bitwarden::MockProvider;
+
+        let provider = MockProvider::new();
+        for (id, name) in [("proj_3", "Billing"), ("proj_1", "Web"), ("proj_2", "Api")] {
+            provider.add_project(MockProvider::project(id, name));
+        }
+        let projects = provider.list_projects().await.unwrap();
+
+        let by_name = sort_projects(projects.clone(), ProjectSort::Name);
+        let names: Vec<_> = by_name.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Api", "Billing", "Web"]);
+
+        let by_id = sort_projects(projects, ProjectSort::Id);
+        let ids: Vec<_> = by_id.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["proj_1", "proj_2", "proj_3"]);
+    }
+
+    #[test]
+    fn test_group_by_organization_sorts_orgs_and_keeps_project_order() {
+        let projects: Vec<Project> = [
+            ("p1", "Api", "org_b"),
+            ("p2", "Billing", "org_a"),
+            ("p3", "Web", "org_b"),
+        ]
+        .iter()
+        .map(|(id, name, org)| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            organization_id: org.to_string(),
+            access: None,
+        })
+        .collect();
+
+        let tree = group_by_organization(&projects);
+
+        let orgs: Vec<_> = tree.keys().copied().collect();
+        assert_eq!(orgs, vec!["org_a", "org_b"]);
+        let names: Vec<_> = tree["org_b"].iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Api", "Web"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_status_reject_env_format() {
+        use crate::bitwarden::MockProvider;
+
+        let list_options = ListOptions {
+            format: Some(OutputFormat::Env),
+            ..Default::default()
+        };
+        let result = list(MockProvider::new(), None, &list_options).await;
+        assert!(matches!(result, Err(crate::AppError::InvalidArguments(_))));
+
+        let status_options = StatusOptions {
+            format: Some(OutputFormat::Env),
+            ..Default::default()
+        };
+        let result = execute(MockProvider::new(), "proj_1", &status_options).await;
+        assert!(matches!(result, Err(crate::AppError::InvalidArguments(_))));
+    }
+
+    #[test]
+    fn test_file_status_serializes_flat() {
+        let remote = map(&[("A", "1"), ("REMOTE", "r")]);
+        let local = map(&[("A", "1")]);
+        let drift = compare(&remote, &local);
+        let status = FileStatus {
+            path: ".env".to_string(),
+            in_sync: drift.is_clean(),
+            drift,
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+
+        assert_eq!(json["path"], ".env");
+        assert_eq!(json["in_sync"], false);
+        assert_eq!(json["only_remote"], serde_json::json!(["REMOTE"]));
+        assert_eq!(json["matching"], 1);
+    }
+
+    #[test]
+    fn test_matches_search_is_case_insensitive() {
+        assert!(matches_search("Payments API", Some("api")));
+        assert!(matches_search("DATABASE_URL", Some("base_u")));
+        assert!(!matches_search("DATABASE_URL", Some("redis")));
+        assert!(matches_search("anything", None));
+    }
+
+    #[test]
+    fn test_mask_value_reveals_prefix_only() {
+        assert_eq!(mask_value("sk_live_abc123", 0), "<14 chars>");
+        assert_eq!(mask_value("sk_live_abc123", 5), "sk_li•••");
+        assert_eq!(mask_value("sk_test_xyz", 5), "sk_te•••");
+    }
+
+    #[test]
+    fn test_mask_value_never_reveals_more_than_half() {
+        assert_eq!(mask_value("abcd", 10), "ab•••");
+        assert_eq!(mask_value("a", 3), "<1 chars>");
+        assert_eq!(mask_value("", 3), "<0 chars>");
+    }
+
+    #[test]
+    fn test_preview_note_flattens_and_truncates() {
+        assert_eq!(
+            preview_note("Stripe key\nrotate monthly"),
+            "Stripe key rotate monthly"
+        );
+
+        let long = "x".repeat(100);
+        let preview = preview_note(&long);
+        assert_eq!(preview.chars().count(), NOTE_PREVIEW_LEN);
+        assert!(preview.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_fail_on_extra_fails_only_for_local_only_keys() {
+        use crate::bitwarden::MockProvider;
+        use std::fs;
+        use tempfile::tempdir;
+
+        let provider = MockProvider::with_project("proj_1", "App");
+        for (key, value) in [("SHARED", "remote"), ("REMOTE_ONLY", "r")] {
+            provider
+                .create_secret("proj_1", key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        let env_files = vec![env_file.to_str().unwrap().to_string()];
+        let run = |ignore: &[&str], short: bool| {
+            let options = StatusOptions {
+                env_files: env_files.clone(),
+                ignore: ignore.iter().map(|k| k.to_string()).collect(),
+                short,
+                fail_on_extra: true,
+                ..Default::default()
+            };
+            let provider = provider.clone();
+            async move { execute(provider, "App", &options).await }
+        };
+
+        // Remote-only and changed keys alone don't fail
+        fs::write(&env_file, "SHARED=local\n").unwrap();
+        assert!(run(&[], false).await.is_ok());
+
+        fs::write(&env_file, "SHARED=local\nLOCAL_ONLY=l\n").unwrap();
+        for short in [false, true] {
+            let err = run(&[], short).await.unwrap_err();
+            assert!(err.to_string().contains("LOCAL_ONLY"));
+            assert_eq!(err.exit_code(), 1);
+        }
+        assert!(run(&["LOCAL_ONLY"], false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_against_compares_two_projects() {
+        use crate::bitwarden::MockProvider;
+        use crate::AppError;
+
+        let provider = MockProvider::new();
+        for (id, name) in [("proj_1", "staging"), ("proj_2", "prod")] {
+            provider.add_project(MockProvider::project(id, name));
+        }
+        for (project, key, value) in [
+            ("proj_1", "API_URL", "https://staging"),
+            ("proj_1", "DEBUG", "1"),
+            ("proj_1", "SHARED", "same"),
+            ("proj_2", "API_URL", "https://prod"),
+            ("proj_2", "SHARED", "same"),
+            ("proj_2", "SENTRY_DSN", "dsn"),
+        ] {
+            provider
+                .create_secret(project, key, value, None)
+                .await
+                .unwrap();
+        }
+
+        let options = StatusOptions {
+            against: Some("prod".to_string()),
+            ..Default::default()
+        };
+        execute(provider.clone(), "staging", &options)
+            .await
+            .unwrap();
+
+        let options = StatusOptions {
+            against: Some("prod".to_string()),
+            fail_on_extra: true,
+            ..Default::default()
+        };
+        match execute(provider.clone(), "staging", &options).await {
+            Err(AppError::CommandExecutionError(message)) => {
+                assert!(message.contains("DEBUG"));
+                assert!(!message.contains("SENTRY_DSN"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let options = StatusOptions {
+            against: Some("prod".to_string()),
+            ignore: vec!["DEBUG".to_string()],
+            fail_on_extra: true,
+            ..Default::default()
+        };
+        execute(provider.clone(), "staging", &options)
+            .await
+            .unwrap();
+
+        let options = StatusOptions {
+            against: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            execute(provider, "staging", &options).await,
+            Err(AppError::ItemNotFound(_))
+        ));
+    }
+}
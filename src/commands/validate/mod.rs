@@ -2,13 +2,453 @@
 //!
 //! Validates .env file format.
 
-use crate::env::parser;
+use crate::config::ValidationConfig;
+use crate::env::encoding::TextEncoding;
+use crate::env::fix::{self, LineEnding};
+use crate::env::parser::FormatError;
+use crate::env::{lock, parser};
+use crate::output::{self, icon, OutputFormat, Symbol};
 use crate::{AppError, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
 
-pub async fn execute(input: &str) -> Result<()> {
-    parser::validate_env_file(input)
+/// Formats `validate --format` accepts
+pub const FORMATS: [OutputFormat; 3] = [OutputFormat::Text, OutputFormat::Json, OutputFormat::Yaml];
+
+/// Machine-readable validation result,
+/// `{ "valid": bool, "errors": [{ "line": N, "message": "..." }] }`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub errors: Vec<FormatError>,
+}
+
+/// Options controlling validation
+#[derive(Debug, Clone, Default)]
+pub struct ValidateOptions {
+    /// Warn about values with leading/trailing whitespace
+    pub warn_whitespace: bool,
+    /// Rewrite the file correcting safe issues before validating
+    pub fix: bool,
+    /// Also drop earlier definitions of duplicate keys when fixing
+    pub fix_duplicates: bool,
+    /// Line endings of the fixed file
+    pub line_ending: LineEnding,
+    /// Output format, text when not set
+    pub format: Option<OutputFormat>,
+    /// Key naming policy from `[validation]`
+    pub policy: ValidationConfig,
+    /// Reject keys a shell can't export, e.g. `APP.NAME` or `1ST_KEY`
+    pub strict_keys: bool,
+    /// Lock the file while fixing it, waiting at most this long for another
+    /// process to finish; `None` disables locking
+    pub lock_timeout: Option<Duration>,
+    /// Encoding of the file
+    pub encoding: TextEncoding,
+    /// Accept a bare `KEY` line as `KEY=`
+    pub allow_bare_keys: bool,
+}
+
+pub async fn execute(input: &str, options: &ValidateOptions) -> Result<()> {
+    let format = options.format.unwrap_or(OutputFormat::Text);
+    format.ensure_supported("validate", &FORMATS)?;
+
+    if format.is_structured() {
+        if options.fix || options.warn_whitespace {
+            return Err(AppError::InvalidArguments(format!(
+                "--format {} cannot be combined with --fix or --warn-whitespace",
+                format
+            )));
+        }
+
+        let report = validation_report(input, options)?;
+        println!("{}", output::serialize(&report, format)?);
+        if !report.valid {
+            return Err(AppError::EnvFileFormatError(format!(
+                "Validation failed: {} error(s) in {}",
+                report.errors.len(),
+                input
+            )));
+        }
+        return Ok(());
+    }
+
+    if options.fix && !options.encoding.is_utf8() {
+        return Err(AppError::InvalidArguments(format!(
+            "--fix rewrites the file as UTF-8 and cannot be combined with --encoding {}",
+            options.encoding
+        )));
+    }
+    if options.fix {
+        let _lock = lock::maybe_lock_exclusive(input, options.lock_timeout)?;
+        apply_fixes(input, options)?;
+    } else {
+        warn_line_endings(input);
+    }
+
+    parser::validate_env_file_with(input, options.encoding, options.allow_bare_keys)
         .map_err(|e| AppError::EnvFileFormatError(format!("Validation failed: {}", e)))?;
+    let keys = key_lines(input, options)?;
+    if options.strict_keys {
+        let invalid = invalid_key_errors(&keys);
+        if !invalid.is_empty() {
+            let details: Vec<String> = invalid
+                .iter()
+                .map(|error| format!("line {}: {}", error.line, error.message))
+                .collect();
+            return Err(AppError::EnvFileFormatError(format!(
+                "Validation failed: {}",
+                details.join("; ")
+            )));
+        }
+    }
+    options.policy.enforce(keys.keys())?;
+
+    println!("{}{} is valid", icon(Symbol::Pass), input);
+
+    if options.warn_whitespace {
+        let issues = parser::find_whitespace_issues(input, options.encoding)
+            .map_err(|e| AppError::EnvFileReadError(format!("Failed to read {}: {}", input, e)))?;
+
+        if !issues.is_empty() {
+            println!();
+            println!(
+                "{}Values with surrounding whitespace ({}), trimmed when read:",
+                icon(Symbol::Warn),
+                issues.len()
+            );
+            for issue in issues {
+                let position = match (issue.leading, issue.trailing) {
+                    (true, true) => "leading and trailing",
+                    (true, false) => "leading",
+                    _ => "trailing",
+                };
+                println!(
+                    "   - {} (line {}): {} whitespace",
+                    issue.key, issue.line, position
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collect every format error, invalid key (with `strict_keys`) and naming
+/// policy violation in `input`
+pub fn validation_report(input: &str, options: &ValidateOptions) -> Result<ValidationReport> {
+    let mut errors =
+        parser::find_format_errors_with(input, options.encoding, options.allow_bare_keys)
+            .map_err(|e| parser::read_error(input, e))?;
+
+    let key_lines = key_lines(input, options)?;
+    if options.strict_keys {
+        errors.extend(invalid_key_errors(&key_lines));
+    }
+    for violation in options.policy.violations(key_lines.keys())? {
+        errors.push(FormatError {
+            line: key_lines[&violation.key],
+            message: violation.to_string(),
+        });
+    }
+    errors.sort_by_key(|error| error.line);
+
+    Ok(ValidationReport {
+        valid: errors.is_empty(),
+        errors,
+    })
+}
+
+/// Rewrite `input` with safe corrections and print what changed
+/// Line of each key in `input`, including bare keys when they're allowed
+fn key_lines(input: &str, options: &ValidateOptions) -> Result<HashMap<String, usize>> {
+    let mut lines = parser::read_key_lines(input, options.encoding)
+        .map_err(|e| parser::read_error(input, e))?;
+    if options.allow_bare_keys {
+        for (key, line) in parser::read_bare_keys(input, options.encoding)
+            .map_err(|e| parser::read_error(input, e))?
+        {
+            lines.entry(key).or_insert(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// Keys a shell can't export, by line
+fn invalid_key_errors(key_lines: &HashMap<String, usize>) -> Vec<FormatError> {
+    let mut errors: Vec<FormatError> = key_lines
+        .iter()
+        .filter(|(key, _)| !parser::is_valid_key_name(key))
+        .map(|(key, &line)| FormatError {
+            line,
+            message: if key.contains(char::is_whitespace) {
+                format!(
+                    "'{}' contains a space; environment variable names can't contain whitespace",
+                    key
+                )
+            } else {
+                format!(
+                    "{} is not a valid environment variable name ([A-Za-z_][A-Za-z0-9_]*)",
+                    key
+                )
+            },
+        })
+        .collect();
+    errors.sort_by_key(|error| error.line);
+    errors
+}
+
+/// Point out a byte order mark or CRLF line endings, which `--fix` removes
+fn warn_line_endings(input: &str) {
+    let Ok(content) = fs::read_to_string(input) else {
+        return;
+    };
+    let crlf = fix::crlf_lines(&content);
+    if fix::has_bom(&content) {
+        println!(
+            "{}{} starts with a byte order mark; run validate --fix to remove it",
+            icon(Symbol::Warn),
+            input
+        );
+    }
+    if crlf > 0 {
+        println!(
+            "{}{} has {} CRLF line ending(s); run validate --fix to convert them to LF",
+            icon(Symbol::Warn),
+            input,
+            crlf
+        );
+    }
+}
+
+fn apply_fixes(input: &str, options: &ValidateOptions) -> Result<()> {
+    let content = fs::read_to_string(input)
+        .map_err(|e| AppError::EnvFileReadError(format!("Failed to read {}: {}", input, e)))?;
+
+    let report = fix::fix_env_content_with(&content, options.fix_duplicates, options.line_ending);
+
+    if report.changed() {
+        fs::write(input, &report.content).map_err(|e| {
+            AppError::EnvFileWriteError(format!("Failed to write {}: {}", input, e))
+        })?;
+
+        println!("Fixed {} issue(s) in {}:", report.fixes.len(), input);
+        for fix in &report.fixes {
+            println!("   - line {}: {}", fix.line, fix.description);
+        }
+    } else {
+        println!("Nothing to fix in {}", input);
+    }
+
+    if !report.duplicates.is_empty() {
+        println!(
+            "{}Duplicate keys left in place (use --fix-duplicates to keep only the last): {}",
+            icon(Symbol::Warn),
+            report.duplicates.join(", ")
+        );
+    }
 
-    println!("✓ {} is valid", input);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_validation_report_json_for_invalid_file() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "GOOD=1\nMISSING_EQUALS\n=no_key\n").unwrap();
+
+        let report = validation_report(input.to_str().unwrap(), &Default::default()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+
+        assert_eq!(json["valid"], false);
+        let errors = json["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["line"], 2);
+        assert_eq!(errors[1]["line"], 3);
+        assert!(errors[1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("empty key name"));
+    }
+
+    #[test]
+    fn test_validation_report_includes_policy_violations() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "GOOD=1\nlower_case=2\n").unwrap();
+        let options = ValidateOptions {
+            policy: ValidationConfig {
+                require_uppercase: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let report = validation_report(input.to_str().unwrap(), &options).unwrap();
+
+        assert!(!report.valid);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 2);
+        assert!(report.errors[0].message.starts_with("lower_case "));
+    }
+
+    #[test]
+    fn test_strict_keys_reports_digit_dot_and_dash_keys() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(
+            &input,
+            "GOOD_KEY=1\n_PRIVATE=2\n1ST_KEY=3\nAPP.NAME=4\nAPP-PORT=5\n",
+        )
+        .unwrap();
+        let input = input.to_str().unwrap();
+
+        assert!(validation_report(input, &Default::default()).unwrap().valid);
+
+        let options = ValidateOptions {
+            strict_keys: true,
+            ..Default::default()
+        };
+        let report = validation_report(input, &options).unwrap();
+
+        let lines: Vec<_> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(report.errors[0].message.starts_with("1ST_KEY "));
+        assert!(report.errors[1].message.starts_with("APP.NAME "));
+        assert!(report.errors[2].message.starts_with("APP-PORT "));
+    }
+
+    #[test]
+    fn test_strict_keys_reports_keys_with_spaces() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "GREETING=hello world\nMY KEY=x\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        // A space in a value is fine, and a spaced key only fails strict mode
+        assert!(validation_report(input, &Default::default()).unwrap().valid);
+
+        let options = ValidateOptions {
+            strict_keys: true,
+            ..Default::default()
+        };
+        let report = validation_report(input, &options).unwrap();
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 2);
+        assert!(report.errors[0]
+            .message
+            .contains("'MY KEY' contains a space"));
+    }
+
+    #[tokio::test]
+    async fn test_strict_keys_fails_text_validation() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "APP.NAME=demo\n").unwrap();
+
+        let options = ValidateOptions {
+            strict_keys: true,
+            ..Default::default()
+        };
+        let err = execute(input.to_str().unwrap(), &options)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::EnvFileFormatError(_)));
+        assert!(err.to_string().contains("line 1: APP.NAME"));
+    }
+
+    #[tokio::test]
+    async fn test_json_format_still_fails_when_invalid() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "MISSING_EQUALS\n").unwrap();
+
+        let options = ValidateOptions {
+            format: Some(OutputFormat::Json),
+            ..Default::default()
+        };
+        let result = execute(input.to_str().unwrap(), &options).await;
+
+        assert!(matches!(result, Err(AppError::EnvFileFormatError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_env_format_is_rejected() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "GOOD=1\n").unwrap();
+
+        let options = ValidateOptions {
+            format: Some(OutputFormat::Env),
+            ..Default::default()
+        };
+        let result = execute(input.to_str().unwrap(), &options).await;
+
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fix_strips_bom_and_crlf() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "\u{feff}API_KEY=abc\r\nPORT=8080\r\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        let options = ValidateOptions {
+            fix: true,
+            ..Default::default()
+        };
+        execute(input, &options).await.unwrap();
+
+        assert_eq!(fs::read(input).unwrap(), b"API_KEY=abc\nPORT=8080\n");
+        execute(input, &Default::default()).await.unwrap();
+
+        // The fixed file round-trips unchanged through the parser
+        let vars = parser::read_env_file(input).unwrap();
+        assert_eq!(vars["API_KEY"], "abc");
+        let copy = dir.path().join(".env.copy");
+        parser::write_env_file(&copy, &vars, false).unwrap();
+        assert_eq!(parser::read_env_file(&copy).unwrap(), vars);
+    }
+
+    #[test]
+    fn test_allow_bare_keys_accepts_key_without_value() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join(".env");
+        fs::write(&input, "GOOD=1\nPASSTHROUGH\nlower\n").unwrap();
+        let input = input.to_str().unwrap();
+
+        let report = validation_report(input, &Default::default()).unwrap();
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors[0].message.contains("missing '='"));
+
+        let options = ValidateOptions {
+            allow_bare_keys: true,
+            ..Default::default()
+        };
+        assert!(validation_report(input, &options).unwrap().valid);
+
+        // Bare keys are still held to the naming policy
+        let options = ValidateOptions {
+            allow_bare_keys: true,
+            policy: ValidationConfig {
+                require_uppercase: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = validation_report(input, &options).unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 3);
+    }
+}
@@ -0,0 +1,133 @@
+//! Verify command - Assert that a local .env exactly matches Bitwarden
+//!
+//! Meant for CI: succeeds only when both sides have the same keys with the
+//! same values, and otherwise fails with the drifted keys and a summary.
+//! Unlike `status` there is no `--ignore`, and values are never printed.
+
+use crate::bitwarden::provider::SecretsProvider;
+use crate::commands::status::{self, Drift};
+use crate::env::encoding::TextEncoding;
+use crate::env::fragments;
+use crate::env::parser::{self, Whitespace};
+use crate::output::{icon, Symbol};
+use crate::{AppError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub async fn execute<P: SecretsProvider>(provider: P, project: &str, env_file: &str) -> Result<()> {
+    let proj = status::find_project(&provider, project).await?;
+    let local = read_local(env_file)?;
+    let remote = provider.get_secrets_map(&proj.id).await?;
+
+    let drift = status::compare(&remote, &local);
+    report(&drift, env_file, &proj.name)
+}
+
+/// Secrets of `env_file`; unlike `status`, a missing file is an error
+fn read_local(env_file: &str) -> Result<HashMap<String, String>> {
+    if !Path::new(env_file).exists() {
+        return Err(AppError::EnvFileReadError(format!(
+            "File {} not found",
+            env_file
+        )));
+    }
+    if fragments::is_fragment_dir(env_file) {
+        return fragments::read_fragments(
+            Path::new(env_file),
+            Whitespace::Trim,
+            TextEncoding::default(),
+        );
+    }
+    parser::read_env_file(env_file).map_err(|e| parser::read_error(env_file, e))
+}
+
+/// Print the outcome, failing unless `drift` is clean
+fn report(drift: &Drift, env_file: &str, project: &str) -> Result<()> {
+    if drift.is_clean() {
+        println!(
+            "{}{} matches project {} ({} secrets)",
+            icon(Symbol::Ok),
+            env_file,
+            project,
+            drift.matching
+        );
+        return Ok(());
+    }
+
+    for line in status::short_lines(drift) {
+        println!("{}", line);
+    }
+    Err(AppError::CommandExecutionError(format!(
+        "{} does not match project {}: {}",
+        env_file,
+        project,
+        drift.summary_line()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::MockProvider;
+    use std::fs;
+    use tempfile::tempdir;
+
+    async fn provider_with(secrets: &[(&str, &str)]) -> MockProvider {
+        let provider = MockProvider::with_project("proj_1", "App");
+        for (key, value) in secrets {
+            provider
+                .create_secret("proj_1", key, value, None)
+                .await
+                .unwrap();
+        }
+        provider
+    }
+
+    async fn verify(local: &str) -> Result<()> {
+        let provider = provider_with(&[("API_KEY", "remote-secret"), ("PORT", "8080")]).await;
+        let dir = tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, local).unwrap();
+        execute(provider, "App", env_file.to_str().unwrap()).await
+    }
+
+    fn mismatch(result: Result<()>) -> String {
+        match result {
+            Err(AppError::CommandExecutionError(message)) => {
+                assert!(!message.contains("secret"));
+                message
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_identical_passes() {
+        assert!(verify("API_KEY=remote-secret\nPORT=8080\n").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_local_only_key() {
+        let message = mismatch(verify("API_KEY=remote-secret\nPORT=8080\nDEBUG=1\n").await);
+        assert!(message.contains("Added: 1"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_remote_only_key() {
+        let message = mismatch(verify("API_KEY=remote-secret\n").await);
+        assert!(message.contains("Removed: 1"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_different_value() {
+        let message = mismatch(verify("API_KEY=local-secret\nPORT=8080\n").await);
+        assert!(message.contains("Changed: 1"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_missing_file() {
+        let provider = provider_with(&[]).await;
+        let result = execute(provider, "App", "/nonexistent/.env").await;
+        assert!(matches!(result, Err(AppError::EnvFileReadError(_))));
+    }
+}
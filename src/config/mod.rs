@@ -1,21 +1,551 @@
 //! Config module - .bwenv.toml configuration management
 //!
 //! Handles reading, writing, and validating project configuration.
+//! String values may reference environment variables as `$VAR`, `${VAR}` or
+//! `${VAR:-default}`, expanded when the file is loaded.
 
-use crate::Result;
+use crate::env::paths;
+use crate::{AppError, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Name of the project configuration file, looked up in the current directory
+pub const CONFIG_FILE: &str = ".bwenv.toml";
+
+/// Env file used when neither a flag nor the configuration names one
+pub const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Where an effective setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// A command-line flag
+    Flag,
+    /// `.bwenv.toml`
+    ConfigFile,
+    /// A `[profiles.<name>]` section of `.bwenv.toml`
+    Profile(String),
+    /// An environment variable
+    Environment(String),
+    /// The git repository
+    Git,
+    /// Built-in default
+    Default,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::Flag => write!(f, "command-line flag"),
+            ValueSource::ConfigFile => write!(f, "{}", CONFIG_FILE),
+            ValueSource::Profile(name) => write!(f, "profile '{}'", name),
+            ValueSource::Environment(var) => write!(f, "environment variable {}", var),
+            ValueSource::Git => write!(f, "git repository"),
+            ValueSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Config {
-    // TODO: Define configuration structure
+    /// Default Bitwarden project for this repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project: Option<String>,
+
+    /// Default .env file location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+
+    /// Automatically sync on pull
+    #[serde(default)]
+    pub auto_sync: bool,
+
+    /// Show secrets in status output
+    #[serde(default)]
+    pub show_secrets: bool,
+
+    /// Append-only JSON lines log of changes made to Bitwarden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<String>,
+
+    /// Settings for `bwenv status` in `[status]`
+    #[serde(default, skip_serializing_if = "StatusConfig::is_empty")]
+    pub status: StatusConfig,
+
+    /// Key naming policy enforced by `push` and `validate`, in `[validation]`
+    #[serde(default, skip_serializing_if = "ValidationConfig::is_empty")]
+    pub validation: ValidationConfig,
+
+    /// Named profiles, selected with `--profile`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// `[validation]` key naming policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ValidationConfig {
+    /// Keys may only contain uppercase letters, digits and underscores
+    #[serde(default)]
+    pub require_uppercase: bool,
+
+    /// Regex every key must start with a match of, e.g. `APP_|DB_`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_prefix_pattern: Option<String>,
+
+    /// Keys that must never appear
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbid_keys: Vec<String>,
+}
+
+/// A key breaking a `[validation]` rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    pub key: String,
+    pub rule: String,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.key, self.rule)
+    }
+}
+
+impl ValidationConfig {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Check `keys` against every rule, in key order
+    pub fn violations<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) -> Result<Vec<PolicyViolation>> {
+        let prefix = match &self.allowed_prefix_pattern {
+            Some(pattern) => {
+                let regex = Regex::new(&format!("^(?:{})", pattern)).map_err(|e| {
+                    AppError::ConfigError(format!(
+                        "Invalid validation.allowed_prefix_pattern '{}': {}",
+                        pattern, e
+                    ))
+                })?;
+                Some((regex, pattern))
+            }
+            None => None,
+        };
+
+        let mut keys: Vec<&String> = keys.into_iter().collect();
+        keys.sort();
+
+        let mut violations = Vec::new();
+        for key in keys {
+            let mut violate = |rule: String| {
+                violations.push(PolicyViolation {
+                    key: key.clone(),
+                    rule,
+                })
+            };
+            if self.forbid_keys.contains(key) {
+                violate("is forbidden (forbid_keys)".to_string());
+            }
+            if self.require_uppercase
+                && !key
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            {
+                violate(
+                    "must contain only uppercase letters, digits and underscores (require_uppercase)"
+                        .to_string(),
+                );
+            }
+            if let Some((regex, pattern)) = &prefix {
+                if !regex.is_match(key) {
+                    violate(format!(
+                        "does not start with a prefix matching '{}' (allowed_prefix_pattern)",
+                        pattern
+                    ));
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Fail with [`AppError::EnvFileFormatError`] naming every violation
+    pub fn enforce<'a>(&self, keys: impl IntoIterator<Item = &'a String>) -> Result<()> {
+        let violations = self.violations(keys)?;
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        Err(AppError::EnvFileFormatError(format!(
+            "Key naming policy violated: {}",
+            details.join("; ")
+        )))
+    }
+}
+
+/// `[status]` settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StatusConfig {
+    /// Keys never reported as drift, e.g. intentionally local-only variables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+}
+
+impl StatusConfig {
+    fn is_empty(&self) -> bool {
+        self.ignore.is_empty()
+    }
+}
+
+/// Per-profile overrides in `[profiles.<name>]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    /// .env file location for this profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
 }
 
 impl Config {
+    /// Load `.bwenv.toml` from the current directory, or defaults if absent
     pub fn load() -> Result<Self> {
-        todo!("Config loading implementation pending")
+        Self::load_from(CONFIG_FILE)
     }
 
+    /// Load configuration from `path`, or defaults if the file does not exist
+    ///
+    /// Environment variables in string values are expanded; one that is unset
+    /// and has no `:-default` is an error.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_from_with(path.as_ref(), |name| std::env::var(name).ok())
+    }
+
+    fn load_from_with(path: &Path, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let error = |e: String| AppError::ConfigError(format!("{}: {}", path.display(), e));
+        let mut value: toml::Value = toml::from_str(&content).map_err(|e| error(e.to_string()))?;
+        expand_strings(&mut value, "", &var).map_err(error)?;
+        value
+            .try_into()
+            .map_err(|e: toml::de::Error| error(e.to_string()))
+    }
+
+    /// Write the configuration to `.bwenv.toml` in the current directory
     pub fn save(&self) -> Result<()> {
-        todo!("Config saving implementation pending")
+        self.save_to(CONFIG_FILE)
+    }
+
+    /// Write the configuration to `path`
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize: {}", e)))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Look up a profile by name
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| AppError::ConfigError(format!("Unknown profile: {}", name)))
+    }
+
+    /// Resolve which .env file a command should use
+    ///
+    /// Precedence, first match wins:
+    /// 1. the explicit command-line flag
+    /// 2. `env_file` in `.bwenv.toml`
+    /// 3. `env_file` of the selected profile
+    /// 4. `.env`
+    pub fn resolve_env_file(
+        &self,
+        explicit: Option<&str>,
+        profile: Option<&str>,
+    ) -> Result<String> {
+        self.resolve_env_file_with_source(explicit, profile)
+            .map(|(path, _)| path)
+    }
+
+    /// Like [`Config::resolve_env_file`], also reporting where the path came from
+    pub fn resolve_env_file_with_source(
+        &self,
+        explicit: Option<&str>,
+        profile: Option<&str>,
+    ) -> Result<(String, ValueSource)> {
+        // Look the profile up first so a misspelled name is always reported
+        let profile_env_file = match profile {
+            Some(name) => self
+                .profile(name)?
+                .env_file
+                .as_deref()
+                .map(|path| (path, ValueSource::Profile(name.to_string()))),
+            None => None,
+        };
+
+        let (path, source) = explicit
+            .map(|path| (path, ValueSource::Flag))
+            .or(self
+                .env_file
+                .as_deref()
+                .map(|path| (path, ValueSource::ConfigFile)))
+            .or(profile_env_file)
+            .unwrap_or((DEFAULT_ENV_FILE, ValueSource::Default));
+        Ok((paths::expand_path(path)?, source))
+    }
+}
+
+/// Expand environment variables in every string of `value`, naming the key
+/// of the first one that can't be expanded
+fn expand_strings(
+    value: &mut toml::Value,
+    key: &str,
+    var: &impl Fn(&str) -> Option<String>,
+) -> std::result::Result<(), String> {
+    match value {
+        toml::Value::String(text) => {
+            *text = paths::expand_vars(text, var).map_err(|e| format!("{}: {}", key, e))?;
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                expand_strings(item, key, var)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (name, item) in table.iter_mut() {
+                let key = if key.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", key, name)
+                };
+                expand_strings(item, &key, var)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn with_profile(env_file: Option<&str>) -> Config {
+        let mut config = Config::default();
+        config.profiles.insert(
+            "staging".to_string(),
+            Profile {
+                env_file: env_file.map(str::to_string),
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_load_missing_file_gives_defaults() {
+        let dir = tempdir().unwrap();
+        let config = Config::load_from(dir.path().join(CONFIG_FILE)).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_load_init_template() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(
+            &path,
+            "default_project = \"MyProject\"\nenv_file = \".env.local\"\nauto_sync = false\n\n[profiles.staging]\nenv_file = \".env.staging\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+
+        assert_eq!(config.default_project.as_deref(), Some("MyProject"));
+        assert_eq!(config.env_file.as_deref(), Some(".env.local"));
+        assert_eq!(
+            config.profile("staging").unwrap().env_file.as_deref(),
+            Some(".env.staging")
+        );
+    }
+
+    #[test]
+    fn test_load_status_ignore() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "[status]\nignore = [\"LOCAL_DEV_PORT\"]\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+
+        assert_eq!(config.status.ignore, vec!["LOCAL_DEV_PORT"]);
+    }
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validation_require_uppercase() {
+        let policy = ValidationConfig {
+            require_uppercase: true,
+            ..Default::default()
+        };
+
+        let violations = policy.violations(&keys(&["API_KEY2", "dbUrl"])).unwrap();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].key, "dbUrl");
+        assert!(violations[0].rule.contains("require_uppercase"));
+    }
+
+    #[test]
+    fn test_validation_allowed_prefix_pattern() {
+        let policy = ValidationConfig {
+            allowed_prefix_pattern: Some("APP_|DB_".to_string()),
+            ..Default::default()
+        };
+
+        let violations = policy
+            .violations(&keys(&["APP_PORT", "DB_URL", "OTHER_APP_KEY"]))
+            .unwrap();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].key, "OTHER_APP_KEY");
+        assert!(violations[0].rule.contains("'APP_|DB_'"));
+    }
+
+    #[test]
+    fn test_validation_invalid_prefix_pattern() {
+        let policy = ValidationConfig {
+            allowed_prefix_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            policy.violations(&keys(&["A"])),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_validation_forbid_keys() {
+        let policy = ValidationConfig {
+            forbid_keys: keys(&["AWS_ROOT_SECRET"]),
+            ..Default::default()
+        };
+
+        let err = policy
+            .enforce(&keys(&["AWS_ROOT_SECRET", "PORT"]))
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::EnvFileFormatError(_)));
+        let message = err.to_string();
+        assert!(message.contains("AWS_ROOT_SECRET"));
+        assert!(message.contains("forbid_keys"));
+    }
+
+    #[test]
+    fn test_load_invalid_toml() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "env_file = [").unwrap();
+
+        assert!(matches!(
+            Config::load_from(&path),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_save_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        let config = with_profile(Some(".env.staging"));
+
+        config.save_to(&path).unwrap();
+
+        assert_eq!(Config::load_from(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_resolve_env_file_precedence() {
+        let mut config = with_profile(Some(".env.staging"));
+
+        assert_eq!(config.resolve_env_file(None, None).unwrap(), ".env");
+        assert_eq!(
+            config.resolve_env_file(None, Some("staging")).unwrap(),
+            ".env.staging"
+        );
+
+        config.env_file = Some(".env.local".to_string());
+        assert_eq!(
+            config.resolve_env_file(None, Some("staging")).unwrap(),
+            ".env.local"
+        );
+        assert_eq!(
+            config
+                .resolve_env_file(Some("custom.env"), Some("staging"))
+                .unwrap(),
+            "custom.env"
+        );
+    }
+
+    #[test]
+    fn test_resolve_env_file_reports_source() {
+        let config = with_profile(Some(".env.staging"));
+
+        assert_eq!(
+            config
+                .resolve_env_file_with_source(None, Some("staging"))
+                .unwrap(),
+            (
+                ".env.staging".to_string(),
+                ValueSource::Profile("staging".to_string())
+            )
+        );
+        assert_eq!(
+            config.resolve_env_file_with_source(None, None).unwrap().1,
+            ValueSource::Default
+        );
+    }
+
+    #[test]
+    fn test_resolve_env_file_unknown_profile() {
+        let config = Config::default();
+        assert!(config.resolve_env_file(None, Some("missing")).is_err());
+        assert!(config
+            .resolve_env_file(Some("custom.env"), Some("missing"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_expands_environment_variables() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(
+            &path,
+            "default_project = \"app-${ENVIRONMENT}\"\nenv_file = \"${ENVIRONMENT}.env\"\n\n[profiles.ci]\nenv_file = \"${CI_ENV_FILE:-.env.ci}\"\n",
+        )
+        .unwrap();
+        let vars = |name: &str| (name == "ENVIRONMENT").then(|| "staging".to_string());
+
+        let config = Config::load_from_with(&path, vars).unwrap();
+        assert_eq!(config.default_project.as_deref(), Some("app-staging"));
+        assert_eq!(config.env_file.as_deref(), Some("staging.env"));
+        assert_eq!(config.profiles["ci"].env_file.as_deref(), Some(".env.ci"));
+
+        fs::write(&path, "[profiles.ci]\nenv_file = \"$UNSET_VAR.env\"\n").unwrap();
+        match Config::load_from_with(&path, vars) {
+            Err(AppError::ConfigError(message)) => {
+                assert!(message.contains("profiles.ci.env_file"));
+                assert!(message.contains("UNSET_VAR is not set"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
     }
 }
@@ -0,0 +1,122 @@
+//! Detection of keys that a transform maps onto the same name
+//!
+//! Renaming keys, e.g. upper-casing them or adding a prefix, can turn two
+//! distinct keys into one, so that one value silently overwrites the other.
+//! Transforms check their output here before anything is written.
+
+use crate::{AppError, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Distinct source keys mapped onto the same target key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCollision {
+    pub target: String,
+    pub sources: Vec<String>,
+}
+
+impl fmt::Display for KeyCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <- {}", self.target, self.sources.join(", "))
+    }
+}
+
+/// Every target key reached from more than one distinct source, sorted by
+/// target
+///
+/// `mappings` pairs each source key, labelled however the caller wants it
+/// reported, with the key it is renamed to.
+pub fn find_collisions<I>(mappings: I) -> Vec<KeyCollision>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut by_target: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (source, target) in mappings {
+        let sources = by_target.entry(target).or_default();
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+
+    by_target
+        .into_iter()
+        .filter(|(_, sources)| sources.len() > 1)
+        .map(|(target, sources)| KeyCollision { target, sources })
+        .collect()
+}
+
+/// Fail with [`AppError::InvalidArguments`] listing every collision of
+/// `mappings`, if there are any
+pub fn ensure_no_collisions<I>(mappings: I, transform: &str) -> Result<()>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let collisions = find_collisions(mappings);
+    if collisions.is_empty() {
+        return Ok(());
+    }
+
+    let details: Vec<String> = collisions.iter().map(|c| c.to_string()).collect();
+    Err(AppError::InvalidArguments(format!(
+        "{} maps different keys to the same name: {}",
+        transform,
+        details.join("; ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mappings(keys: &[&str], transform: fn(&str) -> String) -> Vec<(String, String)> {
+        keys.iter()
+            .map(|key| (key.to_string(), transform(key)))
+            .collect()
+    }
+
+    #[test]
+    fn test_case_collisions() {
+        let normalize = |key: &str| key.to_uppercase().replace('-', "_");
+        let collisions =
+            find_collisions(mappings(&["my-key", "MY_KEY", "OTHER", "Other"], normalize));
+
+        assert_eq!(
+            collisions,
+            vec![
+                KeyCollision {
+                    target: "MY_KEY".to_string(),
+                    sources: vec!["my-key".to_string(), "MY_KEY".to_string()],
+                },
+                KeyCollision {
+                    target: "OTHER".to_string(),
+                    sources: vec!["OTHER".to_string(), "Other".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prefix_collisions() {
+        let strip = |key: &str| key.strip_prefix("APP_").unwrap_or(key).to_string();
+        let err = ensure_no_collisions(
+            mappings(&["APP_PORT", "PORT", "HOST"], strip),
+            "--strip-prefix",
+        )
+        .unwrap_err();
+
+        match err {
+            AppError::InvalidArguments(message) => {
+                assert!(message.starts_with("--strip-prefix"));
+                assert!(message.contains("PORT <- APP_PORT, PORT"));
+                assert!(!message.contains("HOST"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_distinct_targets_and_repeated_sources_are_fine() {
+        let identity = |key: &str| key.to_string();
+        assert!(ensure_no_collisions(mappings(&["A", "B", "A"], identity), "rename").is_ok());
+    }
+}
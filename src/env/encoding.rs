@@ -0,0 +1,98 @@
+//! Text encodings of .env files
+//!
+//! Files are UTF-8 by default. Legacy files, e.g. Windows-1252 ones written
+//! by older Windows tooling, are decoded to UTF-8 before parsing, so values
+//! always reach Bitwarden as UTF-8.
+
+use crate::AppError;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Encoding of a .env file, by WHATWG label such as `utf-8`, `windows-1252`
+/// or `latin1`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextEncoding(&'static encoding_rs::Encoding);
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        Self(encoding_rs::UTF_8)
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.name())
+    }
+}
+
+impl FromStr for TextEncoding {
+    type Err = String;
+
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        encoding_rs::Encoding::for_label(label.as_bytes())
+            .map(Self)
+            .ok_or_else(|| format!("unknown encoding '{}'", label))
+    }
+}
+
+impl TextEncoding {
+    pub fn is_utf8(&self) -> bool {
+        self.0 == encoding_rs::UTF_8
+    }
+
+    /// Decode the contents of `path` to a string
+    pub fn decode(&self, bytes: Vec<u8>, path: &Path) -> Result<String, AppError> {
+        if self.is_utf8() {
+            return String::from_utf8(bytes).map_err(|_| {
+                AppError::EnvFileFormatError(format!(
+                    "{}: file is not valid UTF-8 text (pass --encoding for other encodings)",
+                    path.display()
+                ))
+            });
+        }
+
+        self.0
+            .decode_without_bom_handling_and_without_replacement(&bytes)
+            .map(|text| text.into_owned())
+            .ok_or_else(|| {
+                AppError::EnvFileFormatError(format!(
+                    "{}: file is not valid {} text",
+                    path.display(),
+                    self
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        assert!(TextEncoding::default().is_utf8());
+        assert!("UTF-8".parse::<TextEncoding>().unwrap().is_utf8());
+        assert_eq!(
+            "latin1".parse::<TextEncoding>().unwrap(),
+            "windows-1252".parse::<TextEncoding>().unwrap()
+        );
+        assert!("klingon".parse::<TextEncoding>().is_err());
+    }
+
+    #[test]
+    fn test_decode_windows_1252() {
+        let encoding: TextEncoding = "windows-1252".parse().unwrap();
+        // "café €5" in Windows-1252
+        let bytes = vec![b'c', b'a', b'f', 0xE9, b' ', 0x80, b'5'];
+
+        assert_eq!(
+            encoding.decode(bytes.clone(), Path::new(".env")).unwrap(),
+            "café €5"
+        );
+        assert!(matches!(
+            TextEncoding::default().decode(bytes, Path::new(".env")),
+            Err(AppError::EnvFileFormatError(_))
+        ));
+    }
+}
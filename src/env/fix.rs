@@ -0,0 +1,288 @@
+//! Safe automatic corrections for .env files
+//!
+//! Used by `validate --fix`. Fixes never change a value as `read_env_file`
+//! sees it, except for dropping duplicate keys, which is opt-in.
+
+use super::parser::logical_lines;
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+/// UTF-8 byte order mark, which some Windows editors put at the start of a file
+const BOM: char = '\u{feff}';
+
+/// Line endings of a fixed file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// Whether `content` starts with a byte order mark
+pub fn has_bom(content: &str) -> bool {
+    content.starts_with(BOM)
+}
+
+/// Number of lines of `content` ending in `\r\n`
+pub fn crlf_lines(content: &str) -> usize {
+    content.matches("\r\n").count()
+}
+
+/// A single correction applied to a .env file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// 1-based line number in the original file
+    pub line: usize,
+    pub description: String,
+}
+
+/// Result of fixing .env content
+#[derive(Debug, Clone, Default)]
+pub struct FixReport {
+    /// The corrected content
+    pub content: String,
+    /// Corrections that were applied
+    pub fixes: Vec<Fix>,
+    /// Duplicate keys left in place because duplicate removal was not enabled
+    pub duplicates: Vec<String>,
+}
+
+impl FixReport {
+    /// Whether any correction was applied
+    pub fn changed(&self) -> bool {
+        !self.fixes.is_empty()
+    }
+}
+
+/// Corrects trailing whitespace, `export ` prefixes and a missing final newline
+///
+/// With `fix_duplicates`, earlier definitions of a repeated key are removed so
+/// only the last one (the value the parser already uses) remains.
+pub fn fix_env_content(content: &str, fix_duplicates: bool) -> FixReport {
+    fix_env_content_with(content, fix_duplicates, LineEnding::Lf)
+}
+
+/// Like [`fix_env_content`], also removing a byte order mark and writing
+/// every line ending as `line_ending`
+pub fn fix_env_content_with(
+    content: &str,
+    fix_duplicates: bool,
+    line_ending: LineEnding,
+) -> FixReport {
+    let mut report = FixReport::default();
+    let content = match content.strip_prefix(BOM) {
+        Some(rest) => {
+            report.fixes.push(Fix {
+                line: 1,
+                description: "removed byte order mark".to_string(),
+            });
+            rest
+        }
+        None => content,
+    };
+    if content.is_empty() {
+        return report;
+    }
+
+    let crlf = crlf_lines(content);
+    if crlf > 0 && line_ending == LineEnding::Lf {
+        let first = content.find("\r\n").unwrap_or_default();
+        report.fixes.push(Fix {
+            line: content[..first].matches('\n').count() + 1,
+            description: format!("converted {} CRLF line ending(s) to LF", crlf),
+        });
+    }
+    let normalized = content.replace("\r\n", "\n");
+    let content = normalized.as_str();
+
+    let has_final_newline = content.ends_with('\n');
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    let physical: Vec<String> = body.split('\n').map(str::to_string).collect();
+    let last_line = physical.len();
+
+    let mut lines = Vec::new();
+    for line in logical_lines(physical) {
+        let mut text = line.text.trim_end().to_string();
+        if text.len() != line.text.len() {
+            report.fixes.push(Fix {
+                line: line.number,
+                description: "removed trailing whitespace".to_string(),
+            });
+        }
+
+        if let Some(rest) = strip_export(&text) {
+            text = rest.to_string();
+            report.fixes.push(Fix {
+                line: line.number,
+                description: "removed `export` prefix".to_string(),
+            });
+        }
+
+        lines.push((line.number, text));
+    }
+
+    // The parser keeps the last definition of a key, so that one stays
+    let mut last_definition: HashMap<String, usize> = HashMap::new();
+    for (index, (_, text)) in lines.iter().enumerate() {
+        if let Some(key) = line_key(text) {
+            last_definition.insert(key.to_string(), index);
+        }
+    }
+
+    let mut kept = Vec::new();
+    for (index, (number, text)) in lines.iter().enumerate() {
+        if let Some(key) = line_key(text) {
+            let last = last_definition[key];
+            if last != index {
+                if fix_duplicates {
+                    report.fixes.push(Fix {
+                        line: *number,
+                        description: format!(
+                            "removed duplicate {} (kept line {})",
+                            key, lines[last].0
+                        ),
+                    });
+                    continue;
+                }
+                if !report.duplicates.iter().any(|d| d == key) {
+                    report.duplicates.push(key.to_string());
+                }
+            }
+        }
+        kept.push(text.as_str());
+    }
+
+    if !has_final_newline {
+        report.fixes.push(Fix {
+            line: last_line,
+            description: "added final newline".to_string(),
+        });
+    }
+
+    report.content = kept.join("\n") + "\n";
+    if line_ending == LineEnding::Crlf {
+        let lf = report.content.matches('\n').count().saturating_sub(crlf);
+        if lf > 0 {
+            report.fixes.push(Fix {
+                line: 1,
+                description: format!("converted {} LF line ending(s) to CRLF", lf),
+            });
+        }
+        report.content = report.content.replace('\n', "\r\n");
+    }
+    report
+}
+
+/// Strips a leading `export ` from a KEY=VALUE line
+fn strip_export(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix("export")?;
+    if !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    line_key(rest).map(|_| rest)
+}
+
+/// The key of a KEY=VALUE line, or None for blank lines and comments
+fn line_key(text: &str) -> Option<&str> {
+    let trimmed = text.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let key = text[..text.find('=')?].trim();
+    if key.is_empty() {
+        None
+    } else {
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_clean_content_is_unchanged() {
+        let content = "# comment\nKEY=value\n\nOTHER=1\n";
+        let report = fix_env_content(content, false);
+
+        assert!(!report.changed());
+        assert_eq!(report.content, content);
+    }
+
+    #[test]
+    fn test_fix_trailing_whitespace_and_final_newline() {
+        let report = fix_env_content("KEY=value  \nOTHER=1", false);
+
+        assert_eq!(report.content, "KEY=value\nOTHER=1\n");
+        assert_eq!(report.fixes.len(), 2);
+        assert_eq!(report.fixes[0].line, 1);
+        assert_eq!(report.fixes[1].description, "added final newline");
+    }
+
+    #[test]
+    fn test_fix_export_prefix() {
+        let report = fix_env_content("export KEY=value\nexported=1\n", false);
+
+        assert_eq!(report.content, "KEY=value\nexported=1\n");
+        assert_eq!(report.fixes.len(), 1);
+    }
+
+    #[test]
+    fn test_fix_duplicates_only_when_enabled() {
+        let content = "KEY=first\nOTHER=1\nKEY=second\n";
+
+        let report = fix_env_content(content, false);
+        assert_eq!(report.content, content);
+        assert_eq!(report.duplicates, vec!["KEY".to_string()]);
+
+        let report = fix_env_content(content, true);
+        assert_eq!(report.content, "OTHER=1\nKEY=second\n");
+        assert_eq!(
+            report.fixes[0].description,
+            "removed duplicate KEY (kept line 3)"
+        );
+    }
+
+    #[test]
+    fn test_fix_preserves_multiline_values() {
+        let content = "CERT=\"line one  \nline two\"\n";
+        let report = fix_env_content(content, false);
+
+        assert!(!report.changed());
+        assert_eq!(report.content, content);
+    }
+
+    #[test]
+    fn test_fix_bom_and_crlf() {
+        let report = fix_env_content("\u{feff}KEY=value\r\nOTHER=1\r\n", false);
+
+        assert_eq!(report.content, "KEY=value\nOTHER=1\n");
+        let descriptions: Vec<_> = report
+            .fixes
+            .iter()
+            .map(|f| f.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "removed byte order mark",
+                "converted 2 CRLF line ending(s) to LF"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_to_crlf() {
+        let report = fix_env_content_with("KEY=value\r\nOTHER=1\n", false, LineEnding::Crlf);
+        assert_eq!(report.content, "KEY=value\r\nOTHER=1\r\n");
+        assert_eq!(report.fixes.len(), 1);
+        assert_eq!(
+            report.fixes[0].description,
+            "converted 1 LF line ending(s) to CRLF"
+        );
+
+        let clean = "KEY=value\r\n";
+        assert!(!fix_env_content_with(clean, false, LineEnding::Crlf).changed());
+    }
+}
@@ -0,0 +1,98 @@
+//! `.env.d/` directories of fragments
+//!
+//! A directory given where a .env file is expected stands for every `*.env`
+//! file directly inside it, read in lexical order of file name. A key set in
+//! more than one fragment takes its value from the last, as with nginx or
+//! systemd drop-in directories, so `90-local.env` overrides `10-base.env`.
+//! Other files and subdirectories are ignored.
+
+use crate::env::encoding::TextEncoding;
+use crate::env::parser::{self, Whitespace};
+use crate::{AppError, Result};
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File a pull into a fragment directory writes, named to sort after the
+/// usual numbered fragments so pulled values take precedence
+pub const PULL_FRAGMENT: &str = "99-bwenv.env";
+
+/// Whether `path` is a directory of fragments rather than a .env file
+pub fn is_fragment_dir(path: &str) -> bool {
+    Path::new(path).is_dir()
+}
+
+/// The `*.env` files directly inside `dir`, in lexical order
+pub fn fragment_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        AppError::EnvFileReadError(format!("Failed to read {}: {}", dir.display(), e))
+    })?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "env"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Merge the fragments of `dir`, later files winning
+pub fn read_fragments(
+    dir: &Path,
+    whitespace: Whitespace,
+    encoding: TextEncoding,
+) -> Result<HashMap<String, String>> {
+    let mut merged = HashMap::new();
+    for file in fragment_files(dir)? {
+        let source = file.display().to_string();
+        let vars = parser::read_env_file_as(&file, whitespace, encoding)
+            .map_err(|e| parser::read_error(&source, e))?;
+        for (key, value) in vars {
+            if merged.insert(key.clone(), value).is_some() {
+                debug!("{} overrides {} from an earlier fragment", source, key);
+            }
+        }
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_fragments_merge_in_lexical_order() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("20-local.env"), "PORT=9000\nDEBUG=true\n").unwrap();
+        fs::write(dir.path().join("10-base.env"), "PORT=8080\nHOST=db\n").unwrap();
+        fs::write(dir.path().join("90-override.env"), "DEBUG=false\n").unwrap();
+        // Neither a fragment nor read
+        fs::write(dir.path().join("README.md"), "PORT=1\n").unwrap();
+        fs::create_dir(dir.path().join("nested.env")).unwrap();
+
+        let names: Vec<_> = fragment_files(dir.path())
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["10-base.env", "20-local.env", "90-override.env"]
+        );
+
+        let vars = read_fragments(dir.path(), Whitespace::Trim, TextEncoding::default()).unwrap();
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars["PORT"], "9000");
+        assert_eq!(vars["HOST"], "db");
+        assert_eq!(vars["DEBUG"], "false");
+    }
+
+    #[test]
+    fn test_empty_fragment_dir() {
+        let dir = tempdir().unwrap();
+        let vars = read_fragments(dir.path(), Whitespace::Trim, TextEncoding::default()).unwrap();
+        assert!(vars.is_empty());
+    }
+}
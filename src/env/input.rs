@@ -0,0 +1,202 @@
+//! Input formats other than dotenv
+//!
+//! `push` accepts a flat JSON or YAML object of keys to scalar values as well
+//! as a .env file. Nested objects and arrays have no .env equivalent and are
+//! rejected.
+
+use crate::env::encoding::TextEncoding;
+use crate::env::parser::{self, Whitespace};
+use crate::{AppError, Result};
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Format of a file of secrets to push
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum InputFormat {
+    /// Detect from the extension, then the content
+    #[default]
+    Auto,
+    /// `KEY=VALUE` lines
+    Env,
+    /// A flat JSON object
+    Json,
+    /// A flat YAML mapping
+    Yaml,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InputFormat::Auto => "auto",
+            InputFormat::Env => "env",
+            InputFormat::Json => "json",
+            InputFormat::Yaml => "yaml",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl InputFormat {
+    /// The concrete format of `path`; `Auto` looks at the extension, then
+    /// at the first line that isn't blank or a comment
+    pub fn resolve(self, path: &Path) -> Result<InputFormat> {
+        if self != InputFormat::Auto {
+            return Ok(self);
+        }
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => return Ok(InputFormat::Json),
+            Some("yaml" | "yml") => return Ok(InputFormat::Yaml),
+            _ => {}
+        }
+
+        let content = fs::read(path).map_err(|e| {
+            AppError::EnvFileReadError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        // Only the structure matters, so any encoding of it will do
+        Ok(sniff(&String::from_utf8_lossy(&content)))
+    }
+}
+
+/// Guess the format of `content`; anything unrecognised is treated as .env
+fn sniff(content: &str) -> InputFormat {
+    let first = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+    match first {
+        Some(line) if line.starts_with('{') => InputFormat::Json,
+        Some(line) if line == "---" || (line.contains(':') && !line.contains('=')) => {
+            InputFormat::Yaml
+        }
+        _ => InputFormat::Env,
+    }
+}
+
+/// Read a flat JSON or YAML object of secrets
+///
+/// Strings are taken verbatim, numbers and booleans as written and null as
+/// an empty value.
+pub fn read_structured(
+    path: &Path,
+    format: InputFormat,
+    encoding: TextEncoding,
+) -> Result<HashMap<String, String>> {
+    let source = path.display().to_string();
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::EnvFileReadError(format!("Failed to read {}: {}", source, e)))?;
+    let content = encoding.decode(bytes, path)?;
+
+    let document: Value = match format {
+        InputFormat::Json => serde_json::from_str(&content).map_err(|e| {
+            AppError::EnvFileFormatError(format!("{}: invalid JSON: {}", source, e))
+        })?,
+        InputFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| {
+            AppError::EnvFileFormatError(format!("{}: invalid YAML: {}", source, e))
+        })?,
+        InputFormat::Auto | InputFormat::Env => {
+            return parser::read_env_file_as(path, Whitespace::Trim, encoding)
+                .map_err(|e| parser::read_error(&source, e))
+        }
+    };
+
+    let Value::Object(object) = document else {
+        return Err(AppError::EnvFileFormatError(format!(
+            "{}: expected an object of KEY: value pairs",
+            source
+        )));
+    };
+
+    object
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Null => String::new(),
+                Value::Array(_) | Value::Object(_) => {
+                    return Err(AppError::EnvFileFormatError(format!(
+                        "{}: {} has a nested value; only strings, numbers and booleans can be pushed",
+                        source, key
+                    )))
+                }
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn detect(name: &str, content: &str) -> InputFormat {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        InputFormat::Auto.resolve(&path).unwrap()
+    }
+
+    #[test]
+    fn test_detects_format_from_extension() {
+        assert_eq!(detect("secrets.json", "A=1"), InputFormat::Json);
+        assert_eq!(detect("secrets.yaml", ""), InputFormat::Yaml);
+        assert_eq!(detect("secrets.yml", ""), InputFormat::Yaml);
+        assert_eq!(detect(".env", "A=1\n"), InputFormat::Env);
+    }
+
+    #[test]
+    fn test_detects_format_from_content() {
+        assert_eq!(detect("secrets", "{\"A\": \"1\"}"), InputFormat::Json);
+        assert_eq!(detect("secrets", "# shared\nA: 1\n"), InputFormat::Yaml);
+        assert_eq!(detect("secrets", "---\nA: 1\n"), InputFormat::Yaml);
+        assert_eq!(detect("secrets", "URL=http://x\n"), InputFormat::Env);
+    }
+
+    #[test]
+    fn test_explicit_format_is_not_sniffed() {
+        let path = Path::new("does-not-exist.json");
+        assert_eq!(InputFormat::Env.resolve(path).unwrap(), InputFormat::Env);
+    }
+
+    #[test]
+    fn test_read_structured_json_and_yaml() {
+        let dir = tempdir().unwrap();
+        let json = dir.path().join("secrets.json");
+        fs::write(&json, r#"{"API_KEY": "abc", "PORT": 8080, "DEBUG": true}"#).unwrap();
+        let yaml = dir.path().join("secrets.yaml");
+        fs::write(&yaml, "API_KEY: abc\nPORT: 8080\nDEBUG: true\n").unwrap();
+
+        for (path, format) in [(json, InputFormat::Json), (yaml, InputFormat::Yaml)] {
+            let vars = read_structured(&path, format, TextEncoding::default()).unwrap();
+            assert_eq!(vars.len(), 3);
+            assert_eq!(vars["API_KEY"], "abc");
+            assert_eq!(vars["PORT"], "8080");
+            assert_eq!(vars["DEBUG"], "true");
+        }
+    }
+
+    #[test]
+    fn test_read_structured_rejects_nesting() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        fs::write(&path, r#"{"DB": {"HOST": "localhost"}}"#).unwrap();
+
+        match read_structured(&path, InputFormat::Json, TextEncoding::default()) {
+            Err(AppError::EnvFileFormatError(message)) => assert!(message.contains("DB")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        fs::write(&path, r#"["A", "B"]"#).unwrap();
+        assert!(matches!(
+            read_structured(&path, InputFormat::Json, TextEncoding::default()),
+            Err(AppError::EnvFileFormatError(_))
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+//! Advisory locking of .env files
+//!
+//! Concurrent bwenv processes writing the same file take an exclusive lock
+//! on a `<file>.lock` sidecar, so their writes serialize instead of
+//! interleaving. The sidecar is left in place; removing it while another
+//! process waits on it would let a third process lock a different file.
+
+use crate::{AppError, Result};
+use fs2::FileExt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Seconds to wait for a lock when not configured
+pub const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 30;
+
+/// Delay between attempts to take a held lock
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A held lock, released when dropped
+#[derive(Debug)]
+pub struct EnvFileLock {
+    file: File,
+}
+
+impl Drop for EnvFileLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Lock `path` exclusively, for writing it
+///
+/// Fails with [`AppError::Conflict`] if another process still holds the lock
+/// after `timeout`.
+pub fn lock_exclusive(path: &str, timeout: Duration) -> Result<EnvFileLock> {
+    acquire(path, timeout, FileExt::try_lock_exclusive)
+}
+
+/// Lock `path` shared, for reading it while no one writes it
+pub fn lock_shared(path: &str, timeout: Duration) -> Result<EnvFileLock> {
+    acquire(path, timeout, FileExt::try_lock_shared)
+}
+
+/// Lock `path` exclusively unless locking is disabled with `None`
+pub fn maybe_lock_exclusive(path: &str, timeout: Option<Duration>) -> Result<Option<EnvFileLock>> {
+    timeout.map(|t| lock_exclusive(path, t)).transpose()
+}
+
+/// Lock `path` shared unless locking is disabled with `None`
+pub fn maybe_lock_shared(path: &str, timeout: Option<Duration>) -> Result<Option<EnvFileLock>> {
+    timeout.map(|t| lock_shared(path, t)).transpose()
+}
+
+fn acquire(
+    path: &str,
+    timeout: Duration,
+    try_lock: fn(&File) -> io::Result<()>,
+) -> Result<EnvFileLock> {
+    let lock_path = format!("{}.lock", path);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| {
+            AppError::EnvFileWriteError(format!("Failed to open lock {}: {}", lock_path, e))
+        })?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match try_lock(&file) {
+            Ok(()) => return Ok(EnvFileLock { file }),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                if Instant::now() >= deadline {
+                    return Err(AppError::Conflict(format!(
+                        "{} is locked by another bwenv process (waited {}s). Retry, raise --lock-timeout or pass --no-lock",
+                        path,
+                        timeout.as_secs()
+                    )));
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => {
+                return Err(AppError::EnvFileWriteError(format!(
+                    "Failed to lock {}: {}",
+                    lock_path, e
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    /// Write `content` in two halves with a pause, as a slow writer would
+    fn slow_write(path: &str, content: &str) {
+        let mut file = File::create(path).unwrap();
+        let (first, second) = content.split_at(content.len() / 2);
+        file.write_all(first.as_bytes()).unwrap();
+        file.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        file.write_all(second.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_writers_serialize() {
+        let dir = tempdir().unwrap();
+        let path = Arc::new(dir.path().join(".env").to_str().unwrap().to_string());
+        let contents: [&'static str; 2] = ["A=1\nB=2\nC=3\n", "X=first\nY=second\nZ=third\n"];
+
+        let writers: Vec<_> = contents
+            .into_iter()
+            .map(|content| {
+                let path = Arc::clone(&path);
+                std::thread::spawn(move || {
+                    let _lock = lock_exclusive(&path, Duration::from_secs(5)).unwrap();
+                    slow_write(&path, content);
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let written = fs::read_to_string(path.as_str()).unwrap();
+        assert!(
+            contents.contains(&written.as_str()),
+            "torn write: {:?}",
+            written
+        );
+    }
+
+    #[test]
+    fn test_lock_times_out_while_held() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        let path = path.to_str().unwrap();
+
+        let held = lock_exclusive(path, Duration::from_secs(1)).unwrap();
+        let result = lock_shared(path, Duration::from_millis(100));
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        drop(held);
+        assert!(lock_shared(path, Duration::from_millis(100)).is_ok());
+    }
+}
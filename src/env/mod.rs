@@ -2,7 +2,17 @@
 //!
 //! Re-exports the preserved env_file parser with updated API.
 
+pub mod collisions;
+pub mod encoding;
+pub mod fix;
+pub mod fragments;
+pub mod input;
+pub mod lock;
 pub mod parser;
+pub mod paths;
+pub mod permissions;
+pub mod sections;
+pub mod template;
 
 // Re-export main functions
 pub use parser::{read_env_file, validate_env_file, write_env_file};
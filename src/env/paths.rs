@@ -0,0 +1,149 @@
+//! Shell-style expansion of .env file paths
+//!
+//! A path given in `.bwenv.toml`, or quoted on the command line, reaches
+//! bwenv without the shell having expanded it. A leading `~` becomes the home
+//! directory and `$VAR`, `${VAR}` or `${VAR:-default}` the variable's value,
+//! so that `--output ~/project/.env` doesn't create a directory named `~`.
+
+use crate::{AppError, Result};
+use std::path::{Path, PathBuf};
+
+/// Expand a leading `~` and any environment variables in `path`
+pub fn expand_path(path: &str) -> Result<String> {
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    expand_path_with(path, home.as_deref(), |name| std::env::var(name).ok())
+}
+
+fn expand_path_with(
+    path: &str,
+    home: Option<&Path>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let expanded = expand_vars(path, &var)
+        .map_err(|e| AppError::InvalidArguments(format!("Cannot expand path {}: {}", path, e)))?;
+
+    // `~user` is left alone; only the current user's home is known
+    let rest = match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => rest,
+        _ => return Ok(expanded),
+    };
+    let home = home.ok_or_else(|| {
+        AppError::InvalidArguments(format!(
+            "Cannot expand ~ in {}: the home directory is unknown",
+            path
+        ))
+    })?;
+    let mut resolved = PathBuf::from(home);
+    let rest = rest.trim_start_matches(['/', '\\']);
+    if !rest.is_empty() {
+        resolved.push(rest);
+    }
+    Ok(resolved.display().to_string())
+}
+
+/// Replace each `$NAME`, `${NAME}` and `${NAME:-default}` in `text`; a `$`
+/// not followed by a name is kept
+///
+/// The default is used when the variable is unset or empty. Otherwise an
+/// unset variable is an error, described for the caller to place.
+pub(crate) fn expand_vars(
+    text: &str,
+    var: &impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => return Err("unclosed ${".to_string()),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        let (name, default) = match name.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (name, None),
+        };
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let value = match (var(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Some(value), _) => value,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Err(format!("environment variable {} is not set", name)),
+        };
+        out.push_str(&value);
+        rest = &after[consumed..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/dev".to_string()),
+            "STAGE" => Some("staging".to_string()),
+            _ => None,
+        }
+    }
+
+    fn expand(path: &str) -> Result<String> {
+        expand_path_with(path, Some(Path::new("/home/dev")), vars)
+    }
+
+    #[test]
+    fn test_expands_tilde_under_home() {
+        assert_eq!(expand("~/foo.env").unwrap(), "/home/dev/foo.env");
+        assert_eq!(expand("~").unwrap(), "/home/dev");
+        assert_eq!(expand("~other/foo.env").unwrap(), "~other/foo.env");
+        assert_eq!(expand("./~/foo.env").unwrap(), "./~/foo.env");
+
+        let home = directories::BaseDirs::new()
+            .unwrap()
+            .home_dir()
+            .to_path_buf();
+        assert_eq!(
+            PathBuf::from(expand_path("~/foo.env").unwrap()),
+            home.join("foo.env")
+        );
+    }
+
+    #[test]
+    fn test_expands_environment_variables() {
+        assert_eq!(expand("$HOME/.env").unwrap(), "/home/dev/.env");
+        assert_eq!(
+            expand("config/.env.${STAGE}").unwrap(),
+            "config/.env.staging"
+        );
+        assert_eq!(expand("cost$/.env").unwrap(), "cost$/.env");
+        assert_eq!(expand(".env").unwrap(), ".env");
+
+        assert!(matches!(
+            expand("$MISSING/.env"),
+            Err(AppError::InvalidArguments(m)) if m.contains("MISSING")
+        ));
+        assert!(expand("${STAGE/.env").is_err());
+    }
+
+    #[test]
+    fn test_expands_defaults() {
+        assert_eq!(expand("${MISSING:-.env.local}").unwrap(), ".env.local");
+        assert_eq!(expand("${STAGE:-dev}.env").unwrap(), "staging.env");
+        assert_eq!(expand("${MISSING:-}.env").unwrap(), ".env");
+    }
+}
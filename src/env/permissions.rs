@@ -0,0 +1,171 @@
+//! Permission checks for .env files
+//!
+//! Run before a command reads or writes a .env file, so that a permission
+//! problem is reported with its path instead of surfacing as a generic IO
+//! error halfway through.
+
+use crate::{AppError, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+/// Fail with [`AppError::EnvFilePermissionError`] if `path` can't be opened
+/// for reading
+pub fn check_readable(path: &Path) -> Result<()> {
+    match File::open(path) {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Err(denied("read", path)),
+        _ => Ok(()),
+    }
+}
+
+/// Fail with [`AppError::EnvFilePermissionError`] if `path` can't be written:
+/// the file itself when it exists, otherwise its directory
+pub fn check_writable(path: &Path) -> Result<()> {
+    if path.exists() {
+        return match OpenOptions::new().append(true).open(path) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Err(denied("write", path)),
+            _ => Ok(()),
+        };
+    }
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    // Metadata can't answer this for another owner's directory, so try it
+    let probe = dir.join(format!(".bwenv-write-check-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            Err(AppError::EnvFilePermissionError(format!(
+                "cannot create {}: directory {} is not writable",
+                path.display(),
+                dir.display()
+            )))
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+fn denied(access: &str, path: &Path) -> AppError {
+    AppError::EnvFilePermissionError(format!(
+        "cannot {} {}{}",
+        access,
+        path.display(),
+        owner_hint(path)
+    ))
+}
+
+#[cfg(unix)]
+fn owner_hint(path: &Path) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    match fs::metadata(path) {
+        Ok(meta) => format!(
+            " (owned by uid {}, mode {:o})",
+            meta.uid(),
+            meta.mode() & 0o777
+        ),
+        Err(_) => String::new(),
+    }
+}
+
+#[cfg(not(unix))]
+fn owner_hint(_path: &Path) -> String {
+    String::new()
+}
+
+/// Permission bits of `path` if its group or other users can read it
+#[cfg(unix)]
+pub fn exposed_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & 0o044 != 0).then_some(mode)
+}
+
+/// Warn on stderr if `path` is readable by its group or other users
+#[cfg(unix)]
+pub fn warn_if_exposed(path: &Path) {
+    if let Some(mode) = exposed_mode(path) {
+        eprintln!(
+            "{}{} is readable by other users (mode {:o}); consider chmod 600",
+            crate::output::icon(crate::output::Symbol::Warn),
+            path.display(),
+            mode
+        );
+    }
+}
+
+#[cfg(not(unix))]
+pub fn warn_if_exposed(_path: &Path) {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    /// Whether the current user bypasses permission bits, e.g. root
+    fn permissions_ignored(path: &Path) -> bool {
+        File::open(path).is_ok()
+    }
+
+    #[test]
+    fn test_check_readable_reports_unreadable_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "A=1\n").unwrap();
+        assert!(check_readable(&path).is_ok());
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o000)).unwrap();
+        if permissions_ignored(&path) {
+            return;
+        }
+
+        match check_readable(&path) {
+            Err(AppError::EnvFilePermissionError(message)) => {
+                assert!(message.contains(&path.display().to_string()));
+                assert!(message.contains("mode 0"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_writable_reports_read_only_directory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        assert!(check_writable(&path).is_ok());
+        assert!(!path.exists());
+
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o500)).unwrap();
+        let probe = dir.path().join("probe");
+        if File::create(&probe).is_ok() {
+            return;
+        }
+
+        let result = check_writable(&path);
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700)).unwrap();
+        assert!(matches!(
+            result,
+            Err(AppError::EnvFilePermissionError(message)) if message.contains("not writable")
+        ));
+    }
+
+    #[test]
+    fn test_exposed_mode() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "A=1\n").unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(exposed_mode(&path), Some(0o644));
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(exposed_mode(&path), None);
+    }
+}
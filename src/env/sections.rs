@@ -0,0 +1,45 @@
+//! Selecting the keys of one commented section of a .env file
+//!
+//! A section starts at a header comment such as `# === Database ===`, with
+//! at least three `=` on each side of the name, and runs until the next
+//! header. See [`parser::section_header`].
+
+use crate::env::encoding::TextEncoding;
+use crate::env::fragments;
+use crate::env::parser;
+use crate::{AppError, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Keys of `input` in the section named `section`, compared
+/// case-insensitively
+///
+/// A `.env.d/` directory contributes the section from each fragment. Fails
+/// if no key is in the section, which also catches a misspelled name.
+pub fn section_keys(input: &str, section: &str, encoding: TextEncoding) -> Result<HashSet<String>> {
+    let files = if fragments::is_fragment_dir(input) {
+        fragments::fragment_files(Path::new(input))?
+    } else {
+        vec![PathBuf::from(input)]
+    };
+
+    let mut keys = HashSet::new();
+    for file in &files {
+        let sections = parser::read_env_sections_as(file, encoding)
+            .map_err(|e| parser::read_error(&file.display().to_string(), e))?;
+        keys.extend(
+            sections
+                .into_iter()
+                .filter(|(_, name)| name.eq_ignore_ascii_case(section))
+                .map(|(key, _)| key),
+        );
+    }
+
+    if keys.is_empty() {
+        return Err(AppError::InvalidArguments(format!(
+            "No keys found in section '{}' of {}. Sections start with a '# === {} ===' comment",
+            section, input, section
+        )));
+    }
+    Ok(keys)
+}
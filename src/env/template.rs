@@ -0,0 +1,181 @@
+//! Template rendering - `${KEY}` or `{{KEY}}` placeholder substitution
+//!
+//! Renders arbitrary config files from a map of secrets.
+
+use crate::{AppError, Result};
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+/// Placeholder syntax of a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum PlaceholderStyle {
+    /// `${KEY}`; `$${KEY}` is written as a literal `${KEY}`
+    #[default]
+    Shell,
+    /// `{{KEY}}` or `{{ KEY }}`; `\{{KEY}}` is written as a literal `{{KEY}}`
+    Mustache,
+}
+
+impl PlaceholderStyle {
+    /// Matches a placeholder, capturing its key, or an escaped one,
+    /// capturing the literal text to write in its place
+    fn regex(self) -> &'static Regex {
+        static SHELL: OnceLock<Regex> = OnceLock::new();
+        static MUSTACHE: OnceLock<Regex> = OnceLock::new();
+        match self {
+            PlaceholderStyle::Shell => SHELL.get_or_init(|| {
+                Regex::new(r"\$(?P<escaped>\$\{[A-Za-z_][A-Za-z0-9_]*\})|\$\{(?P<key>[A-Za-z_][A-Za-z0-9_]*)\}")
+                    .unwrap()
+            }),
+            PlaceholderStyle::Mustache => MUSTACHE.get_or_init(|| {
+                Regex::new(
+                    r"\\(?P<escaped>\{\{\s*[A-Za-z_][A-Za-z0-9_]*\s*\}\})|\{\{\s*(?P<key>[A-Za-z_][A-Za-z0-9_]*)\s*\}\}",
+                )
+                .unwrap()
+            }),
+        }
+    }
+}
+
+/// Substitutes `${KEY}` placeholders in a template with values from `vars`
+///
+/// Unresolved placeholders are an error unless `allow_missing` is set, in
+/// which case they are left in the output untouched.
+pub fn render_template(
+    template: &str,
+    vars: &HashMap<String, String>,
+    allow_missing: bool,
+) -> Result<String> {
+    render_template_with(template, vars, PlaceholderStyle::Shell, allow_missing)
+}
+
+/// Like [`render_template`], with placeholders written in `style`
+///
+/// Escaped placeholders are written literally, without their escape, and
+/// never count as unresolved.
+pub fn render_template_with(
+    template: &str,
+    vars: &HashMap<String, String>,
+    style: PlaceholderStyle,
+    allow_missing: bool,
+) -> Result<String> {
+    let mut missing = BTreeSet::new();
+
+    let rendered = style.regex().replace_all(template, |caps: &Captures| {
+        if let Some(escaped) = caps.name("escaped") {
+            return escaped.as_str().to_string();
+        }
+        let key = &caps["key"];
+        match vars.get(key) {
+            Some(value) => value.clone(),
+            None => {
+                missing.insert(key.to_string());
+                caps[0].to_string()
+            }
+        }
+    });
+
+    if !missing.is_empty() && !allow_missing {
+        return Err(AppError::EnvVarError(format!(
+            "Unresolved template placeholders: {}",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    Ok(rendered.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<String, String> {
+        let mut vars = HashMap::new();
+        vars.insert("API_KEY".to_string(), "secret123".to_string());
+        vars.insert("DB_HOST".to_string(), "localhost".to_string());
+        vars
+    }
+
+    #[test]
+    fn test_render_template_substitutes_placeholders() {
+        let template = "token = \"${API_KEY}\"\nhost = \"${DB_HOST}:5432\"\n";
+        let rendered = render_template(template, &vars(), false).unwrap();
+        assert_eq!(
+            rendered,
+            "token = \"secret123\"\nhost = \"localhost:5432\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_template_missing_placeholder_errors() {
+        let result = render_template("${API_KEY} ${MISSING} ${OTHER}", &vars(), false);
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("MISSING, OTHER"));
+    }
+
+    #[test]
+    fn test_render_template_allow_missing_keeps_placeholder() {
+        let rendered = render_template("${API_KEY} ${MISSING}", &vars(), true).unwrap();
+        assert_eq!(rendered, "secret123 ${MISSING}");
+    }
+
+    #[test]
+    fn test_render_template_ignores_bare_dollar() {
+        let rendered = render_template("cost: $5 and $API_KEY", &vars(), false).unwrap();
+        assert_eq!(rendered, "cost: $5 and $API_KEY");
+    }
+
+    #[test]
+    fn test_render_each_placeholder_style() {
+        let expected = "token = \"secret123\"\nhost = \"localhost:5432\"\n";
+        let templates = [
+            (
+                PlaceholderStyle::Shell,
+                "token = \"${API_KEY}\"\nhost = \"${DB_HOST}:5432\"\n",
+            ),
+            (
+                PlaceholderStyle::Mustache,
+                "token = \"{{API_KEY}}\"\nhost = \"{{ DB_HOST }}:5432\"\n",
+            ),
+        ];
+
+        for (style, template) in templates {
+            let rendered = render_template_with(template, &vars(), style, false).unwrap();
+            assert_eq!(rendered, expected, "{:?}", style);
+        }
+    }
+
+    #[test]
+    fn test_escaped_placeholders_pass_through() {
+        let rendered = render_template_with(
+            "$${API_KEY} ${API_KEY}",
+            &vars(),
+            PlaceholderStyle::Shell,
+            false,
+        )
+        .unwrap();
+        assert_eq!(rendered, "${API_KEY} secret123");
+
+        let rendered = render_template_with(
+            "\\{{MISSING}} {{API_KEY}}",
+            &vars(),
+            PlaceholderStyle::Mustache,
+            false,
+        )
+        .unwrap();
+        assert_eq!(rendered, "{{MISSING}} secret123");
+    }
+
+    #[test]
+    fn test_styles_ignore_each_other() {
+        let rendered =
+            render_template_with("{{API_KEY}}", &vars(), PlaceholderStyle::Shell, false).unwrap();
+        assert_eq!(rendered, "{{API_KEY}}");
+
+        let result =
+            render_template_with("{{ MISSING }}", &vars(), PlaceholderStyle::Mustache, false);
+        assert!(result.unwrap_err().to_string().contains("MISSING"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Re-exports preserved error types with updates for Secrets Manager.
 
+pub mod report;
 pub mod types;
 
 // Re-export main error type
+pub use report::ErrorFormat;
 pub use types::AppError;
@@ -0,0 +1,62 @@
+//! Reporting of fatal errors on stderr
+
+use super::AppError;
+use clap::ValueEnum;
+use serde_json::json;
+
+/// How a fatal error is written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human-readable message
+    #[default]
+    Text,
+    /// A single JSON object with the variant, message and exit code
+    Json,
+}
+
+/// Render `err` in the requested format, without a trailing newline
+pub fn render(err: &AppError, format: ErrorFormat) -> String {
+    match format {
+        ErrorFormat::Text => format!("Error: {:?}", err),
+        ErrorFormat::Json => json!({
+            "error": err.kind(),
+            "message": err.to_string(),
+            "exit_code": err.exit_code(),
+        })
+        .to_string(),
+    }
+}
+
+/// Print `err` to stderr in the requested format
+pub fn report(err: &AppError, format: ErrorFormat) {
+    eprintln!("{}", render(err, format));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_json() {
+        let err = AppError::ItemNotFound("Project: app".to_string());
+
+        let value: serde_json::Value =
+            serde_json::from_str(&render(&err, ErrorFormat::Json)).unwrap();
+
+        assert_eq!(value["error"], "ItemNotFound");
+        assert_eq!(
+            value["message"],
+            "Item not found in Bitwarden: Project: app"
+        );
+        assert_eq!(value["exit_code"], 1);
+    }
+
+    #[test]
+    fn test_render_text_matches_default_main_output() {
+        let err = AppError::Conflict("drift".to_string());
+        assert_eq!(
+            render(&err, ErrorFormat::Text),
+            "Error: Conflict(\"drift\")"
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub enum AppError {
     #[error("Bitwarden authentication failed. Make sure the desktop app is running and you're logged in.")]
     BitwardenAuthFailed,
 
+    #[error("Access token command failed: {0}")]
+    TokenCommandFailed(String),
+
     #[error("Bitwarden session error: {0}")]
     BitwardenSessionError(String),
 
@@ -17,6 +20,9 @@ pub enum AppError {
     #[error("Failed to write .env file: {0}")]
     EnvFileWriteError(String),
 
+    #[error("Permission denied for .env file: {0}")]
+    EnvFilePermissionError(String),
+
     #[error("Invalid .env file format: {0}")]
     EnvFileFormatError(String),
 
@@ -35,10 +41,60 @@ pub enum AppError {
     #[error("Invalid command arguments: {0}")]
     InvalidArguments(String),
 
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Interrupted: {0}")]
+    Interrupted(String),
+
+    #[error("Read-only mode: {0}")]
+    ReadOnly(String),
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
+impl AppError {
+    /// Stable name of the error variant, for machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::BitwardenNotFound => "BitwardenNotFound",
+            AppError::BitwardenAuthFailed => "BitwardenAuthFailed",
+            AppError::TokenCommandFailed(_) => "TokenCommandFailed",
+            AppError::BitwardenSessionError(_) => "BitwardenSessionError",
+            AppError::EnvFileReadError(_) => "EnvFileReadError",
+            AppError::EnvFileWriteError(_) => "EnvFileWriteError",
+            AppError::EnvFilePermissionError(_) => "EnvFilePermissionError",
+            AppError::EnvFileFormatError(_) => "EnvFileFormatError",
+            AppError::EnvVarError(_) => "EnvVarError",
+            AppError::ItemNotFound(_) => "ItemNotFound",
+            AppError::FolderNotFound(_) => "FolderNotFound",
+            AppError::CommandExecutionError(_) => "CommandExecutionError",
+            AppError::InvalidArguments(_) => "InvalidArguments",
+            AppError::ConfigError(_) => "ConfigError",
+            AppError::Conflict(_) => "Conflict",
+            AppError::Interrupted(_) => "Interrupted",
+            AppError::ReadOnly(_) => "ReadOnly",
+            AppError::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Process exit code for this error
+    ///
+    /// Usage and configuration mistakes exit with 2, like clap's own usage
+    /// errors, and interrupts with 130; everything else exits with 1.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AppError::InvalidArguments(_) | AppError::ConfigError(_) => 2,
+            AppError::Interrupted(_) => 130,
+            _ => 1,
+        }
+    }
+}
+
 impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
         AppError::Unknown(err.to_string())
@@ -70,12 +126,16 @@ mod tests {
             AppError::BitwardenSessionError("session expired".to_string()),
             AppError::EnvFileReadError("file not found".to_string()),
             AppError::EnvFileWriteError("permission denied".to_string()),
+            AppError::EnvFilePermissionError("cannot read .env".to_string()),
             AppError::EnvFileFormatError("invalid format".to_string()),
             AppError::EnvVarError("missing variable".to_string()),
             AppError::ItemNotFound("test-item".to_string()),
             AppError::FolderNotFound("test-folder".to_string()),
             AppError::CommandExecutionError("command failed".to_string()),
             AppError::InvalidArguments("invalid args".to_string()),
+            AppError::ConfigError("bad config".to_string()),
+            AppError::Conflict("remote changed".to_string()),
+            AppError::ReadOnly("push is not allowed".to_string()),
             AppError::Unknown("unknown error".to_string()),
         ];
 
@@ -193,6 +253,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_token_command_failed_error() {
+        let message = "'pass show bw' exited with exit status: 1";
+        let error = AppError::TokenCommandFailed(message.to_string());
+        assert_eq!(
+            error.to_string(),
+            format!("Access token command failed: {}", message)
+        );
+        assert_eq!(error.kind(), "TokenCommandFailed");
+    }
+
+    #[test]
+    fn test_config_error() {
+        let message = ".bwenv.toml: invalid type";
+        let error = AppError::ConfigError(message.to_string());
+        assert_eq!(
+            error.to_string(),
+            format!("Configuration error: {}", message)
+        );
+    }
+
+    #[test]
+    fn test_conflict_error() {
+        let message = "Remote secrets changed since the plan was made: API_KEY";
+        let error = AppError::Conflict(message.to_string());
+        assert_eq!(error.to_string(), format!("Conflict: {}", message));
+    }
+
     #[test]
     fn test_unknown_error() {
         let message = "Something unexpected happened";
@@ -233,6 +321,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_kind_and_exit_code() {
+        let error = AppError::ItemNotFound("Project: app".to_string());
+        assert_eq!(error.kind(), "ItemNotFound");
+        assert_eq!(error.exit_code(), 1);
+
+        let error = AppError::InvalidArguments("bad flag".to_string());
+        assert_eq!(error.kind(), "InvalidArguments");
+        assert_eq!(error.exit_code(), 2);
+
+        let error = AppError::Interrupted("3 secrets were not pushed".to_string());
+        assert_eq!(error.exit_code(), 130);
+    }
+
     #[test]
     fn test_error_debug_trait() {
         let error = AppError::BitwardenNotFound;
@@ -0,0 +1,140 @@
+//! Git module - Derive project names from the current repository
+//!
+//! Teams usually name their Bitwarden project after the git repository, so
+//! the `origin` remote is used to pick a default project.
+
+use crate::{AppError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Derive a project name from the current git repository
+///
+/// Uses the repository name of the `origin` remote, then the name of the
+/// repository's top-level directory, then the current directory name.
+pub fn project_name() -> Result<String> {
+    if let Some(name) = git_output(&["remote", "get-url", "origin"])
+        .as_deref()
+        .and_then(repo_name_from_url)
+    {
+        return Ok(name);
+    }
+
+    if let Some(name) = git_output(&["rev-parse", "--show-toplevel"])
+        .as_deref()
+        .and_then(dir_name)
+    {
+        return Ok(name);
+    }
+
+    std::env::current_dir()
+        .ok()
+        .as_deref()
+        .and_then(|dir| dir.to_str())
+        .and_then(dir_name)
+        .ok_or_else(|| {
+            AppError::InvalidArguments(
+                "Could not derive a project name; pass --project".to_string(),
+            )
+        })
+}
+
+/// Extract the repository name from a git remote URL
+///
+/// Handles `https://host/org/repo.git`, `git@host:org/repo.git` and
+/// `ssh://git@host/org/repo` forms.
+pub fn repo_name_from_url(url: &str) -> Option<String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Whether git ignores `path`, or `None` outside a git repository
+///
+/// Tracked files are checked against the ignore rules too.
+pub fn is_ignored(path: &str) -> Option<bool> {
+    let status = Command::new("git")
+        .args(["check-ignore", "-q", "--no-index", "--", path])
+        .status()
+        .ok()?;
+    // 0: ignored, 1: not ignored, anything else: not a repository or error
+    match status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+/// Files tracked by git matching any of the pathspecs
+pub fn tracked_files(pathspecs: &[&str]) -> Vec<String> {
+    let mut args = vec!["ls-files", "--"];
+    args.extend_from_slice(pathspecs);
+    git_output(&args)
+        .map(|out| out.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Run a git command and return its trimmed stdout on success
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let trimmed = stdout.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn dir_name(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_name_from_https_url() {
+        assert_eq!(
+            repo_name_from_url("https://github.com/acme/my-app.git"),
+            Some("my-app".to_string())
+        );
+        assert_eq!(
+            repo_name_from_url("https://github.com/acme/my-app/"),
+            Some("my-app".to_string())
+        );
+    }
+
+    #[test]
+    fn test_repo_name_from_ssh_url() {
+        assert_eq!(
+            repo_name_from_url("git@github.com:acme/my-app.git"),
+            Some("my-app".to_string())
+        );
+        assert_eq!(
+            repo_name_from_url("ssh://git@github.com/acme/my-app"),
+            Some("my-app".to_string())
+        );
+        assert_eq!(
+            repo_name_from_url("git@host:my-app.git"),
+            Some("my-app".to_string())
+        );
+    }
+
+    #[test]
+    fn test_repo_name_from_empty_url() {
+        assert_eq!(repo_name_from_url(""), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Interrupt module - Graceful Ctrl-C handling for mutating operations
+//!
+//! Read-only commands keep the default behavior and exit immediately. Commands
+//! that write to Bitwarden install a handler so an interrupted push stops
+//! issuing new calls, lets in-flight ones finish and reports what was done.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Exit code after an interrupt, following the 128 + SIGINT convention
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Shared flag set when the user asks to stop
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt(Arc<AtomicBool>);
+
+impl Interrupt {
+    /// A flag that is only set by [`Interrupt::trigger`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A flag set by Ctrl-C; a second Ctrl-C exits immediately
+    ///
+    /// Must be called from within the tokio runtime.
+    pub fn on_ctrl_c() -> Self {
+        let interrupt = Self::new();
+        let flag = interrupt.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if flag.0.swap(true, Ordering::SeqCst) {
+                    std::process::exit(INTERRUPTED_EXIT_CODE);
+                }
+                eprintln!(
+                    "\nInterrupted: finishing requests in flight (press Ctrl-C again to quit now)"
+                );
+            }
+        });
+        interrupt
+    }
+
+    /// Request a stop
+    pub fn trigger(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether a stop was requested
+    pub fn is_triggered(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_is_shared_between_clones() {
+        let interrupt = Interrupt::new();
+        let clone = interrupt.clone();
+        assert!(!clone.is_triggered());
+
+        interrupt.trigger();
+
+        assert!(clone.is_triggered());
+    }
+}
@@ -3,13 +3,18 @@
 //! A developer-friendly CLI for managing .env files using Bitwarden Secrets Manager.
 //! Built with the official Bitwarden Rust SDK for native performance and security.
 
+pub mod audit;
 pub mod bitwarden;
+pub mod cache;
 pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod env;
 pub mod error;
+pub mod git;
+pub mod interrupt;
 pub mod logging;
+pub mod output;
 pub mod sync;
 
 // Re-export commonly used types
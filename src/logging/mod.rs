@@ -8,6 +8,10 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+mod redact;
+
+pub use redact::{redact, REDACTED};
+
 /// Log verbosity levels following GNU/Linux conventions
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Verbosity {
@@ -75,7 +79,9 @@ pub fn get_log_file_path() -> PathBuf {
 }
 
 /// Initialize logging with the specified verbosity level
-pub fn initialize(verbosity: Verbosity, quiet: bool) -> Result<(), InitError> {
+///
+/// With `redact_logs`, every line is passed through [`redact`] first.
+pub fn initialize(verbosity: Verbosity, quiet: bool, redact_logs: bool) -> Result<(), InitError> {
     // Override verbosity if quiet is specified
     let level_filter = if quiet {
         LevelFilter::Error
@@ -92,7 +98,13 @@ pub fn initialize(verbosity: Verbosity, quiet: bool) -> Result<(), InitError> {
 
     // Configure logging
     let mut dispatch = Dispatch::new()
-        .format(|out, message, record| {
+        .format(move |out, message, record| {
+            let message = message.to_string();
+            let message = if redact_logs {
+                redact(&message)
+            } else {
+                message.as_str().into()
+            };
             out.finish(format_args!(
                 "{} [{}] [{}] {}",
                 Local::now().format("%Y-%m-%d %H:%M:%S"),
@@ -0,0 +1,99 @@
+//! Scrubbing of secrets from log lines
+//!
+//! The log file is always written at debug level, so a value logged by
+//! mistake, e.g. through `{:?}` on a map of secrets, would persist on disk.
+//! Every line is passed through [`redact`] before it is written.
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Written in place of a scrubbed value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// `KEY=value` assignments, keeping the key
+fn assignment_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"\b([A-Za-z_][A-Za-z0-9_]*)=("[^"]*"|'[^']*'|[^\s,;)}\]]+)"#).unwrap()
+    })
+}
+
+/// `"KEY": "value"` entries of debug-printed maps, keeping the key
+fn map_entry_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"("[^"\\]*")\s*:\s*"(?:[^"\\]|\\.)*""#).unwrap())
+}
+
+/// Well-known credential formats, wherever they appear
+fn secret_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            // Bitwarden access tokens: version.id.secret:key
+            r"\b\d+\.[0-9a-fA-F-]{36}\.[A-Za-z0-9+/=:]+",
+            r"|\b(?:sk|pk|rk)_(?:live|test)_[A-Za-z0-9]+",
+            r"|\bgh[pousr]_[A-Za-z0-9]{20,}",
+            r"|\bAKIA[0-9A-Z]{16}\b",
+            r"|\bxox[baprs]-[A-Za-z0-9-]+",
+            // JSON web tokens
+            r"|\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+            r"|-----BEGIN [A-Z ]*PRIVATE KEY-----",
+        ))
+        .unwrap()
+    })
+}
+
+/// `line` with assignment values, map values and known secret formats
+/// replaced by [`REDACTED`]
+pub fn redact(line: &str) -> Cow<'_, str> {
+    let line = secret_re().replace_all(line, REDACTED);
+    let line = match map_entry_re().replace_all(&line, format!("$1: \"{}\"", REDACTED)) {
+        Cow::Borrowed(_) => line,
+        Cow::Owned(redacted) => Cow::Owned(redacted),
+    };
+    match assignment_re().replace_all(&line, format!("$1={}", REDACTED)) {
+        Cow::Borrowed(_) => line,
+        Cow::Owned(redacted) => Cow::Owned(redacted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_crafted_message() {
+        let message = concat!(
+            r#"pushing {"API_KEY": "sk_live_abc123", "PORT": "8080"} "#,
+            "with DATABASE_URL=postgres://u:p@db/app token ",
+            "0.48b4774c-68ca-4539-a3d7-ac00018b4377.secretpart:key ",
+            "and ghp_abcdefghijklmnopqrstuvwxyz",
+        );
+
+        let redacted = redact(message);
+
+        for secret in [
+            "sk_live_abc123",
+            "8080",
+            "postgres://",
+            "secretpart",
+            "ghp_abcdefghijklmnopqrstuvwxyz",
+        ] {
+            assert!(
+                !redacted.contains(secret),
+                "{} leaked: {}",
+                secret,
+                redacted
+            );
+        }
+        assert!(redacted.contains(r#""API_KEY": "[REDACTED]""#));
+        assert!(redacted.contains("DATABASE_URL=[REDACTED]"));
+    }
+
+    #[test]
+    fn test_redact_leaves_plain_lines_alone() {
+        let line = "Mapped 12 secrets of project proj_1 in 3.2ms";
+        assert!(matches!(redact(line), Cow::Borrowed(_)));
+    }
+}
@@ -2,14 +2,25 @@
 //!
 //! Command-line interface for Bitwarden Secrets Manager .env management.
 
-use bwenv::cli;
-use bwenv::Result;
+use bwenv::cli::{self, Cli};
+use bwenv::error;
+use clap::Parser;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     // Initialize logging (will be called from CLI run when implemented)
     // bwenv::logging::initialize()?;
 
+    let cli = Cli::parse();
+    let error_format = cli.error_format;
+
     // Run CLI
-    cli::run().await
+    match cli::run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error::report::report(&e, error_format);
+            ExitCode::from(e.exit_code())
+        }
+    }
 }
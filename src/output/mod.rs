@@ -0,0 +1,343 @@
+//! Output module - Terminal decoration control and output formats
+//!
+//! Decides whether emoji and ANSI colors are written to stdout, based on the
+//! `--color` option, the `NO_COLOR` convention and whether stdout is a TTY.
+//! Without decorations, or on a dumb or non-UTF-8 terminal, status symbols
+//! fall back to ASCII tags such as `[OK]` and `[WARN]`.
+//! Also defines the `--format` values shared by all commands and serializes
+//! structured output for them.
+
+use crate::{AppError, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt;
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DECORATIONS: AtomicBool = AtomicBool::new(true);
+static EMOJI: AtomicBool = AtomicBool::new(true);
+
+/// When to decorate output with colors and emoji
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Decorate only when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    /// Always decorate
+    Always,
+    /// Never decorate
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolve the choice against the current environment
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => no_color_unset() && std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+/// Apply a color choice for the rest of the process
+pub fn init(choice: ColorChoice) {
+    let enabled = choice.enabled();
+    DECORATIONS.store(enabled, Ordering::Relaxed);
+    colored::control::set_override(enabled);
+
+    let emoji = match choice {
+        ColorChoice::Auto => enabled && unicode_terminal(),
+        _ => enabled,
+    };
+    EMOJI.store(emoji, Ordering::Relaxed);
+}
+
+/// Whether emoji and colors should be written
+pub fn decorations_enabled() -> bool {
+    DECORATIONS.load(Ordering::Relaxed)
+}
+
+/// A status symbol prefixed to a line of output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    /// Everything is as it should be
+    Ok,
+    /// A check or step passed
+    Pass,
+    /// A check failed
+    Fail,
+    /// A check was not run
+    Skip,
+    Warn,
+    /// A problem that needs action now, e.g. secrets committed to git
+    Alert,
+    Search,
+    Project,
+    File,
+    /// Keys only in Bitwarden
+    Download,
+    /// Keys only in the local file
+    Upload,
+    /// Keys on both sides with different values
+    Changed,
+    /// A suggested next command
+    Hint,
+}
+
+impl Symbol {
+    /// The emoji or Unicode form, with the spacing that follows it
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Symbol::Ok => "✅ ",
+            Symbol::Pass => "✓ ",
+            Symbol::Fail => "✗ ",
+            Symbol::Skip => "- ",
+            Symbol::Warn => "⚠️  ",
+            Symbol::Alert => "🚨 ",
+            Symbol::Search => "🔍 ",
+            Symbol::Project => "📦 ",
+            Symbol::File => "📄 ",
+            Symbol::Download => "📥 ",
+            Symbol::Upload => "📤 ",
+            Symbol::Changed => "🔄 ",
+            Symbol::Hint => "→ ",
+        }
+    }
+
+    /// The ASCII fallback; purely decorative symbols have none
+    pub fn ascii(self) -> &'static str {
+        match self {
+            Symbol::Ok | Symbol::Pass => "[OK] ",
+            Symbol::Fail => "[FAIL] ",
+            Symbol::Skip => "[SKIP] ",
+            Symbol::Warn => "[WARN] ",
+            Symbol::Alert => "[ALERT] ",
+            Symbol::Search | Symbol::Project | Symbol::File => "",
+            Symbol::Download => "[PULL] ",
+            Symbol::Upload => "[PUSH] ",
+            Symbol::Changed => "[DIFF] ",
+            Symbol::Hint => "-> ",
+        }
+    }
+}
+
+/// Returns the symbol as emoji when the terminal can show them, otherwise
+/// as ASCII
+pub fn icon(symbol: Symbol) -> &'static str {
+    if EMOJI.load(Ordering::Relaxed) {
+        symbol.emoji()
+    } else {
+        symbol.ascii()
+    }
+}
+
+/// Output format selected with `--format`, with the same meaning in every
+/// command that accepts it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable report
+    Text,
+    /// `KEY=VALUE` lines
+    Env,
+    /// Pretty-printed JSON
+    Json,
+    /// YAML
+    Yaml,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Env => "env",
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl OutputFormat {
+    /// Reject a format the command cannot produce
+    pub fn ensure_supported(self, command: &str, supported: &[OutputFormat]) -> Result<()> {
+        if supported.contains(&self) {
+            return Ok(());
+        }
+        let names: Vec<String> = supported.iter().map(|f| f.to_string()).collect();
+        Err(AppError::InvalidArguments(format!(
+            "{} does not support --format {} (supported: {})",
+            command,
+            self,
+            names.join(", ")
+        )))
+    }
+
+    /// Whether this format is serialized data rather than a rendered report
+    pub fn is_structured(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Yaml)
+    }
+}
+
+/// Serialize `value` in a structured format
+pub fn serialize<T: Serialize>(value: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => serde_yaml::to_string(value)
+            .map(|yaml| yaml.trim_end().to_string())
+            .map_err(|e| AppError::Unknown(format!("YAML error: {}", e))),
+        OutputFormat::Text | OutputFormat::Env => Err(AppError::InvalidArguments(format!(
+            "--format {} is not a structured format",
+            format
+        ))),
+    }
+}
+
+/// Ask a yes/no question on stderr and read the answer from stdin
+///
+/// Anything but `y` or `yes` counts as no.
+pub fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// A change described instead of made, printed the same way by every
+/// command's `--dry-run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRun {
+    /// Project name and ID
+    pub project: String,
+    pub key: String,
+    /// What the command would do, e.g. "delete the secret"
+    pub action: String,
+}
+
+impl DryRun {
+    pub fn new(project_name: &str, project_id: &str, key: &str, action: impl Into<String>) -> Self {
+        Self {
+            project: format!("{} ({})", project_name, project_id),
+            key: key.to_string(),
+            action: action.into(),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            "Dry run: nothing was changed".to_string(),
+            format!("  Project: {}", self.project),
+            format!("  Key:     {}", self.key),
+            format!("  Action:  {}", self.action),
+        ]
+    }
+
+    pub fn print(&self) {
+        for line in self.lines() {
+            println!("{}", line);
+        }
+    }
+}
+
+/// `NO_COLOR` disables color when set to any non-empty value
+fn no_color_unset() -> bool {
+    std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// Whether the terminal is likely to render emoji
+fn unicode_terminal() -> bool {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().find_map(var);
+    supports_unicode(var("TERM").as_deref(), locale.as_deref())
+}
+
+/// Not a dumb terminal, and a UTF-8 locale if one is set at all
+fn supports_unicode(term: Option<&str>, locale: Option<&str>) -> bool {
+    if term == Some("dumb") {
+        return false;
+    }
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice_explicit() {
+        assert!(ColorChoice::Always.enabled());
+        assert!(!ColorChoice::Never.enabled());
+    }
+
+    #[test]
+    fn test_serialize_structured_formats() {
+        let value = std::collections::BTreeMap::from([("API_KEY", "abc")]);
+
+        assert_eq!(
+            serialize(&value, OutputFormat::Json).unwrap(),
+            "{\n  \"API_KEY\": \"abc\"\n}"
+        );
+        assert_eq!(
+            serialize(&value, OutputFormat::Yaml).unwrap(),
+            "API_KEY: abc"
+        );
+        assert!(serialize(&value, OutputFormat::Env).is_err());
+    }
+
+    #[test]
+    fn test_ensure_supported_names_alternatives() {
+        let supported = [OutputFormat::Text, OutputFormat::Json];
+        assert!(OutputFormat::Json
+            .ensure_supported("list", &supported)
+            .is_ok());
+
+        let err = OutputFormat::Env
+            .ensure_supported("list", &supported)
+            .unwrap_err();
+        assert!(err.to_string().contains("supported: text, json"));
+    }
+
+    #[test]
+    fn test_icon_follows_decorations() {
+        init(ColorChoice::Never);
+        assert_eq!(icon(Symbol::Ok), "[OK] ");
+        assert_eq!(icon(Symbol::Warn), "[WARN] ");
+        init(ColorChoice::Always);
+        assert_eq!(icon(Symbol::Ok), "✅ ");
+    }
+
+    #[test]
+    fn test_supports_unicode() {
+        assert!(supports_unicode(
+            Some("xterm-256color"),
+            Some("en_US.UTF-8")
+        ));
+        assert!(supports_unicode(None, Some("C.utf8")));
+        assert!(supports_unicode(Some("xterm"), None));
+        assert!(!supports_unicode(Some("dumb"), Some("en_US.UTF-8")));
+        assert!(!supports_unicode(Some("xterm"), Some("C")));
+        assert!(!supports_unicode(Some("xterm"), Some("en_US.ISO-8859-1")));
+    }
+
+    #[test]
+    fn test_dry_run_lines() {
+        let dry_run = DryRun::new("App", "proj_1", "API_TOKEN", "delete the secret");
+        assert_eq!(
+            dry_run.lines(),
+            vec![
+                "Dry run: nothing was changed",
+                "  Project: App (proj_1)",
+                "  Key:     API_TOKEN",
+                "  Action:  delete the secret",
+            ]
+        );
+    }
+}
@@ -124,6 +124,7 @@ impl TestProject {
             id: format!("proj_{}", uuid::Uuid::new_v4()),
             name: name.to_string(),
             organization_id: "org_test".to_string(),
+            access: None,
         };
 
         let provider = MockProvider::new();
@@ -76,11 +76,10 @@ impl TestContext {
             // List all secrets in project
             let secrets = self.provider.list_secrets(project_id).await?;
 
-            // Delete each secret
-            for secret in secrets {
-                if let Err(e) = self.provider.delete_secret(&secret.id).await {
-                    eprintln!("Warning: Failed to delete secret {}: {}", secret.id, e);
-                }
+            // Delete all secrets in one batch
+            let ids: Vec<&str> = secrets.iter().map(|s| s.id.as_str()).collect();
+            if let Err(e) = self.provider.delete_secrets(&ids).await {
+                eprintln!("Warning: Failed to delete test secrets: {}", e);
             }
 
             println!("Cleaned up {} test secrets from project", secrets.len());
@@ -32,7 +32,7 @@ async fn test_push_creates_new_secrets() {
         .await
         .unwrap();
 
-    assert_eq!(results.len(), 3);
+    assert_eq!(results.created.len(), 3);
 
     // Verify secrets were created
     let secrets = provider.list_secrets(&project.project.id).await.unwrap();
@@ -123,7 +123,7 @@ async fn test_push_empty_file() {
         .await
         .unwrap();
 
-    assert_eq!(results.len(), 0);
+    assert_eq!(results.total(), 0);
 
     let secrets = provider.list_secrets(&project.project.id).await.unwrap();
     assert_eq!(secrets.len(), 0);
@@ -172,7 +172,7 @@ async fn test_push_large_number_of_secrets() {
         .await
         .unwrap();
 
-    assert_eq!(results.len(), 100);
+    assert_eq!(results.created.len(), 100);
 
     let secrets = provider.list_secrets(&project.project.id).await.unwrap();
     assert_eq!(secrets.len(), 100);
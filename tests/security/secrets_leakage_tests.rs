@@ -78,6 +78,7 @@ async fn test_secrets_not_in_list_debug() {
         id: "proj_1".to_string(),
         name: "Test Project".to_string(),
         organization_id: "org_1".to_string(),
+        access: None,
     };
 
     let secret = Secret {
@@ -107,6 +108,7 @@ async fn test_secrets_map_not_logged() {
         id: "proj_1".to_string(),
         name: "Test Project".to_string(),
         organization_id: "org_1".to_string(),
+        access: None,
     };
 
     let secret = Secret {
@@ -158,6 +160,7 @@ async fn test_create_secret_error_doesnt_leak_value() {
         id: "proj_1".to_string(),
         name: "Test Project".to_string(),
         organization_id: "org_1".to_string(),
+        access: None,
     };
 
     let provider = MockProvider::with_data(vec![project.clone()], vec![]);
@@ -263,6 +266,7 @@ async fn test_provider_doesnt_cache_secrets_insecurely() {
         id: "proj_1".to_string(),
         name: "Test Project".to_string(),
         organization_id: "org_1".to_string(),
+        access: None,
     };
 
     let secret = Secret {